@@ -9,10 +9,8 @@ define main(): void {
   io.puts("I have a number from 1 to 10")
   io.puts("Guess it. U have 5 chances")
 
-  let guess: int
-
   for count in range(0, 5) {
-    guess = io.gets("Your Guess: ").as_int()
+    let guess = io.gets("Your Guess: ").as_int()
 
     if guess > expected {
       io.puts(guess, " is too high")
@@ -3,28 +3,60 @@ use std::process::exit;
 use std::rc::Rc;
 
 use crate::context::Context;
+use crate::frontend::ast::Program;
 use crate::frontend::lexer::Lexer;
 use crate::frontend::parser::Parser;
 use crate::runtime::Runtime;
 use crate::stdlib::builtins::builtins;
+use crate::typeck;
 use crate::utils::read_file;
 
-pub fn run_from_file(path: &str) {
+fn parse_file(path: &str) -> (String, Program) {
     let input = match read_file(path) {
         Some(contents) => contents,
         None => exit(1),
     };
 
-    let input = input.chars().collect::<Vec<char>>();
-    let mut l = Lexer::new(&input);
+    let chars = input.chars().collect::<Vec<char>>();
+    let mut l = Lexer::new(&chars, path);
     let mut p = Parser::new(&mut l);
     let program = p.parse();
-    if p.has_error() {
-        println!("{}", p.get_error().unwrap());
+    if p.has_errors() {
+        for err in p.get_errors() {
+            println!("{}\n", err.report(&input));
+        }
         exit(1);
     };
+    (input, program)
+}
+
+fn check_program(input: &str, program: &Program) {
+    let errors = typeck::check(program);
+    if errors.is_empty() {
+        return;
+    }
+    for err in errors {
+        println!("{}\n", err.report(input));
+    }
+    exit(1);
+}
+
+pub fn run_from_file(path: &str, check: bool) {
+    let (input, program) = parse_file(path);
+    if check {
+        check_program(&input, &program);
+    }
 
     let env = Context::make_global(builtins());
     let mut evaltr = Runtime::new(Rc::new(RefCell::new(env)));
     evaltr.eval(program);
+    if let Some(report) = evaltr.error_handler.report(&input) {
+        eprintln!("{}", report);
+        exit(1);
+    }
+}
+
+pub fn check_file(path: &str) {
+    let (input, program) = parse_file(path);
+    check_program(&input, &program);
 }
@@ -1,6 +1,8 @@
+use crate::frontend::span::Span;
 use crate::runtime::object::{Object, ObjectInfo};
 use crate::runtime::type_system::Type;
 use crate::stdlib::modules::ModulesManager;
+use core::fmt;
 use std::{cell::RefCell, collections::HashMap, rc::Rc};
 
 #[derive(Debug, Clone, PartialEq)]
@@ -11,30 +13,50 @@ pub enum ContextType {
     IfElse,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Clone)]
 pub struct Context {
     type_: ContextType,
+    label: Option<String>,
     store: HashMap<String, ObjectInfo>,
     parent: Option<Rc<RefCell<Context>>>,
     pub modules: ModulesManager,
+    pub type_args: HashMap<String, Type>,
+    narrowed: HashMap<String, Type>,
+    declared_at: HashMap<String, Span>,
 }
 
 impl Context {
     pub fn make_from(parent: Rc<RefCell<Context>>, type_: ContextType) -> Self {
+        let type_args = parent.borrow().type_args.clone();
         Self {
             type_,
+            label: None,
             store: HashMap::new(),
             parent: Some(parent),
             modules: ModulesManager::setup(),
+            type_args,
+            narrowed: HashMap::new(),
+            declared_at: HashMap::new(),
+        }
+    }
+
+    pub fn make_loop(parent: Rc<RefCell<Context>>, label: Option<String>) -> Self {
+        Self {
+            label,
+            ..Self::make_from(parent, ContextType::Loop)
         }
     }
 
     pub fn make_global(store: HashMap<String, ObjectInfo>) -> Self {
         Self {
             type_: ContextType::Global,
+            label: None,
             store,
             parent: None,
             modules: ModulesManager::setup(),
+            type_args: HashMap::new(),
+            narrowed: HashMap::new(),
+            declared_at: HashMap::new(),
         }
     }
 
@@ -53,6 +75,28 @@ impl Context {
         true
     }
 
+    pub fn declare(
+        &mut self,
+        name: String,
+        type_: Type,
+        value: Object,
+        is_mut: bool,
+        span: &Span,
+    ) -> bool {
+        if !self.set(name.clone(), type_, value, is_mut) {
+            return false;
+        }
+        self.declared_at.insert(name, span.clone());
+        true
+    }
+
+    pub fn declaration(&self, name: &str) -> Option<Span> {
+        if self.store.contains_key(name) {
+            return self.declared_at.get(name).cloned();
+        }
+        self.parent.as_ref()?.borrow().declaration(name)
+    }
+
     pub fn mutate(&mut self, name: String, value: Object) -> bool {
         if self.store.contains_key(&name) {
             let old = self.store.get_mut(&name).unwrap();
@@ -77,9 +121,37 @@ impl Context {
             let obj = self.store.get(name).unwrap();
             return Some(obj.clone());
         }
-        match self.parent {
+        let resolved = match self.parent {
             Some(ref p) => p.borrow().resolve(name),
             None => None,
+        };
+        match self.narrowed.get(name) {
+            Some(type_) => resolved.map(|obj| ObjectInfo {
+                type_: type_.clone(),
+                ..obj
+            }),
+            None => resolved,
+        }
+    }
+
+    pub fn declared_type(&self, name: &str) -> Option<Type> {
+        match self.store.get(name) {
+            Some(obj) => Some(obj.type_.clone()),
+            None => self.parent.as_ref()?.borrow().declared_type(name),
+        }
+    }
+
+    pub fn narrow(&mut self, name: &str, type_: Type) {
+        self.narrowed.insert(name.to_string(), type_);
+    }
+
+    pub fn widen(&mut self, name: &str) {
+        if self.store.contains_key(name) {
+            return;
+        }
+        self.narrowed.remove(name);
+        if let Some(ref p) = self.parent {
+            p.borrow_mut().widen(name);
         }
     }
 
@@ -92,4 +164,29 @@ impl Context {
             None => false,
         }
     }
+
+    pub fn in_loop(&self, label: Option<&str>) -> bool {
+        if self.type_ == ContextType::Loop && (label.is_none() || self.label.as_deref() == label) {
+            return true;
+        }
+        if self.type_ == ContextType::Function {
+            return false;
+        }
+        match self.parent {
+            Some(ref p) => p.borrow().in_loop(label),
+            None => false,
+        }
+    }
+}
+
+// a function keeps the context it was defined in, which usually stores it
+// back, so only the names are printed to not loop forever
+impl fmt::Debug for Context {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Context")
+            .field("type_", &self.type_)
+            .field("label", &self.label)
+            .field("names", &self.store.keys().collect::<Vec<&String>>())
+            .finish_non_exhaustive()
+    }
 }
@@ -1,5 +1,7 @@
 use core::fmt;
 
+use super::span::Span;
+
 pub type Program = Vec<Stmt>;
 pub type BlockStmt = Vec<Stmt>;
 
@@ -10,7 +12,14 @@ pub enum ExprType {
     Float,
     String,
     Boolean,
+    Function(Option<(Vec<ExprType>, Box<ExprType>)>),
     Array(Box<ExprType>),
+    Map(Box<ExprType>, Box<ExprType>),
+    Tuple(Vec<ExprType>),
+    Named(String),
+    TypeParam(String),
+    Optional(Box<ExprType>),
+    Union(Vec<ExprType>),
 }
 
 #[derive(Debug, Clone)]
@@ -24,48 +33,179 @@ pub enum Literal {
     String(String),
     Boolean(bool),
     Array(Vec<Expr>),
+    Map(Vec<(Expr, Expr)>),
+    Tuple(Vec<Expr>),
+}
+
+#[derive(Debug, Clone)]
+pub enum Binding {
+    Name(String),
+    Tuple(Vec<Binding>),
+}
+
+#[derive(Debug, Clone)]
+pub struct Cursor {
+    pub binding: Binding,
+    pub type_: Option<ExprType>,
+    pub span: Span,
+}
+
+#[derive(Debug, Clone)]
+pub struct Expr {
+    pub kind: ExprKind,
+    pub span: Span,
+}
+
+impl Expr {
+    pub fn new(kind: ExprKind, span: Span) -> Self {
+        Self { kind, span }
+    }
 }
 
 #[derive(Debug, Clone)]
-pub enum Expr {
+pub enum ExprKind {
     Literal(Literal),
-    Call(Box<Expr>, Vec<Expr>),
+    Call(Box<Expr>, Vec<Arg>),
     Identifier(Identifier),
     Infix(Box<Expr>, Infix, Box<Expr>),
     Prefix(Prefix, Box<Expr>),
     Postfix(Box<Expr>, Postfix),
     Assign(Identifier, Box<Expr>),
     FieldAcc(Box<Expr>, Box<Expr>),
+    OptionalFieldAcc(Box<Expr>, Box<Expr>),
+    Index(Box<Expr>, Box<Expr>),
+    Slice(Box<Expr>, Option<Box<Expr>>, Option<Box<Expr>>),
+    IndexAssign(Box<Expr>, Box<Expr>, Box<Expr>),
+    Interpolated(Vec<Expr>),
+    Func(Vec<Param>, BlockStmt, Option<ExprType>),
+    StructLit(Identifier, Vec<(Identifier, Expr)>),
+    FieldAssign(Box<Expr>, Identifier, Box<Expr>),
+    Match(Box<Expr>, Vec<MatchArm>),
+    Spread(Box<Expr>),
+    Is(Box<Expr>, ExprType),
+}
+
+#[derive(Debug, Clone)]
+pub struct Param {
+    pub name: Identifier,
+    pub type_: ExprType,
+    pub default: Option<Expr>,
+    pub rest: bool,
+    pub is_mut: bool,
+    pub span: Span,
+}
+
+#[derive(Debug, Clone)]
+pub struct Arg {
+    pub name: Option<Identifier>,
+    pub value: Expr,
+}
+
+#[derive(Debug, Clone)]
+pub struct MatchArm {
+    pub pattern: Pattern,
+    pub guard: Option<Expr>,
+    pub body: ArmBody,
+    pub span: Span,
+}
+
+#[derive(Debug, Clone)]
+pub enum ArmBody {
+    Expr(Expr),
+    Block(BlockStmt),
 }
 
 #[derive(Debug, Clone)]
-pub enum Stmt {
+pub enum Pattern {
+    Wildcard,
+    Binding(String),
+    Literal(Literal),
+    Range(i64, i64),
+    Variant(Identifier, Identifier, Vec<Pattern>),
+}
+
+#[derive(Debug, Clone)]
+pub struct Stmt {
+    pub kind: StmtKind,
+    pub span: Span,
+}
+
+impl Stmt {
+    pub fn new(kind: StmtKind, span: Span) -> Self {
+        Self { kind, span }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum StmtKind {
     Expr(Expr),
-    Let(Identifier, Option<ExprType>, Option<Expr>),
-    Func(Identifier, Vec<(Identifier, ExprType)>, BlockStmt, ExprType),
+    Let(Identifier, Option<ExprType>, Option<Expr>, bool),
+    LetTuple(Vec<Binding>, Option<ExprType>, Expr, bool),
+    Const(Identifier, Option<ExprType>, Expr),
+    Func(Identifier, Vec<Param>, BlockStmt, Option<ExprType>),
     Return(Option<Expr>),
     If {
         condition: Expr,
         consequence: BlockStmt,
-        alternative: Option<BlockStmt>,
+        alternative: Option<Else>,
     },
     ForLoop {
-        cursor: String,
+        cursor: Cursor,
+        value_cursor: Option<Box<Cursor>>,
         iterable: Expr,
         block: BlockStmt,
+        label: Option<String>,
     },
+    While {
+        condition: Expr,
+        block: BlockStmt,
+        label: Option<String>,
+    },
+    Break(Option<String>),
+    Continue(Option<String>),
     Import(String),
+    Struct {
+        name: Identifier,
+        fields: Vec<(Identifier, ExprType)>,
+        methods: Vec<Method>,
+    },
+    Enum {
+        name: Identifier,
+        variants: Vec<(Identifier, Vec<ExprType>)>,
+    },
+    TypeAlias {
+        name: Identifier,
+        type_: ExprType,
+    },
+}
+
+#[derive(Debug, Clone)]
+pub struct Method {
+    pub name: Identifier,
+    pub params: Vec<Param>,
+    pub body: BlockStmt,
+    pub ret_type: Option<ExprType>,
+    pub span: Span,
+}
+
+#[derive(Debug, Clone)]
+pub enum Else {
+    Block(BlockStmt),
+    If(Box<Stmt>),
 }
 
 #[derive(PartialEq, PartialOrd, Debug, Clone)]
 pub enum Precedence {
     Lowest,
     Assign,     // foo = "bar"
+    Coalesce,   // a ?? b
+    Or,         // a || b
+    And,        // a && b
     Comparison, // x > 6
     Sum,        // +
     Product,    // *
-    Prefix,     // !true || -5
-    Postfix,    // 69++ || 10--
+    Prefix,     // !true, -5
+    Postfix,    // 69++, 10--
     FieldAcc,   // Math.PI
     Call,       // myFunction(x)
 }
@@ -83,6 +223,9 @@ pub enum Infix {
     LessOrEqual,
     GratherThan,
     GratherOrEqual,
+    And,
+    Or,
+    NullCoalescing,
 }
 
 #[derive(Debug, Clone)]
@@ -90,6 +233,8 @@ pub enum Prefix {
     Not,
     Plus,
     Minus,
+    Increment,
+    Decrement,
 }
 
 #[derive(Debug, Clone)]
@@ -113,6 +258,8 @@ impl fmt::Display for Prefix {
             Prefix::Not => write!(f, "!"),
             Prefix::Plus => write!(f, "+"),
             Prefix::Minus => write!(f, "-"),
+            Prefix::Increment => write!(f, "++"),
+            Prefix::Decrement => write!(f, "--"),
         }
     }
 }
@@ -131,6 +278,9 @@ impl fmt::Display for Infix {
             Infix::GratherThan => write!(f, ">"),
             Infix::GratherOrEqual => write!(f, ">="),
             Infix::Remainder => write!(f, "%"),
+            Infix::And => write!(f, "&&"),
+            Infix::Or => write!(f, "||"),
+            Infix::NullCoalescing => write!(f, "??"),
         }
     }
 }
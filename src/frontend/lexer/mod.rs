@@ -1,23 +1,36 @@
-use super::token::Token;
+use std::rc::Rc;
+
+use super::span::Span;
+use super::token::{StringPart, Token, TokenInfo, Trivia};
 
 const NULL_CHAR: char = '\0';
 
+#[derive(Debug)]
+pub struct LexerError {
+    pub msg: String,
+    pub span: Span,
+}
+
 pub struct Lexer<'a> {
     input: &'a [char],
+    file: Rc<str>,
     curr_char: char,
     pos: usize,
     read_pos: usize,
+    offset: usize,
     line: usize,
     colm: usize,
 }
 
 impl<'a> Lexer<'a> {
-    pub fn new(input: &'a [char]) -> Self {
+    pub fn new(input: &'a [char], file: &str) -> Self {
         let mut l = Lexer {
             input,
+            file: Rc::from(file),
             curr_char: NULL_CHAR,
             pos: 0,
             read_pos: 0,
+            offset: 0,
             line: 1,
             colm: 1,
         };
@@ -27,7 +40,34 @@ impl<'a> Lexer<'a> {
         l
     }
 
+    pub fn starting_at(input: &'a [char], span: &Span) -> Self {
+        let mut l = Lexer {
+            input,
+            file: Rc::clone(&span.file),
+            curr_char: NULL_CHAR,
+            pos: 0,
+            read_pos: 0,
+            offset: span.start,
+            line: span.line,
+            colm: span.colm,
+        };
+
+        l.read_char();
+
+        l
+    }
+
     fn read_char(&mut self) {
+        if self.read_pos > 0 && self.pos < self.input.len() {
+            self.offset += self.curr_char.len_utf8();
+            if self.curr_char == '\n' {
+                self.line += 1;
+                self.colm = 1;
+            } else {
+                self.colm += 1;
+            }
+        }
+
         if self.read_pos >= self.input.len() {
             self.pos = self.read_pos;
             self.curr_char = NULL_CHAR;
@@ -37,17 +77,92 @@ impl<'a> Lexer<'a> {
         self.curr_char = self.input[self.read_pos];
         self.pos = self.read_pos;
         self.read_pos += 1;
+    }
+
+    pub fn next_token(&mut self) -> Result<TokenInfo, LexerError> {
+        let trivia = self.skip_trivia()?;
+
+        let mark = self.mark();
+        let token = self.read_token();
+        let span = self.span_from(mark);
 
-        if self.curr_char == '\n' {
-            self.line += 1;
-            self.colm = 1;
-        } else {
-            self.colm += 1;
+        match token {
+            Ok(token) => Ok(TokenInfo {
+                token,
+                span,
+                trivia,
+            }),
+            Err(msg) => Err(LexerError { msg, span }),
         }
     }
 
-    pub fn next_token(&mut self) -> Result<Token, String> {
-        self.skip_whitespace();
+    fn mark(&self) -> (usize, usize, usize) {
+        (self.line, self.colm, self.offset)
+    }
+
+    fn span_from(&self, (line, colm, start): (usize, usize, usize)) -> Span {
+        Span {
+            file: Rc::clone(&self.file),
+            line,
+            colm,
+            start,
+            end: self.offset,
+        }
+    }
+
+    fn skip_trivia(&mut self) -> Result<Vec<Trivia>, LexerError> {
+        let mut trivia: Vec<Trivia> = vec![];
+        loop {
+            self.skip_whitespace();
+            if self.curr_char != '/' {
+                return Ok(trivia);
+            }
+
+            if self.next_char_is('/') {
+                let mark = self.mark();
+                let comment = self.chop_while(|x| x != '\n');
+                let is_doc = comment.starts_with("///") && !comment.starts_with("////");
+                if is_doc {
+                    let text = comment[3..].trim().to_string();
+                    trivia.push(Trivia::DocComment(text, self.span_from(mark)));
+                }
+            } else if self.next_char_is('*') {
+                self.skip_block_comment()?;
+            } else {
+                return Ok(trivia);
+            }
+        }
+    }
+
+    fn skip_block_comment(&mut self) -> Result<(), LexerError> {
+        let mark = self.mark();
+        let mut depth = 0;
+        loop {
+            if self.eof() {
+                let mut span = self.span_from(mark);
+                span.end = span.start + 2;
+                return Err(LexerError {
+                    msg: "Unterminated block comment".to_string(),
+                    span,
+                });
+            }
+
+            if self.curr_char == '/' && self.next_char_is('*') {
+                depth += 1;
+                self.read_char();
+            } else if self.curr_char == '*' && self.next_char_is('/') {
+                depth -= 1;
+                self.read_char();
+            }
+            self.read_char();
+
+            if depth == 0 {
+                return Ok(());
+            }
+        }
+    }
+
+    fn read_token(&mut self) -> Result<Token, String> {
         if self.eof() {
             return Ok(Token::Eof);
         }
@@ -60,15 +175,42 @@ impl<'a> Lexer<'a> {
             '[' => Some(Token::Lbracket),
             ']' => Some(Token::Rbracket),
             ',' => Some(Token::Comma),
+            '*' if self.next_char_is('=') => {
+                self.read_char();
+                Some(Token::AsteriskEqual)
+            }
+            '/' if self.next_char_is('=') => {
+                self.read_char();
+                Some(Token::SlashEqual)
+            }
+            '%' if self.next_char_is('=') => {
+                self.read_char();
+                Some(Token::PercentEqual)
+            }
             '*' => Some(Token::Asterisk),
             '/' => Some(Token::Slash),
             ':' => Some(Token::Colon),
             '%' => Some(Token::Percet),
+            '.' if self.next_char_is('.') && self.peek_char(2) == '.' => {
+                self.read_char();
+                self.read_char();
+                Some(Token::Ellipsis)
+            }
+            '.' if self.next_char_is('.') => {
+                self.read_char();
+                Some(Token::DoubleDot)
+            }
             '.' => Some(Token::Dot),
             '-' => {
                 if self.next_char_is('-') {
                     self.read_char();
                     Some(Token::DoubleMinus)
+                } else if self.next_char_is('=') {
+                    self.read_char();
+                    Some(Token::MinusEqual)
+                } else if self.next_char_is('>') {
+                    self.read_char();
+                    Some(Token::Arrow)
                 } else {
                     Some(Token::Minus)
                 }
@@ -77,10 +219,22 @@ impl<'a> Lexer<'a> {
                 if self.next_char_is('+') {
                     self.read_char();
                     Some(Token::DoublePlus)
+                } else if self.next_char_is('=') {
+                    self.read_char();
+                    Some(Token::PlusEqual)
                 } else {
                     Some(Token::Plus)
                 }
             }
+            '?' if self.next_char_is('?') => {
+                self.read_char();
+                Some(Token::DoubleQuestion)
+            }
+            '?' if self.next_char_is('.') => {
+                self.read_char();
+                Some(Token::QuestionDot)
+            }
+            '?' => Some(Token::Question),
             '!' => {
                 if self.next_char_is('=') {
                     self.read_char();
@@ -89,6 +243,15 @@ impl<'a> Lexer<'a> {
                     Some(Token::Bang)
                 }
             }
+            '&' if self.next_char_is('&') => {
+                self.read_char();
+                Some(Token::And)
+            }
+            '|' if self.next_char_is('|') => {
+                self.read_char();
+                Some(Token::Or)
+            }
+            '|' => Some(Token::Pipe),
             '>' => {
                 if self.next_char_is('=') {
                     self.read_char();
@@ -109,6 +272,9 @@ impl<'a> Lexer<'a> {
                 if self.next_char_is('=') {
                     self.read_char();
                     Some(Token::DoubleEqual)
+                } else if self.next_char_is('>') {
+                    self.read_char();
+                    Some(Token::FatArrow)
                 } else {
                     Some(Token::Equal)
                 }
@@ -121,17 +287,17 @@ impl<'a> Lexer<'a> {
             _ => None,
         };
 
-        if token.is_some() {
+        if let Some(token) = token {
             self.read_char();
-            return Ok(token.unwrap());
+            return Ok(token);
         }
 
-        if self.curr_char.is_alphabetic() {
+        if self.curr_char.is_alphabetic() || self.curr_char == '_' {
             return Ok(self.read_identifier());
         }
 
-        if self.curr_char.is_numeric() {
-            return Ok(self.read_number());
+        if self.curr_char.is_ascii_digit() {
+            return self.read_number();
         }
 
         let illegal = Token::Illegal(self.curr_char);
@@ -144,6 +310,8 @@ impl<'a> Lexer<'a> {
         // look for keywords
         match literal.as_str() {
             "let" => Token::Let,
+            "mut" => Token::Mut,
+            "const" => Token::Const,
             "define" => Token::Func,
             "return" => Token::Return,
             "true" => Token::True,
@@ -156,29 +324,212 @@ impl<'a> Lexer<'a> {
             "if" => Token::If,
             "else" => Token::Else,
             "for" => Token::For,
+            "while" => Token::While,
+            "loop" => Token::Loop,
+            "break" => Token::Break,
+            "continue" => Token::Continue,
             "in" => Token::In,
+            "and" => Token::And,
+            "or" => Token::Or,
+            "not" => Token::Not,
             "void" => Token::TypeVoid,
+            "function" => Token::TypeFunction,
             "Array" => Token::ClassArray,
+            "Map" => Token::ClassMap,
             "import" => Token::Import,
+            "struct" => Token::Struct,
+            "enum" => Token::Enum,
+            "match" => Token::Match,
+            "type" => Token::Type,
+            "is" => Token::Is,
             _ => Token::Identifier(literal),
         }
     }
 
     fn read_string(&mut self) -> Result<Token, String> {
         self.read_char();
-        let literal = self.chop_while(|x| x != '"');
-        if self.curr_char != '"' {
-            return Err(format!("Unbalanced '\"'"));
+        let mut parts: Vec<StringPart> = vec![];
+        let mut text = String::new();
+
+        loop {
+            match self.curr_char {
+                '"' => break,
+                '\\' => text.push(self.read_escape()?),
+                '$' if self.next_char_is('{') => {
+                    if !text.is_empty() {
+                        parts.push(StringPart::Text(std::mem::take(&mut text)));
+                    }
+                    parts.push(self.read_interpolation()?);
+                }
+                _ if self.eof() => return Err("Unbalanced '\"'".to_string()),
+                x => {
+                    text.push(x);
+                    self.read_char();
+                }
+            }
+        }
+
+        if parts.is_empty() {
+            return Ok(Token::String(text));
+        }
+        if !text.is_empty() {
+            parts.push(StringPart::Text(text));
+        }
+        Ok(Token::InterpolatedString(parts))
+    }
+
+    fn read_escape(&mut self) -> Result<char, String> {
+        self.read_char();
+        let escaped = match self.curr_char {
+            'n' => '\n',
+            't' => '\t',
+            'r' => '\r',
+            '0' => '\0',
+            '\\' => '\\',
+            '"' => '"',
+            '$' => '$',
+            'u' => return self.read_unicode_escape(),
+            _ if self.eof() => return Err("Unbalanced '\"'".to_string()),
+            x => return Err(format!("Unknown escape sequence '\\{}'", x)),
+        };
+        self.read_char();
+        Ok(escaped)
+    }
+
+    fn read_unicode_escape(&mut self) -> Result<char, String> {
+        if !self.next_char_is('{') {
+            return Err("Expected '{' after '\\u'".to_string());
+        }
+        self.read_char();
+        self.read_char();
+        let digits = self.chop_while(|x| x.is_ascii_hexdigit());
+        if self.curr_char != '}' || digits.is_empty() || digits.len() > 6 {
+            return Err("Invalid unicode escape, expected '\\u{XXXX}'".to_string());
+        }
+        self.read_char();
+        u32::from_str_radix(&digits, 16)
+            .ok()
+            .and_then(char::from_u32)
+            .ok_or(format!("'\\u{{{}}}' is not a valid unicode char", digits))
+    }
+
+    fn read_interpolation(&mut self) -> Result<StringPart, String> {
+        self.read_char();
+        self.read_char();
+        let mark = self.mark();
+        let start = self.pos;
+        let mut depth = 0;
+        let mut in_string = false;
+
+        while !self.eof() {
+            match self.curr_char {
+                '\\' if in_string => self.read_char(),
+                '"' => in_string = !in_string,
+                '{' if !in_string => depth += 1,
+                '}' if !in_string && depth == 0 => break,
+                '}' if !in_string => depth -= 1,
+                _ => {}
+            }
+            self.read_char();
+        }
+
+        if self.eof() {
+            return Err("Unterminated '${' in string".to_string());
+        }
+
+        let code = self.chop(start, self.pos);
+        let span = self.span_from(mark);
+        self.read_char();
+        Ok(StringPart::Code(code, span))
+    }
+
+    fn read_number(&mut self) -> Result<Token, String> {
+        let radix = match (self.curr_char, self.peek_char(1)) {
+            ('0', 'x' | 'X') => 16,
+            ('0', 'b' | 'B') => 2,
+            ('0', 'o' | 'O') => 8,
+            _ => 10,
+        };
+
+        if radix != 10 {
+            let start = self.pos;
+            self.read_char();
+            self.read_char();
+            let digits = self.chop_while(|x| x.is_ascii_alphanumeric() || x == '_');
+            let literal = self.chop(start, self.pos);
+            if digits.is_empty() || !digits.chars().all(|x| x.is_digit(radix) || x == '_') {
+                return Err(format!("Invalid base {} literal '{}'", radix, literal));
+            }
+            let digits = Self::strip_separators(&digits, &literal)?;
+            return match i64::from_str_radix(&digits, radix) {
+                Ok(val) => Ok(Token::Int(val)),
+                Err(_) => Err(format!("Integer literal '{}' is too large", literal)),
+            };
+        }
+
+        let start = self.pos;
+        let mut is_float = false;
+        self.chop_while(|x| x.is_ascii_digit() || x == '_');
+
+        if self.curr_char == '.' && self.peek_char(1).is_ascii_digit() {
+            is_float = true;
+            self.read_char();
+            self.chop_while(|x| x.is_ascii_digit() || x == '_');
+        }
+
+        let exponent_follows = match self.peek_char(1) {
+            '+' | '-' => self.peek_char(2).is_ascii_digit(),
+            x => x.is_ascii_digit(),
+        };
+        if matches!(self.curr_char, 'e' | 'E') && exponent_follows {
+            is_float = true;
+            self.read_char();
+            self.read_char();
+            self.chop_while(|x| x.is_ascii_digit() || x == '_');
+        }
+
+        let malformed = self.curr_char.is_alphanumeric()
+            || self.curr_char == '_'
+            || (self.curr_char == '.' && self.peek_char(1).is_ascii_digit());
+        if malformed {
+            self.chop_while(|x| x.is_alphanumeric() || x == '_' || x == '.');
+            let literal = self.chop(start, self.pos);
+            return Err(format!("Invalid number literal '{}'", literal));
+        }
+
+        let literal = self.chop(start, self.pos);
+        let digits = Self::strip_separators(&literal, &literal)?;
+
+        if is_float {
+            return match digits.parse::<f64>() {
+                Ok(val) if val.is_finite() => Ok(Token::Float(val)),
+                _ => Err(format!("Float literal '{}' is out of range", literal)),
+            };
+        }
+
+        match digits.parse::<i64>() {
+            Ok(val) => Ok(Token::Int(val)),
+            Err(_) => Err(format!(
+                "Integer literal '{}' is too large, max is {}",
+                literal,
+                i64::MAX
+            )),
         }
-        Ok(Token::String(literal))
     }
 
-    fn read_number(&mut self) -> Token {
-        let literal = self.chop_while(|x| x.is_numeric() || x == '.');
-        if literal.contains(".") {
-            return Token::Float(literal.parse::<f64>().unwrap_or(0.0));
+    fn strip_separators(digits: &str, literal: &str) -> Result<String, String> {
+        let chars = digits.chars().collect::<Vec<char>>();
+        for (index, x) in chars.iter().enumerate() {
+            if *x != '_' {
+                continue;
+            }
+            let prev = if index > 0 { chars[index - 1] } else { '_' };
+            let next = chars.get(index + 1).copied().unwrap_or('_');
+            if !prev.is_ascii_alphanumeric() || !next.is_ascii_alphanumeric() {
+                return Err(format!("Misplaced digit separator in '{}'", literal));
+            }
         }
-        return Token::Int(literal.parse::<i64>().unwrap());
+        Ok(digits.replace('_', ""))
     }
 
     fn skip_whitespace(&mut self) {
@@ -200,21 +551,28 @@ impl<'a> Lexer<'a> {
         self.input[begin..end].iter().collect::<String>()
     }
 
-    fn eof(&mut self) -> bool {
+    fn eof(&self) -> bool {
         self.curr_char == NULL_CHAR
     }
 
-    fn next_char_is(&mut self, x: char) -> bool {
+    fn next_char_is(&self, x: char) -> bool {
         if self.read_pos >= self.input.len() {
             return false;
         }
         self.input[self.read_pos] == x
     }
+
+    fn peek_char(&self, distance: usize) -> char {
+        match self.input.get(self.pos + distance) {
+            Some(x) if self.pos < self.input.len() => *x,
+            _ => NULL_CHAR,
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::super::token::Token;
+    use super::super::token::{StringPart, Token, Trivia};
     use super::Lexer;
 
     #[test]
@@ -340,9 +698,199 @@ for x in range(1, 10, 2) {
         ];
 
         let input = input.chars().collect::<Vec<char>>();
-        let mut lexer = Lexer::new(&input);
+        let mut lexer = Lexer::new(&input, "test.fl");
+        for expected_token in expected_tokens {
+            assert_eq!(expected_token, lexer.next_token().unwrap().token);
+        }
+    }
+
+    #[test]
+    fn test_comments() {
+        let input = r#"
+// line comment
+let x = 1 // trailing comment
+/* block /* nested */ still comment */
+/// adds one
+define inc(y: int): int { return y / 1 }
+"#;
+        let input = input.chars().collect::<Vec<char>>();
+        let mut lexer = Lexer::new(&input, "comments.fl");
+
+        let expected_tokens = [
+            Token::Let,
+            Token::Identifier("x".to_string()),
+            Token::Equal,
+            Token::Int(1),
+        ];
+        for expected_token in expected_tokens {
+            assert_eq!(expected_token, lexer.next_token().unwrap().token);
+        }
+
+        let define = lexer.next_token().unwrap();
+        assert_eq!(Token::Func, define.token);
+        match &define.trivia[..] {
+            [Trivia::DocComment(text, span)] => {
+                assert_eq!("adds one", text);
+                assert_eq!((5, 1), (span.line, span.colm));
+            }
+            _ => panic!("expected one doc comment, got {:?}", define.trivia),
+        }
+
+        let rest = (0..14)
+            .map(|_| lexer.next_token().unwrap().token)
+            .collect::<Vec<Token>>();
+        assert_eq!(Token::Slash, rest[11]);
+        assert_eq!(Token::Eof, lexer.next_token().unwrap().token);
+    }
+
+    #[test]
+    fn test_unterminated_block_comment() {
+        let input = "let x = 1\n  /* open /* nested */\nlet y = 2"
+            .chars()
+            .collect::<Vec<char>>();
+        let mut lexer = Lexer::new(&input, "comments.fl");
+        for _ in 0..4 {
+            lexer.next_token().unwrap();
+        }
+
+        let err = lexer.next_token().unwrap_err();
+        assert_eq!("Unterminated block comment", err.msg);
+        assert_eq!(
+            (2, 3, 12, 14),
+            (err.span.line, err.span.colm, err.span.start, err.span.end)
+        );
+    }
+
+    #[test]
+    fn test_string_escapes_and_interpolation() {
+        let input =
+            r#""tab\there \"quoted\" \u{1F600}\\ \${x}" "Hi ${name}, ${ {"a": "}"}.length }!""#;
+        let input = input.chars().collect::<Vec<char>>();
+        let mut lexer = Lexer::new(&input, "strings.fl");
+
+        assert_eq!(
+            Token::String("tab\there \"quoted\" \u{1F600}\\ ${x}".to_string()),
+            lexer.next_token().unwrap().token
+        );
+
+        match lexer.next_token().unwrap().token {
+            Token::InterpolatedString(parts) => {
+                assert_eq!(5, parts.len());
+                assert_eq!(StringPart::Text("Hi ".to_string()), parts[0]);
+                match &parts[1] {
+                    StringPart::Code(code, span) => {
+                        assert_eq!("name", code);
+                        assert_eq!((1, 48), (span.line, span.colm));
+                    }
+                    part => panic!("expected code, got {:?}", part),
+                }
+                match &parts[3] {
+                    StringPart::Code(code, _) => assert_eq!(r#" {"a": "}"}.length "#, code),
+                    part => panic!("expected code, got {:?}", part),
+                }
+            }
+            token => panic!("expected interpolated string, got {:?}", token),
+        }
+        assert_eq!(Token::Eof, lexer.next_token().unwrap().token);
+
+        let input = r#""\q""#.chars().collect::<Vec<char>>();
+        let err = Lexer::new(&input, "strings.fl").next_token().unwrap_err();
+        assert_eq!("Unknown escape sequence '\\q'", err.msg);
+    }
+
+    #[test]
+    fn test_numeric_literals() {
+        let input = "0xFF 0b1010 0o17 1_000_000 1.5e-3 2E3 3.25 10.as_float 0x7fff_ffff_ffff_ffff";
+        let input = input.chars().collect::<Vec<char>>();
+        let mut lexer = Lexer::new(&input, "numbers.fl");
+
+        let expected_tokens = [
+            Token::Int(255),
+            Token::Int(10),
+            Token::Int(15),
+            Token::Int(1_000_000),
+            Token::Float(1.5e-3),
+            Token::Float(2000.0),
+            Token::Float(3.25),
+            Token::Int(10),
+            Token::Dot,
+            Token::Identifier("as_float".to_string()),
+            Token::Int(i64::MAX),
+            Token::Eof,
+        ];
         for expected_token in expected_tokens {
-            assert_eq!(expected_token, lexer.next_token().unwrap());
+            assert_eq!(expected_token, lexer.next_token().unwrap().token);
+        }
+
+        let malformed = [
+            ("1.2.3", "Invalid number literal '1.2.3'"),
+            ("12abc", "Invalid number literal '12abc'"),
+            ("0b102", "Invalid base 2 literal '0b102'"),
+            ("0x", "Invalid base 16 literal '0x'"),
+            ("1__000", "Misplaced digit separator in '1__000'"),
+            ("100_", "Misplaced digit separator in '100_'"),
+            ("1e999", "Float literal '1e999' is out of range"),
+            (
+                "9223372036854775808",
+                "Integer literal '9223372036854775808' is too large, max is 9223372036854775807",
+            ),
+            (
+                "0xFFFF_FFFF_FFFF_FFFF_F",
+                "Integer literal '0xFFFF_FFFF_FFFF_FFFF_F' is too large",
+            ),
+        ];
+        for (literal, msg) in malformed {
+            let input = format!("let x = {}", literal)
+                .chars()
+                .collect::<Vec<char>>();
+            let mut lexer = Lexer::new(&input, "numbers.fl");
+            for _ in 0..3 {
+                lexer.next_token().unwrap();
+            }
+            let err = lexer.next_token().unwrap_err();
+            assert_eq!(msg, err.msg);
+            assert_eq!(
+                (1, 9, 8, 8 + literal.len()),
+                (err.span.line, err.span.colm, err.span.start, err.span.end)
+            );
+        }
+    }
+
+    #[test]
+    fn test_token_spans() {
+        let input = "let x = 10\n  io.puts(\"olá\", x)"
+            .chars()
+            .collect::<Vec<char>>();
+        let mut lexer = Lexer::new(&input, "spans.fl");
+
+        let expected = [
+            (Token::Let, 1, 1, 0, 3),
+            (Token::Identifier("x".to_string()), 1, 5, 4, 5),
+            (Token::Equal, 1, 7, 6, 7),
+            (Token::Int(10), 1, 9, 8, 10),
+            (Token::Identifier("io".to_string()), 2, 3, 13, 15),
+            (Token::Dot, 2, 5, 15, 16),
+            (Token::Identifier("puts".to_string()), 2, 6, 16, 20),
+            (Token::Lparen, 2, 10, 20, 21),
+            (Token::String("olá".to_string()), 2, 11, 21, 27),
+            (Token::Comma, 2, 16, 27, 28),
+            (Token::Identifier("x".to_string()), 2, 18, 29, 30),
+            (Token::Rparen, 2, 19, 30, 31),
+        ];
+
+        for (token, line, colm, start, end) in expected {
+            let info = lexer.next_token().unwrap();
+            assert_eq!(token, info.token);
+            assert_eq!("spans.fl", &*info.span.file);
+            assert_eq!(
+                (line, colm, start, end),
+                (
+                    info.span.line,
+                    info.span.colm,
+                    info.span.start,
+                    info.span.end
+                )
+            );
         }
     }
 }
@@ -2,3 +2,4 @@ pub mod token;
 pub mod lexer;
 pub mod parser;
 pub mod ast;
+pub mod span;
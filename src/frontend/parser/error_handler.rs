@@ -1,16 +1,22 @@
 use core::fmt;
 
+use super::super::span::Span;
 use super::super::token::Token;
 
 #[derive(Clone)]
+#[allow(clippy::enum_variant_names)]
 pub enum ParserErrorKind {
     SyntaxError,
+    MatchError,
+    SemanticError,
 }
 
 impl fmt::Display for ParserErrorKind {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             ParserErrorKind::SyntaxError => write!(f, "[Syntax Error]"),
+            ParserErrorKind::MatchError => write!(f, "[Match Error]"),
+            ParserErrorKind::SemanticError => write!(f, "[Semantic Error]"),
         }
     }
 }
@@ -19,6 +25,13 @@ impl fmt::Display for ParserErrorKind {
 pub struct ParserError {
     kind: ParserErrorKind,
     msg: String,
+    span: Span,
+}
+
+impl ParserError {
+    pub fn report(&self, source: &str) -> String {
+        format!("{}\n{}", self, self.span.snippet(source))
+    }
 }
 
 impl fmt::Display for ParserError {
@@ -28,64 +41,117 @@ impl fmt::Display for ParserError {
 }
 
 pub struct ParserErrorHandler {
-    error: Option<ParserError>,
+    errors: Vec<ParserError>,
+    panicking: bool,
 }
 
 impl ParserErrorHandler {
     pub fn new() -> Self {
-        Self { error: None }
+        Self {
+            errors: vec![],
+            panicking: false,
+        }
     }
 
     pub fn has_error(&self) -> bool {
-        if self.error.is_some() {
-            return true;
+        !self.errors.is_empty()
+    }
+
+    pub fn is_panicking(&self) -> bool {
+        self.panicking
+    }
+
+    pub fn recover(&mut self) {
+        self.panicking = false;
+    }
+
+    pub fn set_error(&mut self, kind: ParserErrorKind, msg: String, span: Span) {
+        self.add_error(ParserError { kind, msg, span });
+    }
+
+    pub fn add_error(&mut self, error: ParserError) {
+        if self.panicking {
+            return;
         }
-        false
+        self.errors.push(error);
+        self.panicking = true;
     }
 
-    pub fn set_error(&mut self, kind: ParserErrorKind, msg: String) {
-        self.error = Some(ParserError { kind, msg });
+    pub fn set_semantic_error(&mut self, msg: String, span: Span) {
+        if self.panicking {
+            return;
+        }
+        self.errors.push(ParserError {
+            kind: ParserErrorKind::SemanticError,
+            msg,
+            span,
+        });
     }
 
-    pub fn get_error(&self) -> Option<ParserError> {
-        if self.error.is_none() {
-            return None;
+    pub fn add_errors(&mut self, errors: Vec<ParserError>) {
+        if self.panicking {
+            return;
         }
-        Some(self.error.clone().unwrap())
+        self.errors.extend(errors);
+        self.panicking = true;
+    }
+
+    pub fn get_errors(&self) -> Vec<ParserError> {
+        let mut errors = self.errors.clone();
+        errors.sort_by_key(|err| (err.span.line, err.span.colm));
+        errors
+    }
+
+    pub fn set_invalid_left_side_of_assignment_error(&mut self, span: Span) {
+        self.add_error(ParserError {
+            kind: ParserErrorKind::SyntaxError,
+            msg: "Left side of assignment must be an identifier or an index".to_string(),
+            span,
+        });
     }
 
-    pub fn set_invalid_left_side_of_assignment_error(&mut self) {
-        self.error = Some(ParserError {
+    pub fn set_invalid_update_operand_error(&mut self, operator: &Token, span: Span) {
+        self.add_error(ParserError {
             kind: ParserErrorKind::SyntaxError,
-            msg: format!("Left side of assignment must an identifier"),
+            msg: format!("'{operator}' can only be applied to a variable"),
+            span,
         });
     }
 
-    pub fn set_identifier_error(&mut self, token: &Token) {
-        self.error = Some(ParserError {
+    pub fn set_identifier_error(&mut self, token: &Token, span: Span) {
+        self.add_error(ParserError {
             kind: ParserErrorKind::SyntaxError,
             msg: format!("'{token}' cannot be used as identifier"),
+            span,
         });
     }
 
-    pub fn set_expected_but_provided_error(&mut self, expected: &Token, provided: &Token) {
-        self.error = Some(ParserError {
+    pub fn set_expected_but_provided_error(
+        &mut self,
+        expected: &Token,
+        provided: &Token,
+        span: Span,
+    ) {
+        self.add_error(ParserError {
             kind: ParserErrorKind::SyntaxError,
             msg: format!("expected '{}' but provided '{}'", expected, provided),
+            span,
         });
     }
 
-    pub fn set_unexpexted_token_error(&mut self, token: &Token) {
-        self.error = Some(ParserError {
+    pub fn set_unexpexted_token_error(&mut self, token: &Token, span: Span) {
+        self.add_error(ParserError {
             kind: ParserErrorKind::SyntaxError,
             msg: format!("unexpected {}", token),
+            span,
         });
     }
 
-    pub fn set_not_type_annot_error(&mut self, token: &Token) {
-        self.error = Some(ParserError {
+    pub fn set_not_type_annot_error(&mut self, token: &Token, span: Span) {
+        self.add_error(ParserError {
             kind: ParserErrorKind::SyntaxError,
             msg: format!("Invalid type: {}", token),
+            span,
         });
     }
 }
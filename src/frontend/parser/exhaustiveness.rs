@@ -0,0 +1,230 @@
+use std::collections::HashMap;
+
+use super::error_handler::ParserErrorKind;
+use super::Parser;
+use crate::frontend::ast::*;
+use crate::frontend::span::Span;
+
+#[derive(Default)]
+struct Collected<'p> {
+    enums: HashMap<String, Vec<String>>,
+    matches: Vec<(&'p [MatchArm], &'p Span)>,
+}
+
+impl<'a> Parser<'a> {
+    pub(super) fn check_exhaustiveness(&mut self, program: &Program) {
+        let mut collected = Collected::default();
+        collect_stmts(program, &mut collected);
+
+        for (arms, span) in &collected.matches {
+            if let Some(msg) = find_missing_case(arms, &collected.enums) {
+                self.error_handler
+                    .set_error(ParserErrorKind::MatchError, msg, (*span).clone());
+                self.error_handler.recover();
+            }
+        }
+    }
+}
+
+fn find_missing_case(arms: &[MatchArm], enums: &HashMap<String, Vec<String>>) -> Option<String> {
+    let unguarded = arms
+        .iter()
+        .filter(|arm| arm.guard.is_none())
+        .map(|arm| &arm.pattern)
+        .collect::<Vec<&Pattern>>();
+
+    for arm in arms {
+        if let Pattern::Variant(Identifier(name), Identifier(variant), _) = &arm.pattern {
+            match enums.get(name) {
+                Some(variants) if !variants.contains(variant) => {
+                    return Some(format!("'{}' has no variant '{}'", name, variant));
+                }
+                _ => {}
+            }
+        }
+    }
+
+    if unguarded.iter().any(|pattern| is_irrefutable(pattern)) {
+        return None;
+    }
+
+    let enum_name = arms.iter().find_map(|arm| match &arm.pattern {
+        Pattern::Variant(Identifier(name), ..) => Some(name),
+        _ => None,
+    });
+    if let Some(enum_name) = enum_name {
+        let variants = enums.get(enum_name)?;
+        // payloads are only covered by names and `_`
+        let missing = variants.iter().find(|variant| {
+            !unguarded.iter().any(|pattern| {
+                matches!(pattern, Pattern::Variant(_, Identifier(name), payload)
+                    if name == *variant && payload.iter().all(is_irrefutable))
+            })
+        })?;
+        return Some(format!(
+            "Non-exhaustive match, '{}.{}' is not handled",
+            enum_name, missing
+        ));
+    }
+
+    let has_bool = |val: bool| {
+        unguarded
+            .iter()
+            .any(|pattern| matches!(pattern, Pattern::Literal(Literal::Boolean(b)) if *b == val))
+    };
+    if has_bool(true) && has_bool(false) {
+        return None;
+    }
+
+    Some("Non-exhaustive match, add a '_' arm".to_string())
+}
+
+fn is_irrefutable(pattern: &Pattern) -> bool {
+    matches!(pattern, Pattern::Wildcard | Pattern::Binding(_))
+}
+
+fn collect_stmts<'p>(stmts: &'p [Stmt], collected: &mut Collected<'p>) {
+    for stmt in stmts {
+        collect_stmt(stmt, collected);
+    }
+}
+
+fn collect_stmt<'p>(stmt: &'p Stmt, collected: &mut Collected<'p>) {
+    match &stmt.kind {
+        StmtKind::Expr(expr)
+        | StmtKind::Return(Some(expr))
+        | StmtKind::Let(_, _, Some(expr), _)
+        | StmtKind::LetTuple(_, _, expr, _)
+        | StmtKind::Const(_, _, expr) => collect_expr(expr, collected),
+        StmtKind::Func(_, params, body, _) => {
+            collect_params(params, collected);
+            collect_stmts(body, collected);
+        }
+        StmtKind::If {
+            condition,
+            consequence,
+            alternative,
+        } => {
+            collect_expr(condition, collected);
+            collect_stmts(consequence, collected);
+            match alternative {
+                Some(Else::Block(block)) => collect_stmts(block, collected),
+                Some(Else::If(stmt)) => collect_stmt(stmt, collected),
+                None => {}
+            }
+        }
+        StmtKind::ForLoop {
+            iterable, block, ..
+        } => {
+            collect_expr(iterable, collected);
+            collect_stmts(block, collected);
+        }
+        StmtKind::While {
+            condition, block, ..
+        } => {
+            collect_expr(condition, collected);
+            collect_stmts(block, collected);
+        }
+        StmtKind::Struct { methods, .. } => {
+            for method in methods {
+                collect_params(&method.params, collected);
+                collect_stmts(&method.body, collected);
+            }
+        }
+        StmtKind::Enum {
+            name: Identifier(name),
+            variants,
+        } => {
+            let variants = variants
+                .iter()
+                .map(|(Identifier(variant), _)| variant.clone())
+                .collect();
+            collected.enums.insert(name.clone(), variants);
+        }
+        StmtKind::Let(..)
+        | StmtKind::Return(None)
+        | StmtKind::Break(_)
+        | StmtKind::Continue(_)
+        | StmtKind::Import(_)
+        | StmtKind::TypeAlias { .. } => {}
+    }
+}
+
+fn collect_params<'p>(params: &'p [Param], collected: &mut Collected<'p>) {
+    for default in params.iter().filter_map(|param| param.default.as_ref()) {
+        collect_expr(default, collected);
+    }
+}
+
+fn collect_expr<'p>(expr: &'p Expr, collected: &mut Collected<'p>) {
+    match &expr.kind {
+        ExprKind::Literal(Literal::Array(items) | Literal::Tuple(items))
+        | ExprKind::Interpolated(items) => {
+            for item in items {
+                collect_expr(item, collected);
+            }
+        }
+        ExprKind::Literal(Literal::Map(entries)) => {
+            for (key, value) in entries {
+                collect_expr(key, collected);
+                collect_expr(value, collected);
+            }
+        }
+        ExprKind::Call(func, args) => {
+            collect_expr(func, collected);
+            for arg in args {
+                collect_expr(&arg.value, collected);
+            }
+        }
+        ExprKind::Infix(lhs, _, rhs)
+        | ExprKind::FieldAcc(lhs, rhs)
+        | ExprKind::OptionalFieldAcc(lhs, rhs)
+        | ExprKind::Index(lhs, rhs) => {
+            collect_expr(lhs, collected);
+            collect_expr(rhs, collected);
+        }
+        ExprKind::Prefix(_, expr)
+        | ExprKind::Postfix(expr, _)
+        | ExprKind::Assign(_, expr)
+        | ExprKind::Spread(expr)
+        | ExprKind::Is(expr, _) => collect_expr(expr, collected),
+        ExprKind::Slice(target, start, end) => {
+            collect_expr(target, collected);
+            for bound in [start, end].into_iter().flatten() {
+                collect_expr(bound, collected);
+            }
+        }
+        ExprKind::IndexAssign(target, index, value) => {
+            collect_expr(target, collected);
+            collect_expr(index, collected);
+            collect_expr(value, collected);
+        }
+        ExprKind::FieldAssign(src, _, value) => {
+            collect_expr(src, collected);
+            collect_expr(value, collected);
+        }
+        ExprKind::Func(params, body, _) => {
+            collect_params(params, collected);
+            collect_stmts(body, collected);
+        }
+        ExprKind::StructLit(_, fields) => {
+            for (_, value) in fields {
+                collect_expr(value, collected);
+            }
+        }
+        ExprKind::Match(subject, arms) => {
+            collect_expr(subject, collected);
+            collected.matches.push((arms, &expr.span));
+            for arm in arms {
+                if let Some(guard) = &arm.guard {
+                    collect_expr(guard, collected);
+                }
+                match &arm.body {
+                    ArmBody::Expr(expr) => collect_expr(expr, collected),
+                    ArmBody::Block(block) => collect_stmts(block, collected),
+                }
+            }
+        }
+        ExprKind::Literal(_) | ExprKind::Identifier(_) => {}
+    }
+}
@@ -1,20 +1,32 @@
 mod error_handler;
+mod exhaustiveness;
 mod parsers;
 
 use self::parsers::if_parser::parse_if_stmt;
 use super::ast::*;
 use super::lexer::Lexer;
-use super::token::Token;
+use super::span::Span;
+use super::token::{StringPart, Token, TokenInfo};
 use error_handler::*;
+use parsers::enum_parser::parse_enum_stmt;
 use parsers::forloop_parser::parse_forloop_stmt;
-use parsers::func_parser::parse_func_stmt;
-use parsers::let_parser::parse_let_stmt;
+use parsers::func_parser::{parse_func_expr, parse_func_stmt};
+use parsers::let_parser::{parse_const_stmt, parse_let_stmt};
+use parsers::match_parser::parse_match_expr;
+use parsers::struct_parser::parse_struct_stmt;
+use parsers::while_parser::{parse_loop_stmt, parse_while_stmt};
 
 pub struct Parser<'a> {
     l: &'a mut Lexer<'a>,
     curr_token: Token,
     next_token: Token,
+    curr_span: Span,
+    next_span: Span,
     error_handler: ParserErrorHandler,
+    no_struct_literal: bool,
+    type_params: Vec<String>,
+    block_depth: usize,
+    consts: Vec<String>,
 }
 
 impl<'a> Parser<'a> {
@@ -23,7 +35,13 @@ impl<'a> Parser<'a> {
             l,
             curr_token: Token::Eof,
             next_token: Token::Eof,
+            curr_span: Span::default(),
+            next_span: Span::default(),
             error_handler: ParserErrorHandler::new(),
+            no_struct_literal: false,
+            type_params: vec![],
+            block_depth: 0,
+            consts: vec![],
         };
 
         p.bump();
@@ -33,83 +51,216 @@ impl<'a> Parser<'a> {
     }
 
     fn bump(&mut self) {
-        let next_token = self.l.next_token();
-        if next_token.is_err() {
-            self.error_handler
-                .set_error(ParserErrorKind::SyntaxError, next_token.err().unwrap());
-            return;
-        }
+        let next = match self.l.next_token() {
+            Ok(next) => next,
+            Err(err) => {
+                self.error_handler.set_error(
+                    ParserErrorKind::SyntaxError,
+                    err.msg,
+                    err.span.clone(),
+                );
+                TokenInfo {
+                    token: Token::Illegal('\0'),
+                    span: err.span,
+                    trivia: vec![],
+                }
+            }
+        };
 
         self.curr_token = self.next_token.clone();
-        self.next_token = next_token.unwrap();
+        self.curr_span = self.next_span.clone();
+        self.next_token = next.token;
+        self.next_span = next.span;
     }
 
     pub fn parse(&mut self) -> Program {
         let mut program: Program = vec![];
-        while !self.current_token_is(&Token::Eof) && !self.error_handler.has_error() {
+        while !self.current_token_is(&Token::Eof) {
+            let start = self.curr_span.clone();
             match self.parse_stmt() {
-                Some(stmt) => program.push(stmt),
-                None => {}
+                Some(stmt) if !self.error_handler.is_panicking() => {
+                    program.push(stmt);
+                    self.bump();
+                }
+                _ => self.synchronize(&start),
             }
-            self.bump();
         }
+        self.check_exhaustiveness(&program);
         program
     }
 
+    // resume at the next statement keyword or the `}` closing the block,
+    // never at `stmt_start` itself; at the top level a `}` is skipped along
+    fn synchronize(&mut self, stmt_start: &Span) {
+        let mut depth = 0;
+        while !self.current_token_is(&Token::Eof) {
+            let at_boundary = matches!(
+                self.curr_token,
+                Token::Let
+                    | Token::Const
+                    | Token::Func
+                    | Token::If
+                    | Token::For
+                    | Token::While
+                    | Token::Loop
+                    | Token::Break
+                    | Token::Continue
+                    | Token::Import
+                    | Token::Struct
+                    | Token::Enum
+                    | Token::Type
+                    | Token::Return
+            ) || (self.curr_token == Token::Rbrace && self.block_depth > 0);
+            if depth == 0 && at_boundary && self.curr_span != *stmt_start {
+                break;
+            }
+            match self.curr_token {
+                Token::Lbrace => depth += 1,
+                Token::Rbrace if depth > 0 => depth -= 1,
+                _ => {}
+            }
+            self.bump();
+        }
+        self.error_handler.recover();
+    }
+
     fn parse_stmt(&mut self) -> Option<Stmt> {
-        if self.has_error() {
+        if self.error_handler.is_panicking() {
             return None;
         }
 
-        match self.curr_token {
+        let start = self.curr_span.clone();
+        let kind = match self.curr_token {
             Token::Let => parse_let_stmt(self),
-            Token::Func => parse_func_stmt(self),
+            Token::Const => parse_const_stmt(self),
+            Token::Func if !self.next_token_is(&Token::Lparen) => parse_func_stmt(self),
             Token::Return => self.parse_return_stmt(),
             Token::If => parse_if_stmt(self),
-            Token::For => parse_forloop_stmt(self),
+            Token::For => parse_forloop_stmt(self, None),
+            Token::While => parse_while_stmt(self, None),
+            Token::Loop => parse_loop_stmt(self, None),
+            Token::Break => Some(StmtKind::Break(self.parse_loop_label())),
+            Token::Continue => Some(StmtKind::Continue(self.parse_loop_label())),
+            Token::Identifier(_) if self.next_token_is(&Token::Colon) => self.parse_labelled_stmt(),
             Token::Import => self.parse_import_stmt(),
+            Token::Struct => parse_struct_stmt(self),
+            Token::Enum => parse_enum_stmt(self),
+            Token::Type => self.parse_type_alias_stmt(),
             _ => self.parse_expr_stmt(),
+        }?;
+
+        Some(Stmt::new(kind, start.to(&self.curr_span)))
+    }
+
+    fn parse_labelled_stmt(&mut self) -> Option<StmtKind> {
+        let Identifier(label) = self.parse_identifier()?;
+        self.bump();
+        self.bump();
+        match self.curr_token {
+            Token::For => parse_forloop_stmt(self, Some(label)),
+            Token::While => parse_while_stmt(self, Some(label)),
+            Token::Loop => parse_loop_stmt(self, Some(label)),
+            _ => {
+                self.error_handler.set_error(
+                    ParserErrorKind::SyntaxError,
+                    format!("Label '{}' must be followed by a loop", label),
+                    self.curr_span.clone(),
+                );
+                None
+            }
+        }
+    }
+
+    fn parse_loop_label(&mut self) -> Option<String> {
+        match self.next_token.clone() {
+            Token::Identifier(label) if self.next_span.line == self.curr_span.line => {
+                self.bump();
+                Some(label)
+            }
+            _ => None,
+        }
+    }
+
+    fn parse_type_alias_stmt(&mut self) -> Option<StmtKind> {
+        self.bump();
+        let name = match self.parse_identifier() {
+            Some(identifier) => identifier,
+            None => {
+                self.error_handler
+                    .set_identifier_error(&self.curr_token, self.curr_span.clone());
+                return None;
+            }
+        };
+        if !self.bump_expected_next(&Token::Equal) {
+            return None;
+        }
+        self.bump();
+        let type_ = self.parse_type()?;
+        if type_ == ExprType::Void {
+            self.error_handler.set_error(
+                ParserErrorKind::SyntaxError,
+                "Can't name the type 'void'".to_string(),
+                self.curr_span.clone(),
+            );
+            return None;
         }
+        Some(StmtKind::TypeAlias { name, type_ })
     }
 
-    fn parse_import_stmt(&mut self) -> Option<Stmt> {
+    fn parse_import_stmt(&mut self) -> Option<StmtKind> {
         self.bump();
         let target = match self.curr_token.clone() {
             Token::Identifier(val) => val,
             _ => {
-                self.error_handler.set_error(ParserErrorKind::SyntaxError, format!("Missing module name"));
+                self.error_handler.set_error(
+                    ParserErrorKind::SyntaxError,
+                    "Missing module name".to_string(),
+                    self.curr_span.clone(),
+                );
                 return None;
             }
         };
 
-        Some(Stmt::Import(target))
+        Some(StmtKind::Import(target))
     }
 
     fn parse_block_stmt(&mut self) -> Option<Vec<Stmt>> {
+        let no_struct_literal = std::mem::replace(&mut self.no_struct_literal, false);
+        self.block_depth += 1;
+        let block = self.parse_block_stmts();
+        self.block_depth -= 1;
+        self.no_struct_literal = no_struct_literal;
+        block
+    }
+
+    fn parse_block_stmts(&mut self) -> Option<Vec<Stmt>> {
         self.bump();
         let mut block: Vec<Stmt> = vec![];
         while !self.current_token_is(&Token::Rbrace) && !self.current_token_is(&Token::Eof) {
+            let start = self.curr_span.clone();
             match self.parse_stmt() {
-                Some(stmt) => block.push(stmt),
-                _ => return None,
+                Some(stmt) if !self.error_handler.is_panicking() => {
+                    block.push(stmt);
+                    self.bump();
+                }
+                _ => self.synchronize(&start),
             }
-            self.bump();
         }
         if !self.current_token_is(&Token::Rbrace) {
-            self.error_handler
-                .set_expected_but_provided_error(&Token::Rbrace, &self.curr_token);
+            self.error_handler.set_expected_but_provided_error(
+                &Token::Rbrace,
+                &self.curr_token,
+                self.curr_span.clone(),
+            );
             return None;
         }
         Some(block)
     }
 
-    fn parse_return_stmt(&mut self) -> Option<Stmt> {
+    fn parse_return_stmt(&mut self) -> Option<StmtKind> {
         self.bump();
-        let expr = match self.parse_expr(Precedence::Lowest) {
-            Some(expr) => expr,
-            None => return None,
-        };
-        Some(Stmt::Return(Some(expr)))
+        let expr = self.parse_expr(Precedence::Lowest)?;
+        Some(StmtKind::Return(Some(expr)))
     }
 
     fn parse_identifier(&mut self) -> Option<Identifier> {
@@ -119,40 +270,50 @@ impl<'a> Parser<'a> {
         }
     }
 
-    fn parse_expr_stmt(&mut self) -> Option<Stmt> {
-        match self.parse_expr(Precedence::Lowest) {
-            Some(expr) => Some(Stmt::Expr(expr)),
-            None => None,
-        }
+    fn parse_expr_stmt(&mut self) -> Option<StmtKind> {
+        self.parse_expr(Precedence::Lowest).map(StmtKind::Expr)
     }
 
     fn parse_expr(&mut self, precedence: Precedence) -> Option<Expr> {
-        if self.has_error() {
+        if self.error_handler.is_panicking() {
             return None;
         }
 
         let mut left = match self.curr_token {
+            Token::Identifier(_)
+                if self.next_token_is(&Token::Lbrace) && !self.no_struct_literal =>
+            {
+                self.parse_struct_literal()
+            }
             Token::Identifier(_) => self.parse_identifier_expr(),
             Token::String(_) => self.parse_string_expr(),
+            Token::InterpolatedString(_) => self.parse_interpolated_string_expr(),
             Token::Int(_) => self.parse_int_expr(),
             Token::Float(_) => self.parse_float_expr(),
-            Token::True => Some(Expr::Literal(Literal::Boolean(true))),
-            Token::False => Some(Expr::Literal(Literal::Boolean(false))),
-            Token::Null => Some(Expr::Literal(Literal::Null)),
-            Token::Bang | Token::Plus | Token::Minus => self.parse_prefix_expr(),
+            Token::True => self.make_literal(Literal::Boolean(true)),
+            Token::False => self.make_literal(Literal::Boolean(false)),
+            Token::Null => self.make_literal(Literal::Null),
+            Token::Bang
+            | Token::Not
+            | Token::Plus
+            | Token::Minus
+            | Token::DoublePlus
+            | Token::DoubleMinus => self.parse_prefix_expr(),
             Token::Lbracket => self.parse_array_expr(),
+            Token::Lparen => self.parse_paren_expr(),
+            Token::Lbrace if !self.no_struct_literal => self.parse_map_expr(),
+            Token::Func => parse_func_expr(self),
+            Token::Match => parse_match_expr(self),
             _ => {
                 let token = self.curr_token.clone();
-                self.error_handler.set_unexpexted_token_error(&token);
+                self.error_handler
+                    .set_unexpexted_token_error(&token, self.curr_span.clone());
                 return None;
             }
-        };
+        }?;
 
         while precedence < self.next_token_precedence() {
-            if left.is_none() {
-                return None;
-            }
-            match self.next_token {
+            left = match self.next_token {
                 Token::Plus
                 | Token::Minus
                 | Token::Asterisk
@@ -163,52 +324,90 @@ impl<'a> Parser<'a> {
                 | Token::LessThan
                 | Token::Percet
                 | Token::GratherOrEqual
-                | Token::LessOrEqual => {
+                | Token::LessOrEqual
+                | Token::And
+                | Token::Or
+                | Token::DoubleQuestion => {
                     self.bump();
-                    left = self.parse_infix_expr(left.unwrap());
+                    self.parse_infix_expr(left)
                 }
-                Token::Lparen => {
+                // on a new line `(` starts a tuple or a grouped expression
+                Token::Lparen if self.next_span.line == self.curr_span.line => {
                     self.bump();
-                    left = self.parse_call_expr(left.unwrap());
+                    self.parse_call_expr(left)
                 }
-                Token::Equal => {
+                // on a new line `[` starts an array literal
+                Token::Lbracket if self.next_span.line == self.curr_span.line => {
                     self.bump();
-                    left = self.parse_assign_expr(left.unwrap());
+                    self.parse_index_expr(left)
                 }
-                Token::DoublePlus | Token::DoubleMinus => {
+                Token::Equal
+                | Token::PlusEqual
+                | Token::MinusEqual
+                | Token::AsteriskEqual
+                | Token::SlashEqual
+                | Token::PercentEqual => {
                     self.bump();
-                    left = self.parse_postfix_expr(left.unwrap());
+                    self.parse_assign_expr(left)
                 }
-                Token::Dot => {
+                // on a new line `++x` starts the next statement
+                Token::DoublePlus | Token::DoubleMinus
+                    if self.next_span.line == self.curr_span.line =>
+                {
                     self.bump();
-                    left = self.parse_field_access_expr(left.unwrap());
+                    self.parse_postfix_expr(left)
                 }
-                _ => return left,
-            }
+                Token::Dot | Token::QuestionDot => {
+                    self.bump();
+                    self.parse_field_access_expr(left)
+                }
+                Token::Is => {
+                    self.bump();
+                    self.parse_is_expr(left)
+                }
+                _ => return Some(left),
+            }?;
         }
-        left
+        Some(left)
+    }
+
+    fn make_literal(&self, literal: Literal) -> Option<Expr> {
+        Some(Expr::new(
+            ExprKind::Literal(literal),
+            self.curr_span.clone(),
+        ))
+    }
+
+    fn parse_is_expr(&mut self, left: Expr) -> Option<Expr> {
+        self.bump();
+        let type_ = self.parse_type()?;
+        let span = left.span.to(&self.curr_span);
+        Some(Expr::new(ExprKind::Is(Box::new(left), type_), span))
     }
 
     fn parse_field_access_expr(&mut self, src: Expr) -> Option<Expr> {
+        let optional = self.current_token_is(&Token::QuestionDot);
         self.bump();
-        let target = match self.parse_expr(Precedence::FieldAcc) {
-            Some(expr) => expr,
-            None => return None
+        let target = self.parse_expr(Precedence::FieldAcc)?;
+        let span = src.span.to(&target.span);
+        let kind = match optional {
+            true => ExprKind::OptionalFieldAcc(Box::new(src), Box::new(target)),
+            false => ExprKind::FieldAcc(Box::new(src), Box::new(target)),
         };
-        Some(Expr::FieldAcc(Box::new(src), Box::new(target)))
+        Some(Expr::new(kind, span))
     }
 
     fn parse_int_expr(&mut self) -> Option<Expr> {
         match self.curr_token {
-            Token::Int(val) => Some(Expr::Literal(Literal::Int(val))),
-            _ => return None,
+            Token::Int(val) => self.make_literal(Literal::Int(val)),
+            _ => None,
         }
     }
 
     fn parse_float_expr(&mut self) -> Option<Expr> {
         match self.curr_token {
-            Token::Float(val) => Some(Expr::Literal(Literal::Float(val))),
-            _ => return None,
+            Token::Float(val) => self.make_literal(Literal::Float(val)),
+            _ => None,
         }
     }
 
@@ -219,42 +418,83 @@ impl<'a> Parser<'a> {
             _ => return None,
         };
 
-        Some(Expr::Postfix(Box::new(left), postfix))
+        if !matches!(left.kind, ExprKind::Identifier(_)) {
+            let token = self.curr_token.clone();
+            self.error_handler
+                .set_invalid_update_operand_error(&token, left.span);
+            return None;
+        }
+
+        let span = left.span.to(&self.curr_span);
+        Some(Expr::new(ExprKind::Postfix(Box::new(left), postfix), span))
     }
 
     fn parse_prefix_expr(&mut self) -> Option<Expr> {
-        let prefix = match self.curr_token {
-            Token::Bang => Prefix::Not,
-            Token::Plus => Prefix::Plus,
-            Token::Minus => Prefix::Minus,
+        let start = self.curr_span.clone();
+        let (prefix, precedence) = match self.curr_token {
+            Token::Bang => (Prefix::Not, Precedence::Prefix),
+            // like in Python, `not a == b` reads as `not (a == b)`
+            Token::Not => (Prefix::Not, Precedence::And),
+            Token::Plus => (Prefix::Plus, Precedence::Prefix),
+            Token::Minus => (Prefix::Minus, Precedence::Prefix),
+            Token::DoublePlus => (Prefix::Increment, Precedence::Prefix),
+            Token::DoubleMinus => (Prefix::Decrement, Precedence::Prefix),
             _ => return None,
         };
+        let operator = self.curr_token.clone();
 
         self.bump();
 
-        let expr = match self.parse_expr(Precedence::Prefix) {
-            Some(expr) => expr,
-            None => return None,
-        };
+        let expr = self.parse_expr(precedence)?;
+        if matches!(prefix, Prefix::Increment | Prefix::Decrement)
+            && !matches!(expr.kind, ExprKind::Identifier(_))
+        {
+            self.error_handler
+                .set_invalid_update_operand_error(&operator, expr.span);
+            return None;
+        }
+        let span = start.to(&expr.span);
 
-        Some(Expr::Prefix(prefix, Box::new(expr)))
+        Some(Expr::new(ExprKind::Prefix(prefix, Box::new(expr)), span))
     }
 
     fn parse_assign_expr(&mut self, left: Expr) -> Option<Expr> {
-        let identifier = match left {
-            Expr::Identifier(identifier) => identifier,
-            _ => {
-                self.error_handler
-                    .set_invalid_left_side_of_assignment_error();
-                return None;
-            }
+        let is_field = matches!(&left.kind, ExprKind::FieldAcc(_, target)
+            if matches!(target.kind, ExprKind::Identifier(_)));
+        if !is_field && !matches!(left.kind, ExprKind::Identifier(_) | ExprKind::Index(..)) {
+            self.error_handler
+                .set_invalid_left_side_of_assignment_error(left.span);
+            return None;
+        }
+        let compound = match self.curr_token {
+            Token::PlusEqual => Some(Infix::Plus),
+            Token::MinusEqual => Some(Infix::Minus),
+            Token::AsteriskEqual => Some(Infix::Multiply),
+            Token::SlashEqual => Some(Infix::Devide),
+            Token::PercentEqual => Some(Infix::Remainder),
+            _ => None,
         };
         self.bump();
-        let expr = match self.parse_expr(Precedence::Lowest) {
-            Some(expr) => expr,
-            None => return None,
+        let mut expr = self.parse_expr(Precedence::Lowest)?;
+        let span = left.span.to(&expr.span);
+
+        if let Some(infix) = compound {
+            expr = Expr::new(
+                ExprKind::Infix(Box::new(left.clone()), infix, Box::new(expr)),
+                span.clone(),
+            );
+        }
+
+        let kind = match left.kind {
+            ExprKind::Index(target, index) => ExprKind::IndexAssign(target, index, Box::new(expr)),
+            ExprKind::Identifier(identifier) => ExprKind::Assign(identifier, Box::new(expr)),
+            ExprKind::FieldAcc(src, target) => match target.kind {
+                ExprKind::Identifier(field) => ExprKind::FieldAssign(src, field, Box::new(expr)),
+                _ => unreachable!(),
+            },
+            _ => unreachable!(),
         };
-        Some(Expr::Assign(identifier, Box::new(expr)))
+        Some(Expr::new(kind, span))
     }
 
     fn parse_infix_expr(&mut self, left: Expr) -> Option<Expr> {
@@ -270,42 +510,139 @@ impl<'a> Parser<'a> {
             Token::LessOrEqual => Infix::LessOrEqual,
             Token::GratherThan => Infix::GratherThan,
             Token::GratherOrEqual => Infix::GratherOrEqual,
+            Token::And => Infix::And,
+            Token::Or => Infix::Or,
+            Token::DoubleQuestion => Infix::NullCoalescing,
             _ => return None,
         };
         let precedence = self.current_token_precedence();
         self.bump();
-        match self.parse_expr(precedence) {
-            Some(expr) => Some(Expr::Infix(Box::new(left), infix, Box::new(expr))),
-            None => None,
+        let right = self.parse_expr(precedence)?;
+        let span = left.span.to(&right.span);
+        Some(Expr::new(
+            ExprKind::Infix(Box::new(left), infix, Box::new(right)),
+            span,
+        ))
+    }
+
+    fn parse_index_expr(&mut self, target: Expr) -> Option<Expr> {
+        self.bump();
+
+        let start = match self.curr_token {
+            Token::DoubleDot => None,
+            _ => {
+                let index = self.parse_expr(Precedence::Lowest)?;
+                if !self.next_token_is(&Token::DoubleDot) {
+                    if !self.bump_expected_next(&Token::Rbracket) {
+                        return None;
+                    }
+                    let span = target.span.to(&self.curr_span);
+                    return Some(Expr::new(
+                        ExprKind::Index(Box::new(target), Box::new(index)),
+                        span,
+                    ));
+                }
+                self.bump();
+                Some(Box::new(index))
+            }
+        };
+
+        let end = match self.next_token {
+            Token::Rbracket => None,
+            _ => {
+                self.bump();
+                Some(Box::new(self.parse_expr(Precedence::Lowest)?))
+            }
+        };
+
+        if !self.bump_expected_next(&Token::Rbracket) {
+            return None;
         }
+        let span = target.span.to(&self.curr_span);
+        Some(Expr::new(
+            ExprKind::Slice(Box::new(target), start, end),
+            span,
+        ))
     }
 
     fn parse_call_expr(&mut self, func: Expr) -> Option<Expr> {
-        let args = match self.parse_expr_list(Token::Rparen) {
-            Some(exprs) => exprs,
-            None => return None,
-        };
-        return Some(Expr::Call(Box::new(func), args));
+        let no_struct_literal = std::mem::replace(&mut self.no_struct_literal, false);
+        let args = self.parse_call_args();
+        self.no_struct_literal = no_struct_literal;
+        let span = func.span.to(&self.curr_span);
+        Some(Expr::new(ExprKind::Call(Box::new(func), args?), span))
+    }
+
+    fn parse_call_args(&mut self) -> Option<Vec<Arg>> {
+        let mut args: Vec<Arg> = vec![];
+        if self.next_token_is(&Token::Rparen) {
+            self.bump();
+            return Some(args);
+        }
+
+        loop {
+            self.bump();
+            let name = match self.curr_token.clone() {
+                Token::Identifier(name) if self.next_token_is(&Token::Colon) => {
+                    self.bump();
+                    self.bump();
+                    Some(Identifier(name))
+                }
+                _ => None,
+            };
+            let start = self.curr_span.clone();
+            let value = match name {
+                Some(_) => self.parse_expr(Precedence::Lowest)?,
+                None => self.parse_list_item()?,
+            };
+
+            if name.is_none() && args.iter().any(|arg| arg.name.is_some()) {
+                self.error_handler.set_error(
+                    ParserErrorKind::SyntaxError,
+                    "Positional argument can't follow a named one".to_string(),
+                    start.to(&self.curr_span),
+                );
+                return None;
+            }
+            args.push(Arg { name, value });
+
+            if !self.next_token_is(&Token::Comma) {
+                break;
+            }
+            self.bump();
+        }
+        if !self.bump_expected_next(&Token::Rparen) {
+            return None;
+        }
+        Some(args)
+    }
+
+    fn parse_condition(&mut self) -> Option<Expr> {
+        let no_struct_literal = std::mem::replace(&mut self.no_struct_literal, true);
+        let expr = self.parse_expr(Precedence::Lowest);
+        self.no_struct_literal = no_struct_literal;
+        expr
     }
 
     fn parse_expr_list(&mut self, stop: Token) -> Option<Vec<Expr>> {
+        let no_struct_literal = std::mem::replace(&mut self.no_struct_literal, false);
+        let list = self.parse_expr_list_items(stop);
+        self.no_struct_literal = no_struct_literal;
+        list
+    }
+
+    fn parse_expr_list_items(&mut self, stop: Token) -> Option<Vec<Expr>> {
         let mut list: Vec<Expr> = vec![];
         if self.next_token_is(&stop) {
             self.bump();
             return Some(list);
         }
         self.bump();
-        match self.parse_expr(Precedence::Lowest) {
-            Some(expr) => list.push(expr),
-            None => return None,
-        }
+        list.push(self.parse_list_item()?);
         while self.next_token_is(&Token::Comma) {
             self.bump();
             self.bump();
-            match self.parse_expr(Precedence::Lowest) {
-                Some(expr) => list.push(expr),
-                None => return None,
-            }
+            list.push(self.parse_list_item()?);
         }
         if !self.bump_expected_next(&stop) {
             return None;
@@ -313,48 +650,313 @@ impl<'a> Parser<'a> {
         Some(list)
     }
 
-    fn parse_identifier_expr(&mut self) -> Option<Expr> {
-        match self.parse_identifier() {
-            Some(identifier) => Some(Expr::Identifier(identifier)),
-            None => None,
+    fn parse_list_item(&mut self) -> Option<Expr> {
+        if !self.current_token_is(&Token::Ellipsis) {
+            return self.parse_expr(Precedence::Lowest);
+        }
+        let start = self.curr_span.clone();
+        self.bump();
+        let spread = self.parse_expr(Precedence::Lowest)?;
+        let span = start.to(&self.curr_span);
+        Some(Expr::new(ExprKind::Spread(Box::new(spread)), span))
+    }
+
+    fn parse_struct_literal(&mut self) -> Option<Expr> {
+        let start = self.curr_span.clone();
+        let name = self.parse_identifier()?;
+        self.bump();
+
+        let mut fields: Vec<(Identifier, Expr)> = vec![];
+        while !self.next_token_is(&Token::Rbrace) {
+            self.bump();
+            let field = match self.curr_token.clone() {
+                Token::Identifier(field) => Identifier(field),
+                _ => {
+                    self.error_handler
+                        .set_identifier_error(&self.curr_token, self.curr_span.clone());
+                    return None;
+                }
+            };
+            if !self.bump_expected_next(&Token::Colon) {
+                return None;
+            }
+            self.bump();
+            let no_struct_literal = std::mem::replace(&mut self.no_struct_literal, false);
+            let value = self.parse_expr(Precedence::Lowest);
+            self.no_struct_literal = no_struct_literal;
+            fields.push((field, value?));
+
+            if !self.next_token_is(&Token::Comma) {
+                break;
+            }
+            self.bump();
+        }
+        if !self.bump_expected_next(&Token::Rbrace) {
+            return None;
         }
+
+        let span = start.to(&self.curr_span);
+        Some(Expr::new(ExprKind::StructLit(name, fields), span))
+    }
+
+    fn parse_identifier_expr(&mut self) -> Option<Expr> {
+        let identifier = self.parse_identifier()?;
+        Some(Expr::new(
+            ExprKind::Identifier(identifier),
+            self.curr_span.clone(),
+        ))
     }
 
     fn parse_string_expr(&self) -> Option<Expr> {
         match self.curr_token.clone() {
-            Token::String(val) => Some(Expr::Literal(Literal::String(val))),
+            Token::String(val) => self.make_literal(Literal::String(val)),
             _ => None,
         }
     }
 
-    fn parse_array_expr(&mut self) -> Option<Expr> {
-        let items = match self.parse_expr_list(Token::Rbracket) {
-            Some(items) => items,
-            None => return None,
+    fn parse_interpolated_string_expr(&mut self) -> Option<Expr> {
+        let parts = match self.curr_token.clone() {
+            Token::InterpolatedString(parts) => parts,
+            _ => return None,
         };
-        return Some(Expr::Literal(Literal::Array(items)));
+
+        let mut exprs: Vec<Expr> = vec![];
+        for part in parts {
+            let expr = match part {
+                StringPart::Text(text) => Expr::new(
+                    ExprKind::Literal(Literal::String(text)),
+                    self.curr_span.clone(),
+                ),
+                StringPart::Code(code, span) => self.parse_embedded_expr(&code, &span)?,
+            };
+            exprs.push(expr);
+        }
+
+        Some(Expr::new(
+            ExprKind::Interpolated(exprs),
+            self.curr_span.clone(),
+        ))
+    }
+
+    fn parse_embedded_expr(&mut self, code: &str, span: &Span) -> Option<Expr> {
+        let input = code.chars().collect::<Vec<char>>();
+        let mut l = Lexer::starting_at(&input, span);
+        let mut p = Parser::new(&mut l);
+
+        if p.current_token_is(&Token::Eof) && !p.has_errors() {
+            self.error_handler.set_error(
+                ParserErrorKind::SyntaxError,
+                "Empty expression in string interpolation".to_string(),
+                span.clone(),
+            );
+            return None;
+        }
+
+        let expr = p.parse_expr(Precedence::Lowest);
+        if expr.is_some() && !p.next_token_is(&Token::Eof) {
+            let token = p.next_token.clone();
+            p.error_handler
+                .set_unexpexted_token_error(&token, p.next_span.clone());
+        }
+
+        if p.has_errors() {
+            self.error_handler.add_errors(p.get_errors());
+            return None;
+        }
+        expr
+    }
+
+    fn parse_array_expr(&mut self) -> Option<Expr> {
+        let start = self.curr_span.clone();
+        let items = self.parse_expr_list(Token::Rbracket)?;
+        Some(Expr::new(
+            ExprKind::Literal(Literal::Array(items)),
+            start.to(&self.curr_span),
+        ))
+    }
+
+    fn parse_paren_expr(&mut self) -> Option<Expr> {
+        let start = self.curr_span.clone();
+        let mut items = self.parse_expr_list(Token::Rparen)?;
+        let span = start.to(&self.curr_span);
+        if let Some(spread) = items
+            .iter()
+            .find(|item| matches!(item.kind, ExprKind::Spread(_)))
+        {
+            self.error_handler.set_error(
+                ParserErrorKind::SyntaxError,
+                "Spread '...' is only allowed in calls and array literals".to_string(),
+                spread.span.clone(),
+            );
+            return None;
+        }
+        match items.len() {
+            0 => {
+                self.error_handler.set_error(
+                    ParserErrorKind::SyntaxError,
+                    "Expected an expression or a tuple inside '()'".to_string(),
+                    span,
+                );
+                None
+            }
+            1 => items.pop(),
+            _ => Some(Expr::new(ExprKind::Literal(Literal::Tuple(items)), span)),
+        }
+    }
+
+    fn parse_map_expr(&mut self) -> Option<Expr> {
+        let start = self.curr_span.clone();
+        let no_struct_literal = std::mem::replace(&mut self.no_struct_literal, false);
+        let entries = self.parse_map_entries();
+        self.no_struct_literal = no_struct_literal;
+        Some(Expr::new(
+            ExprKind::Literal(Literal::Map(entries?)),
+            start.to(&self.curr_span),
+        ))
+    }
+
+    fn parse_map_entries(&mut self) -> Option<Vec<(Expr, Expr)>> {
+        let mut entries: Vec<(Expr, Expr)> = vec![];
+        while !self.next_token_is(&Token::Rbrace) {
+            self.bump();
+            let key = self.parse_expr(Precedence::Lowest)?;
+            if !self.bump_expected_next(&Token::Colon) {
+                return None;
+            }
+            self.bump();
+            let value = self.parse_expr(Precedence::Lowest)?;
+            entries.push((key, value));
+            if !self.next_token_is(&Token::Comma) {
+                break;
+            }
+            self.bump();
+        }
+        if !self.bump_expected_next(&Token::Rbrace) {
+            return None;
+        }
+        Some(entries)
     }
 
     fn parse_type(&mut self) -> Option<ExprType> {
+        let type_ = self.parse_optional_type()?;
+        if !self.next_token_is(&Token::Pipe) {
+            return Some(type_);
+        }
+
+        let mut members = vec![type_];
+        while self.next_token_is(&Token::Pipe) {
+            self.bump();
+            self.bump();
+            let member = self.parse_optional_type()?;
+            if member == ExprType::Void || members[0] == ExprType::Void {
+                self.error_handler.set_error(
+                    ParserErrorKind::SyntaxError,
+                    "Type 'void' can't be part of a union".to_string(),
+                    self.curr_span.clone(),
+                );
+                return None;
+            }
+            if !members.contains(&member) {
+                members.push(member);
+            }
+        }
+        Some(ExprType::Union(members))
+    }
+
+    fn parse_optional_type(&mut self) -> Option<ExprType> {
+        let type_ = self.parse_required_type()?;
+        if !self.next_token_is(&Token::Question) {
+            return Some(type_);
+        }
+        self.bump();
+        if type_ == ExprType::Void {
+            self.error_handler.set_error(
+                ParserErrorKind::SyntaxError,
+                "Type 'void' can't be optional".to_string(),
+                self.curr_span.clone(),
+            );
+            return None;
+        }
+        Some(ExprType::Optional(Box::new(type_)))
+    }
+
+    fn parse_required_type(&mut self) -> Option<ExprType> {
         match self.curr_token {
             Token::TypeInt => Some(ExprType::Int),
             Token::TypeVoid => Some(ExprType::Void),
             Token::TypeFloat => Some(ExprType::Float),
             Token::TypeString => Some(ExprType::String),
             Token::TypeBoolean => Some(ExprType::Boolean),
+            Token::TypeFunction => Some(ExprType::Function(None)),
+            Token::Identifier(ref name) if self.type_params.contains(name) => {
+                Some(ExprType::TypeParam(name.clone()))
+            }
+            Token::Identifier(ref name) => Some(ExprType::Named(name.clone())),
+            Token::Lparen => self.parse_paren_type(),
             Token::ClassArray => {
-                let generic_type = match self.parse_generic_type() {
-                    Some(generic_type) => generic_type,
-                    None => return None,
-                };
+                let generic_type = self.parse_generic_type()?;
                 Some(ExprType::Array(Box::new(generic_type)))
             }
+            Token::ClassMap => self.parse_map_type(),
             _ => {
                 self.error_handler
-                    .set_not_type_annot_error(&self.curr_token);
+                    .set_not_type_annot_error(&self.curr_token, self.curr_span.clone());
+                None
+            }
+        }
+    }
+
+    fn parse_paren_type(&mut self) -> Option<ExprType> {
+        let start = self.curr_span.clone();
+        let mut items: Vec<ExprType> = vec![];
+        let mut void_span = None;
+        if self.next_token_is(&Token::Rparen) {
+            self.bump();
+        } else {
+            loop {
+                self.bump();
+                let item_type = self.parse_type()?;
+                if item_type == ExprType::Void {
+                    void_span = Some(self.curr_span.clone());
+                }
+                items.push(item_type);
+                if !self.next_token_is(&Token::Comma) {
+                    break;
+                }
+                self.bump();
+            }
+            if !self.bump_expected_next(&Token::Rparen) {
                 return None;
             }
         }
+
+        if !self.next_token_is(&Token::Arrow) {
+            let msg = match void_span {
+                _ if items.len() < 2 => "A tuple needs at least two items",
+                Some(_) => "Tuple items can't be of type 'void'",
+                None => return Some(ExprType::Tuple(items)),
+            };
+            self.error_handler.set_error(
+                ParserErrorKind::SyntaxError,
+                msg.to_string(),
+                void_span.unwrap_or(start.to(&self.curr_span)),
+            );
+            return None;
+        }
+        if let Some(span) = void_span {
+            self.error_handler.set_error(
+                ParserErrorKind::SyntaxError,
+                "Function parameter can't not be of type 'void'".to_string(),
+                span,
+            );
+            return None;
+        }
+        let params = items;
+
+        self.bump();
+        self.bump();
+        let return_type = self.parse_type()?;
+        Some(ExprType::Function(Some((params, Box::new(return_type)))))
     }
 
     fn parse_generic_type(&mut self) -> Option<ExprType> {
@@ -362,30 +964,64 @@ impl<'a> Parser<'a> {
             return None;
         }
         self.bump();
-        let generic_type = match self.parse_type() {
-            Some(expr_type) => expr_type,
-            None => return None,
-        };
+        let generic_type = self.parse_type()?;
         if !self.bump_expected_next(&Token::GratherThan) {
             return None;
         }
         Some(generic_type)
     }
 
+    fn parse_map_type(&mut self) -> Option<ExprType> {
+        if !self.bump_expected_next(&Token::LessThan) {
+            return None;
+        }
+        self.bump();
+        let key_type = self.parse_type()?;
+        if !matches!(
+            key_type,
+            ExprType::Int | ExprType::String | ExprType::Boolean
+        ) {
+            self.error_handler.set_error(
+                ParserErrorKind::SyntaxError,
+                "Map keys must be of type 'int', 'string' or 'boolean'".to_string(),
+                self.curr_span.clone(),
+            );
+            return None;
+        }
+        if !self.bump_expected_next(&Token::Comma) {
+            return None;
+        }
+        self.bump();
+        let value_type = self.parse_type()?;
+        if !self.bump_expected_next(&Token::GratherThan) {
+            return None;
+        }
+        Some(ExprType::Map(Box::new(key_type), Box::new(value_type)))
+    }
+
     fn token_to_precedence(token: &Token) -> Precedence {
         match token {
             Token::Plus | Token::Minus => Precedence::Sum,
             Token::Asterisk | Token::Slash | Token::Percet => Precedence::Product,
-            Token::Lparen => Precedence::Call,
-            Token::Equal => Precedence::Assign,
+            Token::Lparen | Token::Lbracket => Precedence::Call,
+            Token::Equal
+            | Token::PlusEqual
+            | Token::MinusEqual
+            | Token::AsteriskEqual
+            | Token::SlashEqual
+            | Token::PercentEqual => Precedence::Assign,
+            Token::DoubleQuestion => Precedence::Coalesce,
+            Token::Or => Precedence::Or,
+            Token::And => Precedence::And,
             Token::DoubleEqual
             | Token::NotEqual
             | Token::LessThan
             | Token::LessOrEqual
             | Token::GratherThan
-            | Token::GratherOrEqual => Precedence::Comparison,
+            | Token::GratherOrEqual
+            | Token::Is => Precedence::Comparison,
             Token::DoublePlus | Token::DoubleMinus => Precedence::Postfix,
-            Token::Dot => Precedence::FieldAcc,
+            Token::Dot | Token::QuestionDot => Precedence::FieldAcc,
             _ => Precedence::Lowest,
         }
     }
@@ -403,8 +1039,11 @@ impl<'a> Parser<'a> {
             self.bump();
             return true;
         }
-        self.error_handler
-            .set_expected_but_provided_error(token, &self.next_token);
+        self.error_handler.set_expected_but_provided_error(
+            token,
+            &self.next_token,
+            self.next_span.clone(),
+        );
         false
     }
 
@@ -416,11 +1055,676 @@ impl<'a> Parser<'a> {
         self.next_token == *token
     }
 
-    pub fn get_error(&self) -> Option<ParserError> {
-        self.error_handler.get_error()
+    pub fn get_errors(&self) -> Vec<ParserError> {
+        self.error_handler.get_errors()
     }
 
-    pub fn has_error(&self) -> bool {
+    pub fn has_errors(&self) -> bool {
         self.error_handler.has_error()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::super::ast::{
+        Binding, Else, Expr, ExprKind, ExprType, Identifier, Infix, Literal, Pattern, Prefix,
+        Program, StmtKind,
+    };
+    use super::super::lexer::Lexer;
+    use super::error_handler::ParserError;
+    use super::Parser;
+
+    fn parse(input: &str) -> (Program, Vec<ParserError>) {
+        let chars = input.chars().collect::<Vec<char>>();
+        let mut l = Lexer::new(&chars, "test.fl");
+        let mut p = Parser::new(&mut l);
+        let program = p.parse();
+        (program, p.get_errors())
+    }
+
+    #[test]
+    fn test_reports_every_syntax_error() {
+        let input = r#"
+let a = 0b12
+let b: = 2
+
+define f(x: int): int {
+    let y = x +
+    if y > { return y }
+    return y
+}
+
+let ok = "fine"
+}
+let c = [1 + }
+let last = [ok, ok
+"#;
+        let (program, errors) = parse(input);
+
+        let errors = errors
+            .iter()
+            .map(|err| {
+                let location = err.report(input).lines().nth(1).unwrap().trim().to_string();
+                format!("{} {}", location, err)
+            })
+            .collect::<Vec<String>>();
+
+        assert_eq!(
+            vec![
+                "--> test.fl:2:9 [Syntax Error]: Invalid base 2 literal '0b12'",
+                "--> test.fl:3:8 [Syntax Error]: Invalid type: =",
+                "--> test.fl:7:5 [Syntax Error]: unexpected if",
+                "--> test.fl:7:12 [Syntax Error]: unexpected {",
+                "--> test.fl:12:1 [Syntax Error]: unexpected }",
+                "--> test.fl:13:14 [Syntax Error]: unexpected }",
+                "--> test.fl:15:1 [Syntax Error]: expected ']' but provided 'EOF'",
+            ],
+            errors
+        );
+
+        assert_eq!(2, program.len());
+    }
+
+    #[test]
+    fn test_labelled_loops() {
+        let input = r#"
+outer: while true {
+    loop { break outer }
+    continue
+}
+break
+inner
+"#;
+        let (program, errors) = parse(input);
+        assert!(errors.is_empty());
+
+        let StmtKind::While { block, label, .. } = &program[0].kind else {
+            panic!("expected a while loop");
+        };
+        assert_eq!(Some("outer"), label.as_deref());
+
+        let StmtKind::While {
+            block: inner,
+            label,
+            ..
+        } = &block[0].kind
+        else {
+            panic!("expected `loop` lowered to a while loop");
+        };
+        assert_eq!(None, *label);
+        assert!(matches!(&inner[0].kind, StmtKind::Break(Some(l)) if l == "outer"));
+        assert!(matches!(block[1].kind, StmtKind::Continue(None)));
+
+        assert!(matches!(program[1].kind, StmtKind::Break(None)));
+        assert!(matches!(program[2].kind, StmtKind::Expr(_)));
+    }
+
+    #[test]
+    fn test_logical_operators_precedence() {
+        let input = "a || b && not c == d";
+        let (program, errors) = parse(input);
+        assert!(errors.is_empty());
+
+        let StmtKind::Expr(expr) = &program[0].kind else {
+            panic!("expected an expression");
+        };
+        let ExprKind::Infix(_, Infix::Or, rhs) = &expr.kind else {
+            panic!("expected `||` at the top");
+        };
+        let ExprKind::Infix(_, Infix::And, rhs) = &rhs.kind else {
+            panic!("expected `&&` to bind tighter than `||`");
+        };
+        let ExprKind::Prefix(Prefix::Not, operand) = &rhs.kind else {
+            panic!("expected `not` below `&&`");
+        };
+        assert!(matches!(operand.kind, ExprKind::Infix(_, Infix::Equal, _)));
+    }
+
+    #[test]
+    fn test_else_if_chain() {
+        let input = "if a { } else if b { } else if c { } else { x }";
+        let (program, errors) = parse(input);
+        assert!(errors.is_empty());
+        assert_eq!(1, program.len());
+
+        let mut stmt = &program[0];
+        let mut branches = 0;
+        loop {
+            let StmtKind::If { alternative, .. } = &stmt.kind else {
+                panic!("expected an if statement");
+            };
+            branches += 1;
+            match alternative {
+                Some(Else::If(next)) => stmt = next,
+                Some(Else::Block(block)) => {
+                    assert_eq!(1, block.len());
+                    break;
+                }
+                None => panic!("expected the chain to end with an else block"),
+            }
+        }
+        assert_eq!(3, branches);
+    }
+
+    #[test]
+    fn test_compound_assignment_desugars() {
+        let input = "total *= 2 + 1\n--count";
+        let (program, errors) = parse(input);
+        assert!(errors.is_empty());
+
+        let StmtKind::Expr(expr) = &program[0].kind else {
+            panic!("expected an expression");
+        };
+        let ExprKind::Assign(target, value) = &expr.kind else {
+            panic!("expected `*=` to become an assignment");
+        };
+        assert_eq!("total", target.0);
+        let ExprKind::Infix(lhs, Infix::Multiply, rhs) = &value.kind else {
+            panic!("expected `total * (2 + 1)`");
+        };
+        assert!(matches!(&lhs.kind, ExprKind::Identifier(name) if name.0 == "total"));
+        assert!(matches!(rhs.kind, ExprKind::Infix(_, Infix::Plus, _)));
+
+        let StmtKind::Expr(expr) = &program[1].kind else {
+            panic!("expected an expression");
+        };
+        assert!(matches!(expr.kind, ExprKind::Prefix(Prefix::Decrement, _)));
+    }
+
+    #[test]
+    fn test_index_and_slice_exprs() {
+        let input = "grid[y][x] = s[1..n]\nxs[..2]\n[1, 2]";
+        let (program, errors) = parse(input);
+        assert!(errors.is_empty());
+        assert_eq!(3, program.len());
+
+        let StmtKind::Expr(expr) = &program[0].kind else {
+            panic!("expected an expression");
+        };
+        let ExprKind::IndexAssign(target, _, value) = &expr.kind else {
+            panic!("expected an index assignment");
+        };
+        assert!(matches!(target.kind, ExprKind::Index(..)));
+        assert!(matches!(value.kind, ExprKind::Slice(_, Some(_), Some(_))));
+
+        let StmtKind::Expr(expr) = &program[1].kind else {
+            panic!("expected an expression");
+        };
+        assert!(matches!(expr.kind, ExprKind::Slice(_, None, Some(_))));
+    }
+
+    #[test]
+    fn test_anonymous_functions() {
+        let input = "define twice(f: function): function { return define(x: int): int { return f(f(x)) } }\ndefine(x: int): void { }(1)";
+        let (program, errors) = parse(input);
+        assert!(errors.is_empty());
+        assert_eq!(2, program.len());
+
+        let StmtKind::Func(_, _, body, _) = &program[0].kind else {
+            panic!("expected a function declaration");
+        };
+        let StmtKind::Return(Some(expr)) = &body[0].kind else {
+            panic!("expected a return");
+        };
+        assert!(matches!(&expr.kind, ExprKind::Func(params, _, _) if params.len() == 1));
+
+        let StmtKind::Expr(expr) = &program[1].kind else {
+            panic!("expected an expression statement");
+        };
+        let ExprKind::Call(callee, _) = &expr.kind else {
+            panic!("expected the function to be called");
+        };
+        assert!(matches!(callee.kind, ExprKind::Func(..)));
+    }
+
+    #[test]
+    fn test_function_types() {
+        let input = "let apply: ((int) -> int, int) -> void";
+        let (program, errors) = parse(input);
+        assert!(errors.is_empty());
+
+        let StmtKind::Let(_, Some(type_), None, _) = &program[0].kind else {
+            panic!("expected an annotated declaration");
+        };
+        let callback = ExprType::Function(Some((vec![ExprType::Int], Box::new(ExprType::Int))));
+        assert_eq!(
+            ExprType::Function(Some((
+                vec![callback, ExprType::Int],
+                Box::new(ExprType::Void)
+            ))),
+            *type_
+        );
+    }
+
+    #[test]
+    fn test_structs() {
+        let input = r#"
+struct Point {
+    x: float, y: float
+    define norm(self): float { return self.x }
+}
+if origin { p.x = Point { x: 0.0, y: 1.0 }.norm() }
+"#;
+        let (program, errors) = parse(input);
+        assert!(errors.is_empty());
+
+        let StmtKind::Struct {
+            fields, methods, ..
+        } = &program[0].kind
+        else {
+            panic!("expected a struct declaration");
+        };
+        assert_eq!(2, fields.len());
+        assert_eq!(
+            ExprType::Named("Point".to_string()),
+            methods[0].params[0].type_,
+            "`self` gets the struct type"
+        );
+
+        let StmtKind::If { consequence, .. } = &program[1].kind else {
+            panic!("expected an if statement");
+        };
+        let StmtKind::Expr(expr) = &consequence[0].kind else {
+            panic!("expected an expression");
+        };
+        let ExprKind::FieldAssign(_, field, value) = &expr.kind else {
+            panic!("expected a field assignment");
+        };
+        assert_eq!("x", field.0);
+        assert!(matches!(&value.kind, ExprKind::FieldAcc(lit, _)
+            if matches!(lit.kind, ExprKind::StructLit(..))));
+    }
+
+    #[test]
+    fn test_enums_and_match() {
+        let input = r#"
+let a = match s {
+    Shape.Rect(w, _) if w > 1.0 => w,
+    -5..5 => 0.0,
+    n => n
+}
+enum Shape { Circle(float), Rect(float, float), Empty }
+let b = match s { Shape.Circle(r) => r, Shape.Rect(w, h) => w * h }
+let c = match ok { true => 1, false => 0 }
+let d = match n { 0 => 1 }
+"#;
+        let (program, errors) = parse(input);
+
+        let StmtKind::Let(_, _, Some(expr), _) = &program[0].kind else {
+            panic!("expected a let statement");
+        };
+        let ExprKind::Match(_, arms) = &expr.kind else {
+            panic!("expected a match expression");
+        };
+        assert!(
+            matches!(&arms[0].pattern, Pattern::Variant(_, variant, payload)
+            if variant.0 == "Rect" && matches!(payload[1], Pattern::Wildcard))
+        );
+        assert!(arms[0].guard.is_some());
+        assert!(matches!(arms[1].pattern, Pattern::Range(-5, 5)));
+        assert!(matches!(&arms[2].pattern, Pattern::Binding(name) if name == "n"));
+
+        let StmtKind::Enum { variants, .. } = &program[1].kind else {
+            panic!("expected an enum declaration");
+        };
+        assert_eq!(3, variants.len());
+        assert_eq!(2, variants[1].1.len());
+
+        let errors = errors
+            .iter()
+            .map(|err| err.to_string())
+            .collect::<Vec<String>>();
+        assert_eq!(
+            vec![
+                "[Match Error]: Non-exhaustive match, 'Shape.Empty' is not handled",
+                "[Match Error]: Non-exhaustive match, add a '_' arm",
+            ],
+            errors
+        );
+    }
+
+    #[test]
+    fn test_maps() {
+        let input = r#"
+let m: Map<string, Array<int>> = {"a": [1], "b": []}
+for k, v in m { m[k] = {}["k"] }
+let bad: Map<float, int> = {}
+"#;
+        let (program, errors) = parse(input);
+
+        let StmtKind::Let(_, Some(type_), Some(expr), _) = &program[0].kind else {
+            panic!("expected a typed let statement");
+        };
+        assert_eq!(
+            &ExprType::Map(
+                Box::new(ExprType::String),
+                Box::new(ExprType::Array(Box::new(ExprType::Int)))
+            ),
+            type_
+        );
+        assert!(
+            matches!(&expr.kind, ExprKind::Literal(Literal::Map(entries)) if entries.len() == 2)
+        );
+
+        let StmtKind::ForLoop {
+            cursor,
+            value_cursor,
+            iterable,
+            block,
+            ..
+        } = &program[1].kind
+        else {
+            panic!("expected a for loop");
+        };
+        assert!(matches!(&cursor.binding, Binding::Name(name) if name == "k"));
+        assert!(
+            matches!(value_cursor, Some(cursor) if matches!(&cursor.binding, Binding::Name(name) if name == "v"))
+        );
+        assert!(matches!(&iterable.kind, ExprKind::Identifier(_)));
+        assert_eq!(1, block.len());
+
+        let errors = errors
+            .iter()
+            .map(|err| err.to_string())
+            .collect::<Vec<String>>();
+        assert_eq!(
+            vec!["[Syntax Error]: Map keys must be of type 'int', 'string' or 'boolean'"],
+            errors
+        );
+    }
+
+    #[test]
+    fn test_tuples() {
+        let input = r#"
+define divmod(a: int, b: int): (int, int) { return (a / b, a % b) }
+let (q, (r, _)) = (1, ((2 + 3) * 4, 5))
+for (i, x) in pairs {}
+let f: (int) -> (int, int) = divmod
+"#;
+        let (program, errors) = parse(input);
+        assert!(errors.is_empty());
+
+        let StmtKind::Func(_, _, _, ret_type) = &program[0].kind else {
+            panic!("expected a function declaration");
+        };
+        assert_eq!(
+            &Some(ExprType::Tuple(vec![ExprType::Int, ExprType::Int])),
+            ret_type
+        );
+
+        let StmtKind::LetTuple(names, _, expr, _) = &program[1].kind else {
+            panic!("expected a destructuring let");
+        };
+        assert!(matches!(&names[1], Binding::Tuple(inner) if inner.len() == 2));
+        let ExprKind::Literal(Literal::Tuple(items)) = &expr.kind else {
+            panic!("expected a tuple literal");
+        };
+        assert!(
+            matches!(&items[1].kind, ExprKind::Literal(Literal::Tuple(inner))
+            if matches!(inner[0].kind, ExprKind::Infix(_, Infix::Multiply, _)))
+        );
+
+        let StmtKind::ForLoop { cursor, .. } = &program[2].kind else {
+            panic!("expected a for loop");
+        };
+        assert!(matches!(&cursor.binding, Binding::Tuple(names) if names.len() == 2));
+
+        let StmtKind::Let(_, Some(ExprType::Function(Some((_, ret_type)))), _, _) =
+            &program[3].kind
+        else {
+            panic!("expected a typed let statement");
+        };
+        assert!(matches!(**ret_type, ExprType::Tuple(_)));
+    }
+
+    #[test]
+    fn test_default_params_and_named_args() {
+        let input = r#"
+define greet(name: string, greeting: string = "Hello"): string { return greeting + name }
+greet("Ana", greeting: "Hi")
+greet(greeting: "Hi", "Ana")
+define bad(a: int = 1, b: int): void {}
+"#;
+        let (program, errors) = parse(input);
+
+        let StmtKind::Func(_, params, _, _) = &program[0].kind else {
+            panic!("expected a function declaration");
+        };
+        assert!(params[0].default.is_none());
+        assert!(matches!(
+            &params[1].default,
+            Some(Expr {
+                kind: ExprKind::Literal(Literal::String(_)),
+                ..
+            })
+        ));
+
+        let StmtKind::Expr(Expr {
+            kind: ExprKind::Call(_, args),
+            ..
+        }) = &program[1].kind
+        else {
+            panic!("expected a call");
+        };
+        assert!(args[0].name.is_none());
+        assert!(matches!(&args[1].name, Some(Identifier(name)) if name == "greeting"));
+
+        // a positional arg after a named one and a required param after an
+        // optional one
+        assert_eq!(2, errors.len());
+    }
+
+    #[test]
+    fn test_rest_params_and_spread() {
+        let input = r#"
+define log(level: int, parts: ...string): void {}
+log(1, ...xs, "a")
+let ys = [...xs, 1]
+define bad(parts: ...string, level: int): void {}
+"#;
+        let (program, errors) = parse(input);
+
+        let StmtKind::Func(_, params, _, _) = &program[0].kind else {
+            panic!("expected a function declaration");
+        };
+        assert!(!params[0].rest && params[1].rest);
+        assert_eq!(ExprType::Array(Box::new(ExprType::String)), params[1].type_);
+
+        let StmtKind::Expr(Expr {
+            kind: ExprKind::Call(_, args),
+            ..
+        }) = &program[1].kind
+        else {
+            panic!("expected a call");
+        };
+        assert!(matches!(args[1].value.kind, ExprKind::Spread(_)));
+
+        let StmtKind::Let(_, _, Some(value), _) = &program[2].kind else {
+            panic!("expected a let statement");
+        };
+        assert!(
+            matches!(&value.kind, ExprKind::Literal(Literal::Array(items))
+            if matches!(items[0].kind, ExprKind::Spread(_)))
+        );
+
+        assert_eq!(1, errors.len());
+    }
+
+    #[test]
+    fn test_generic_functions() {
+        let input = r#"
+define first<T>(xs: Array<T>): T { return xs[0] }
+let apply = define<A, B>(x: A, f: (A) -> B): B { return f(x) }
+define unused<T>(x: int): T { return x }
+define twice<T, T>(x: T): T { return x }
+"#;
+        let (program, errors) = parse(input);
+
+        let StmtKind::Func(_, params, _, ret_type) = &program[0].kind else {
+            panic!("expected a function declaration");
+        };
+        let param_type = ExprType::TypeParam("T".to_string());
+        assert_eq!(
+            ExprType::Array(Box::new(param_type.clone())),
+            params[0].type_
+        );
+        assert_eq!(&Some(param_type), ret_type);
+
+        let StmtKind::Let(_, _, Some(value), _) = &program[1].kind else {
+            panic!("expected a let statement");
+        };
+        assert!(
+            matches!(&value.kind, ExprKind::Func(params, _, Some(ExprType::TypeParam(ret)))
+            if ret == "B" && matches!(params[1].type_, ExprType::Function(Some(_))))
+        );
+
+        // not inferable from the args and declared twice
+        assert_eq!(2, errors.len());
+    }
+
+    #[test]
+    fn test_optional_types() {
+        let input = r#"
+let age: int? = null
+let names: Array<string?>? = null
+let name = person?.boss?.name ?? "nobody" || other
+define f(): void? {}
+"#;
+        let (program, errors) = parse(input);
+
+        let StmtKind::Let(_, Some(type_), _, _) = &program[0].kind else {
+            panic!("expected a typed let statement");
+        };
+        assert_eq!(&ExprType::Optional(Box::new(ExprType::Int)), type_);
+
+        let StmtKind::Let(_, Some(ExprType::Optional(array)), _, _) = &program[1].kind else {
+            panic!("expected an optional array");
+        };
+        assert!(
+            matches!(&**array, ExprType::Array(items) if matches!(**items, ExprType::Optional(_)))
+        );
+
+        let StmtKind::Let(_, _, Some(value), _) = &program[2].kind else {
+            panic!("expected a let statement");
+        };
+        let ExprKind::Infix(lhs, Infix::NullCoalescing, rhs) = &value.kind else {
+            panic!("expected '??'");
+        };
+        assert!(matches!(lhs.kind, ExprKind::OptionalFieldAcc(..)));
+        assert!(matches!(rhs.kind, ExprKind::Infix(_, Infix::Or, _)));
+
+        assert_eq!(1, errors.len());
+    }
+
+    #[test]
+    fn test_union_types_and_aliases() {
+        let input = r#"
+type Id = int | string?
+let ok = id is string && id is Id
+let bad: int | void = 1
+"#;
+        let (program, errors) = parse(input);
+
+        let StmtKind::TypeAlias { name, type_ } = &program[0].kind else {
+            panic!("expected a type alias");
+        };
+        assert_eq!("Id", name.0);
+        assert_eq!(
+            &ExprType::Union(vec![
+                ExprType::Int,
+                ExprType::Optional(Box::new(ExprType::String))
+            ]),
+            type_
+        );
+
+        let StmtKind::Let(_, _, Some(value), _) = &program[1].kind else {
+            panic!("expected a let statement");
+        };
+        let ExprKind::Infix(lhs, Infix::And, rhs) = &value.kind else {
+            panic!("expected '&&'");
+        };
+        assert!(matches!(&lhs.kind, ExprKind::Is(_, ExprType::String)));
+        assert!(matches!(&rhs.kind, ExprKind::Is(_, ExprType::Named(_))));
+
+        assert_eq!(1, errors.len());
+    }
+
+    #[test]
+    fn test_const_and_mutable_bindings() {
+        let input = r#"
+const MAX = 10
+const LIMIT: int = MAX * 2
+let mut count = 0
+let mut (q, r) = (1, 2)
+define down(mut n: int, step: int): int { return n - step }
+const BAD = {"a": count + 1}
+if true { const INNER = 1 }
+"#;
+        let (program, errors) = parse(input);
+
+        assert!(matches!(&program[0].kind, StmtKind::Const(_, None, _)));
+        assert!(matches!(
+            &program[1].kind,
+            StmtKind::Const(_, Some(ExprType::Int), _)
+        ));
+        assert!(matches!(&program[2].kind, StmtKind::Let(_, _, _, true)));
+        assert!(matches!(
+            &program[3].kind,
+            StmtKind::LetTuple(_, _, _, true)
+        ));
+
+        let StmtKind::Func(_, params, _, _) = &program[4].kind else {
+            panic!("expected a function");
+        };
+        assert!(params[0].is_mut);
+        assert!(!params[1].is_mut);
+
+        // a const can't read variables nor be declared inside a block, the
+        // first doesn't keep the rest of the statement from being parsed
+        let errors = errors
+            .iter()
+            .map(|err| err.to_string())
+            .collect::<Vec<String>>();
+        assert_eq!(
+            vec![
+                "[Semantic Error]: Const 'BAD' can only be set from literals, operators and other consts",
+                "[Syntax Error]: 'const' is only allowed at the top level, use 'let' inside blocks",
+            ],
+            errors
+        );
+        assert!(matches!(&program[5].kind, StmtKind::Const(..)));
+    }
+
+    #[test]
+    fn test_typed_for_cursors() {
+        let input = r#"
+for f: string in fruits {}
+for i, x: Array<int> in rows {}
+"#;
+        let (program, errors) = parse(input);
+        assert!(errors.is_empty());
+
+        let StmtKind::ForLoop {
+            cursor,
+            value_cursor: None,
+            ..
+        } = &program[0].kind
+        else {
+            panic!("expected a for loop with one cursor");
+        };
+        assert!(matches!(&cursor.binding, Binding::Name(name) if name == "f"));
+        assert!(matches!(cursor.type_, Some(ExprType::String)));
+
+        let StmtKind::ForLoop {
+            cursor,
+            value_cursor: Some(value_cursor),
+            ..
+        } = &program[1].kind
+        else {
+            panic!("expected a for loop with two cursors");
+        };
+        assert!(cursor.type_.is_none());
+        assert!(matches!(&value_cursor.binding, Binding::Name(name) if name == "x"));
+        assert!(matches!(value_cursor.type_, Some(ExprType::Array(_))));
+    }
+}
@@ -0,0 +1,64 @@
+use crate::frontend::ast::{ExprType, Identifier, StmtKind};
+use crate::frontend::parser::{Parser, ParserErrorKind};
+use crate::frontend::token::Token;
+
+pub fn parse_enum_stmt(p: &mut Parser) -> Option<StmtKind> {
+    p.bump();
+    let name = match p.parse_identifier() {
+        Some(identifier) => identifier,
+        None => {
+            p.error_handler
+                .set_identifier_error(&p.curr_token, p.curr_span.clone());
+            return None;
+        }
+    };
+
+    if !p.bump_expected_next(&Token::Lbrace) {
+        return None;
+    }
+
+    let mut variants: Vec<(Identifier, Vec<ExprType>)> = vec![];
+    while !p.next_token_is(&Token::Rbrace) {
+        p.bump();
+        let span = p.curr_span.clone();
+        let variant = match p.parse_identifier() {
+            Some(identifier) => identifier,
+            None => {
+                p.error_handler.set_identifier_error(&p.curr_token, span);
+                return None;
+            }
+        };
+        if variants.iter().any(|(other, _)| other.0 == variant.0) {
+            p.error_handler.set_error(
+                ParserErrorKind::SyntaxError,
+                format!("'{}' is already declared in '{}'", variant.0, name.0),
+                span,
+            );
+            return None;
+        }
+
+        let mut payload: Vec<ExprType> = vec![];
+        if p.next_token_is(&Token::Lparen) {
+            p.bump();
+            loop {
+                p.bump();
+                payload.push(p.parse_type()?);
+                if !p.next_token_is(&Token::Comma) {
+                    break;
+                }
+                p.bump();
+            }
+            if !p.bump_expected_next(&Token::Rparen) {
+                return None;
+            }
+        }
+        variants.push((variant, payload));
+
+        if p.next_token_is(&Token::Comma) {
+            p.bump();
+        }
+    }
+    p.bump();
+
+    Some(StmtKind::Enum { name, variants })
+}
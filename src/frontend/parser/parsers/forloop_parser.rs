@@ -1,41 +1,52 @@
+use super::let_parser::parse_binding;
 use crate::frontend::{
-    ast::{Identifier, Precedence, Stmt},
+    ast::{Cursor, StmtKind},
     parser::Parser,
     token::Token,
 };
 
-pub fn parse_forloop_stmt(p: &mut Parser) -> Option<Stmt> {
+pub fn parse_forloop_stmt(p: &mut Parser, label: Option<String>) -> Option<StmtKind> {
     p.bump();
-    let loop_cursor_name = match p.parse_identifier() {
-        Some(identifier) => {
-            let Identifier(name) = identifier;
-            name
-        }
-        None => {
-            p.error_handler.set_identifier_error(&p.curr_token);
-            return None;
-        }
-    };
+    let cursor = parse_cursor(p)?;
+
+    let mut value_cursor = None;
+    if p.next_token_is(&Token::Comma) {
+        p.bump();
+        p.bump();
+        value_cursor = Some(Box::new(parse_cursor(p)?));
+    }
 
     if !p.bump_expected_next(&Token::In) {
         return None;
     }
 
     p.bump();
-    let iterable = match p.parse_expr(Precedence::Lowest) {
-        Some(expr) => expr,
-        None => return None,
-    };
+    let iterable = p.parse_condition()?;
     p.bump();
 
-    let block = match p.parse_block_stmt() {
-        Some(block) => block,
-        None => return None,
-    };
+    let block = p.parse_block_stmt()?;
 
-    Some(Stmt::ForLoop {
-        cursor: loop_cursor_name,
+    Some(StmtKind::ForLoop {
+        cursor,
+        value_cursor,
         iterable,
         block,
+        label,
+    })
+}
+
+fn parse_cursor(p: &mut Parser) -> Option<Cursor> {
+    let start = p.curr_span.clone();
+    let binding = parse_binding(p)?;
+    let mut type_ = None;
+    if p.next_token_is(&Token::Colon) {
+        p.bump();
+        p.bump();
+        type_ = Some(p.parse_type()?);
+    }
+    Some(Cursor {
+        binding,
+        type_,
+        span: start.to(&p.curr_span),
     })
 }
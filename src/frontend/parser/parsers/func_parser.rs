@@ -1,104 +1,294 @@
-use crate::frontend::ast::{ExprType, Identifier, Stmt};
+use crate::frontend::ast::{
+    BlockStmt, Expr, ExprKind, ExprType, Identifier, Method, Param, Precedence, StmtKind,
+};
 use crate::frontend::parser::{Parser, ParserErrorKind};
+use crate::frontend::span::Span;
 use crate::frontend::token::Token;
 
-pub fn parse_func_stmt(p: &mut Parser) -> Option<Stmt> {
+pub fn parse_func_stmt(p: &mut Parser) -> Option<StmtKind> {
+    let fn_name = parse_func_name(p)?;
+    let (fn_params, body, return_type) = parse_func_rest(p, None)?;
+    Some(StmtKind::Func(fn_name, fn_params, body, return_type))
+}
+
+pub fn parse_method(p: &mut Parser, receiver: &ExprType) -> Option<Method> {
+    let start = p.curr_span.clone();
+    let name = parse_func_name(p)?;
+    let (params, body, ret_type) = parse_func_rest(p, Some(receiver))?;
+    Some(Method {
+        name,
+        params,
+        body,
+        ret_type,
+        span: start.to(&p.curr_span),
+    })
+}
+
+fn parse_func_name(p: &mut Parser) -> Option<Identifier> {
     let fn_name = match p.next_token.clone() {
         Token::Identifier(name) => Identifier(name),
         _ => {
-            p.error_handler.set_identifier_error(&p.next_token);
+            p.error_handler
+                .set_identifier_error(&p.next_token, p.next_span.clone());
             return None;
         }
     };
     p.bump();
+    Some(fn_name)
+}
+
+pub fn parse_func_expr(p: &mut Parser) -> Option<Expr> {
+    let start = p.curr_span.clone();
+    let (fn_params, body, return_type) = parse_func_rest(p, None)?;
+    let span = start.to(&p.curr_span);
+    Some(Expr::new(
+        ExprKind::Func(fn_params, body, return_type),
+        span,
+    ))
+}
+
+type FuncParts = (Vec<Param>, BlockStmt, Option<ExprType>);
+
+fn parse_func_rest(p: &mut Parser, receiver: Option<&ExprType>) -> Option<FuncParts> {
+    let outer_type_params = p.type_params.len();
+    let parts = parse_generic_func_rest(p, receiver);
+    p.type_params.truncate(outer_type_params);
+    parts
+}
+
+fn parse_generic_func_rest(p: &mut Parser, receiver: Option<&ExprType>) -> Option<FuncParts> {
+    let type_params = match p.next_token_is(&Token::LessThan) {
+        true => parse_type_params(p)?,
+        false => vec![],
+    };
+    p.type_params
+        .extend(type_params.iter().map(|(Identifier(name), _)| name.clone()));
+
     if !p.bump_expected_next(&Token::Lparen) {
         return None;
     }
-    let fn_params = match parse_func_params(p) {
-        Some(params) => params,
-        None => return None,
+    let fn_params = parse_func_params(p, receiver)?;
+
+    // the type params are inferred from the args, so each must show up in
+    // the type of a param
+    for (Identifier(name), span) in type_params {
+        let used = fn_params
+            .iter()
+            .any(|param| mentions_type_param(&param.type_, &name));
+        if !used {
+            p.error_handler.set_error(
+                ParserErrorKind::SyntaxError,
+                format!("Type parameter '{}' isn't used by any parameter", name),
+                span,
+            );
+            return None;
+        }
+    }
+
+    let return_type = match p.next_token_is(&Token::Colon) {
+        true => {
+            p.bump();
+            p.bump();
+            Some(p.parse_type()?)
+        }
+        false => None,
     };
 
-    if !p.bump_expected_next(&Token::Colon) {
+    if !p.bump_expected_next(&Token::Lbrace) {
         return None;
     }
+    let body = p.parse_block_stmt()?;
+    Some((fn_params, body, return_type))
+}
 
+fn parse_type_params(p: &mut Parser) -> Option<Vec<(Identifier, Span)>> {
     p.bump();
+    let mut type_params: Vec<(Identifier, Span)> = vec![];
+    loop {
+        p.bump();
+        let name = match p.curr_token.clone() {
+            Token::Identifier(name) => name,
+            _ => {
+                p.error_handler
+                    .set_identifier_error(&p.curr_token, p.curr_span.clone());
+                return None;
+            }
+        };
+        let declared = p.type_params.contains(&name)
+            || type_params
+                .iter()
+                .any(|(Identifier(declared), _)| *declared == name);
+        if declared {
+            p.error_handler.set_error(
+                ParserErrorKind::SyntaxError,
+                format!("Type parameter '{}' is already declared", name),
+                p.curr_span.clone(),
+            );
+            return None;
+        }
+        type_params.push((Identifier(name), p.curr_span.clone()));
 
-    let return_type = match p.parse_type() {
-        Some(ret_type) => ret_type,
-        None => return None,
-    };
-
-    if !p.bump_expected_next(&Token::Lbrace) {
+        if !p.next_token_is(&Token::Comma) {
+            break;
+        }
+        p.bump();
+    }
+    if !p.bump_expected_next(&Token::GratherThan) {
         return None;
     }
-    let body = match p.parse_block_stmt() {
-        Some(block) => block,
-        None => return None,
-    };
-    Some(Stmt::Func(fn_name, fn_params, body, return_type))
+    Some(type_params)
 }
 
-fn parse_func_params(p: &mut Parser) -> Option<Vec<(Identifier, ExprType)>> {
-    let mut params: Vec<(Identifier, ExprType)> = vec![];
+fn mentions_type_param(type_: &ExprType, name: &str) -> bool {
+    match type_ {
+        ExprType::TypeParam(param) => param == name,
+        ExprType::Array(items_type) | ExprType::Optional(items_type) => {
+            mentions_type_param(items_type, name)
+        }
+        ExprType::Map(key_type, value_type) => {
+            mentions_type_param(key_type, name) || mentions_type_param(value_type, name)
+        }
+        ExprType::Tuple(items) | ExprType::Union(items) => {
+            items.iter().any(|item| mentions_type_param(item, name))
+        }
+        ExprType::Function(Some((params, return_type))) => {
+            params.iter().any(|param| mentions_type_param(param, name))
+                || mentions_type_param(return_type, name)
+        }
+        _ => false,
+    }
+}
+
+fn parse_func_params(p: &mut Parser, receiver: Option<&ExprType>) -> Option<Vec<Param>> {
+    let mut params: Vec<Param> = vec![];
+
+    if let Some(receiver) = receiver {
+        if p.next_token != Token::Identifier("self".to_string()) {
+            p.error_handler.set_error(
+                ParserErrorKind::SyntaxError,
+                "Methods must take 'self' as first parameter".to_string(),
+                p.next_span.clone(),
+            );
+            return None;
+        }
+        p.bump();
+        params.push(Param {
+            name: Identifier("self".to_string()),
+            type_: receiver.clone(),
+            default: None,
+            rest: false,
+            is_mut: false,
+            span: p.curr_span.clone(),
+        });
+        if !p.next_token_is(&Token::Comma) {
+            if !p.bump_expected_next(&Token::Rparen) {
+                return None;
+            }
+            return Some(params);
+        }
+        p.bump();
+    }
+
     if p.next_token_is(&Token::Rparen) {
         p.bump();
         return Some(params);
     }
-    p.bump();
-    let identifier = match p.parse_identifier() {
+
+    loop {
+        p.bump();
+        let param = parse_func_param(p)?;
+        if let Some(last) = params.last().filter(|last| last.rest) {
+            p.error_handler.set_error(
+                ParserErrorKind::SyntaxError,
+                format!("Rest parameter '{}' must be the last one", last.name.0),
+                p.curr_span.clone(),
+            );
+            return None;
+        }
+        let follows_default = params.last().is_some_and(|last| last.default.is_some());
+        if param.default.is_none() && !param.rest && follows_default {
+            p.error_handler.set_error(
+                ParserErrorKind::SyntaxError,
+                format!(
+                    "Parameter '{}' without a default value can't follow one with a default",
+                    param.name.0
+                ),
+                p.curr_span.clone(),
+            );
+            return None;
+        }
+        params.push(param);
+
+        if !p.next_token_is(&Token::Comma) {
+            break;
+        }
+        p.bump();
+    }
+    if !p.bump_expected_next(&Token::Rparen) {
+        return None;
+    }
+    Some(params)
+}
+
+fn parse_func_param(p: &mut Parser) -> Option<Param> {
+    let start = p.curr_span.clone();
+    let is_mut = p.current_token_is(&Token::Mut);
+    if is_mut {
+        p.bump();
+    }
+    let name = match p.parse_identifier() {
         Some(identifier) => identifier,
         _ => {
-            p.error_handler.set_identifier_error(&p.curr_token);
+            p.error_handler
+                .set_identifier_error(&p.curr_token, p.curr_span.clone());
             return None;
         }
     };
+    let span = start.to(&p.curr_span);
+    if !p.bump_expected_next(&Token::Colon) {
+        return None;
+    }
     p.bump();
-    p.bump();
-    let param_type = match p.parse_type() {
-        Some(type_) => type_,
-        None => return None,
-    };
+    let rest = p.current_token_is(&Token::Ellipsis);
+    if rest {
+        p.bump();
+    }
+    let mut type_ = p.parse_type()?;
 
-    if param_type == ExprType::Void {
+    if type_ == ExprType::Void {
         p.error_handler.set_error(
             ParserErrorKind::SyntaxError,
-            format!("Function parameter can't not be of type 'void'"),
+            "Function parameter can't not be of type 'void'".to_string(),
+            p.curr_span.clone(),
         );
         return None;
     }
 
-    params.push((identifier, param_type));
-
-    while p.next_token_is(&Token::Comma) {
+    let mut default = None;
+    if p.next_token_is(&Token::Equal) {
         p.bump();
         p.bump();
+        default = Some(p.parse_expr(Precedence::Lowest)?);
+    }
 
-        let identifier = match p.parse_identifier() {
-            Some(identifier) => identifier,
-            _ => {
-                p.error_handler.set_identifier_error(&p.curr_token);
-                return None;
-            }
-        };
-        p.bump();
-        p.bump();
-        let param_type = match p.parse_type() {
-            Some(type_) => type_,
-            None => return None,
-        };
-        if param_type == ExprType::Void {
+    if rest {
+        if default.is_some() {
             p.error_handler.set_error(
                 ParserErrorKind::SyntaxError,
-                format!("Function parameter can't not be of type 'void'"),
+                format!("Rest parameter '{}' can't have a default value", name.0),
+                p.curr_span.clone(),
             );
             return None;
         }
-        params.push((identifier, param_type));
-    }
-    if !p.bump_expected_next(&Token::Rparen) {
-        return None;
+        type_ = ExprType::Array(Box::new(type_));
     }
-    Some(params)
+
+    Some(Param {
+        name,
+        type_,
+        default,
+        rest,
+        is_mut,
+        span,
+    })
 }
@@ -1,36 +1,41 @@
 use super::super::super::{
-    ast::{BlockStmt, Precedence, Stmt},
+    ast::{Else, Stmt, StmtKind},
     parser::Parser,
     token::Token,
 };
 
-pub fn parse_if_stmt(p: &mut Parser) -> Option<Stmt> {
+pub fn parse_if_stmt(p: &mut Parser) -> Option<StmtKind> {
     p.bump();
 
-    let condition = match p.parse_expr(Precedence::Lowest) {
-        Some(expr) => expr,
-        None => return None,
-    };
+    let condition = p.parse_condition()?;
 
     p.bump();
 
-    let consequence = match p.parse_block_stmt() {
-        Some(block) => block,
-        None => return None,
-    };
+    let consequence = p.parse_block_stmt()?;
 
-    let alternative: Option<BlockStmt> = match p.next_token_is(&Token::Else) {
+    let alternative = match p.next_token_is(&Token::Else) {
         true => {
             p.bump();
             p.bump();
-            p.parse_block_stmt()
+            Some(parse_else(p)?)
         }
         false => None,
     };
 
-    Some(Stmt::If {
+    Some(StmtKind::If {
         condition,
         consequence,
         alternative,
     })
 }
+
+fn parse_else(p: &mut Parser) -> Option<Else> {
+    if p.curr_token != Token::If {
+        return p.parse_block_stmt().map(Else::Block);
+    }
+
+    let start = p.curr_span.clone();
+    let kind = parse_if_stmt(p)?;
+    let span = start.to(&p.curr_span);
+    Some(Else::If(Box::new(Stmt::new(kind, span))))
+}
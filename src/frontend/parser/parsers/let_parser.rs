@@ -1,50 +1,172 @@
+use super::super::super::span::Span;
 use super::super::super::token::Token;
-use super::super::{Identifier, Parser, Precedence, Stmt};
+use super::super::{
+    Binding, Expr, ExprKind, Identifier, Literal, Parser, ParserErrorKind, Precedence, StmtKind,
+};
+
+pub fn parse_let_stmt(p: &mut Parser) -> Option<StmtKind> {
+    let is_mut = p.next_token_is(&Token::Mut);
+    if is_mut {
+        p.bump();
+    }
+
+    if p.next_token_is(&Token::Lparen) {
+        p.bump();
+        return parse_let_tuple_stmt(p, is_mut);
+    }
 
-pub fn parse_let_stmt(p: &mut Parser) -> Option<Stmt> {
     let var_name = match p.next_token.clone() {
         Token::Identifier(val) => val,
         _ => {
-            p.error_handler.set_identifier_error(&p.next_token);
+            p.error_handler
+                .set_identifier_error(&p.next_token, p.next_span.clone());
             return None;
         }
     };
     p.bump();
 
+    let mut var_type = None;
     if p.next_token_is(&Token::Colon) {
         p.bump();
         p.bump();
+        var_type = Some(p.parse_type()?);
+    }
 
-        let var_type = match p.parse_type() {
-            Some(type_) => type_,
-            None => return None,
-        };
+    if !p.next_token_is(&Token::Equal) {
+        return Some(StmtKind::Let(Identifier(var_name), var_type, None, is_mut));
+    }
+
+    p.bump();
+    p.bump();
+    let expr = p.parse_expr(Precedence::Lowest)?;
 
-        if !p.next_token_is(&Token::Equal) {
-            return Some(Stmt::Let(Identifier(var_name), Some(var_type), None));
+    Some(StmtKind::Let(
+        Identifier(var_name),
+        var_type,
+        Some(expr),
+        is_mut,
+    ))
+}
+
+pub fn parse_const_stmt(p: &mut Parser) -> Option<StmtKind> {
+    if p.block_depth > 0 {
+        p.error_handler.set_error(
+            ParserErrorKind::SyntaxError,
+            "'const' is only allowed at the top level, use 'let' inside blocks".to_string(),
+            p.curr_span.clone(),
+        );
+        return None;
+    }
+    p.bump();
+    let name = match p.parse_identifier() {
+        Some(identifier) => identifier,
+        None => {
+            p.error_handler
+                .set_identifier_error(&p.curr_token, p.curr_span.clone());
+            return None;
         }
+    };
 
+    let mut const_type = None;
+    if p.next_token_is(&Token::Colon) {
         p.bump();
         p.bump();
-        let expr = match p.parse_expr(Precedence::Lowest) {
-            Some(expr) => expr,
-            None => return None,
-        };
+        const_type = Some(p.parse_type()?);
+    }
 
-        return Some(Stmt::Let(Identifier(var_name), Some(var_type), Some(expr)));
+    if !p.bump_expected_next(&Token::Equal) {
+        return None;
     }
+    p.bump();
+    let expr = p.parse_expr(Precedence::Lowest)?;
 
-    if !p.next_token_is(&Token::Equal) {
-        return Some(Stmt::Let(Identifier(var_name), None, None));
+    if let Some(span) = first_non_constant(p, &expr) {
+        p.error_handler.set_semantic_error(
+            format!(
+                "Const '{}' can only be set from literals, operators and other consts",
+                name.0
+            ),
+            span,
+        );
     }
+    p.consts.push(name.0.clone());
 
-    p.bump();
-    p.bump();
+    Some(StmtKind::Const(name, const_type, expr))
+}
 
-    let expr = match p.parse_expr(Precedence::Lowest) {
-        Some(expr) => expr,
-        None => return None,
+fn first_non_constant(p: &Parser, expr: &Expr) -> Option<Span> {
+    let parts: Vec<&Expr> = match &expr.kind {
+        ExprKind::Literal(Literal::Array(items) | Literal::Tuple(items))
+        | ExprKind::Interpolated(items) => items.iter().collect(),
+        ExprKind::Literal(Literal::Map(entries)) => entries
+            .iter()
+            .flat_map(|(key, value)| [key, value])
+            .collect(),
+        ExprKind::Literal(_) => vec![],
+        ExprKind::Identifier(Identifier(name)) if p.consts.contains(name) => vec![],
+        ExprKind::Prefix(_, operand) | ExprKind::Spread(operand) | ExprKind::Is(operand, _) => {
+            vec![operand]
+        }
+        ExprKind::Infix(lhs, _, rhs) => vec![lhs, rhs],
+        _ => return Some(expr.span.clone()),
+    };
+    parts
+        .into_iter()
+        .find_map(|part| first_non_constant(p, part))
+}
+
+fn parse_let_tuple_stmt(p: &mut Parser, is_mut: bool) -> Option<StmtKind> {
+    let Binding::Tuple(names) = parse_binding(p)? else {
+        unreachable!("a binding starting with '(' is a tuple");
     };
 
-    Some(Stmt::Let(Identifier(var_name), None, Some(expr)))
+    let mut var_type = None;
+    if p.next_token_is(&Token::Colon) {
+        p.bump();
+        p.bump();
+        var_type = Some(p.parse_type()?);
+    }
+
+    if !p.bump_expected_next(&Token::Equal) {
+        return None;
+    }
+    p.bump();
+    let expr = p.parse_expr(Precedence::Lowest)?;
+
+    Some(StmtKind::LetTuple(names, var_type, expr, is_mut))
+}
+
+pub fn parse_binding(p: &mut Parser) -> Option<Binding> {
+    if let Some(Identifier(name)) = p.parse_identifier() {
+        return Some(Binding::Name(name));
+    }
+    if !p.current_token_is(&Token::Lparen) {
+        p.error_handler
+            .set_identifier_error(&p.curr_token, p.curr_span.clone());
+        return None;
+    }
+
+    let start = p.curr_span.clone();
+    let mut items: Vec<Binding> = vec![];
+    loop {
+        p.bump();
+        items.push(parse_binding(p)?);
+        if !p.next_token_is(&Token::Comma) {
+            break;
+        }
+        p.bump();
+    }
+    if !p.bump_expected_next(&Token::Rparen) {
+        return None;
+    }
+
+    if items.len() < 2 {
+        p.error_handler.set_error(
+            ParserErrorKind::SyntaxError,
+            "A tuple needs at least two items".to_string(),
+            start.to(&p.curr_span),
+        );
+        return None;
+    }
+    Some(Binding::Tuple(items))
 }
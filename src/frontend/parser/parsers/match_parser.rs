@@ -0,0 +1,142 @@
+use crate::frontend::ast::{
+    ArmBody, Expr, ExprKind, Identifier, Literal, MatchArm, Pattern, Precedence,
+};
+use crate::frontend::parser::{Parser, ParserErrorKind};
+use crate::frontend::token::Token;
+
+pub fn parse_match_expr(p: &mut Parser) -> Option<Expr> {
+    let start = p.curr_span.clone();
+    p.bump();
+    let subject = p.parse_condition()?;
+
+    if !p.bump_expected_next(&Token::Lbrace) {
+        return None;
+    }
+
+    let no_struct_literal = std::mem::replace(&mut p.no_struct_literal, false);
+    let arms = parse_match_arms(p);
+    p.no_struct_literal = no_struct_literal;
+    let arms = arms?;
+
+    let span = start.to(&p.curr_span);
+    Some(Expr::new(ExprKind::Match(Box::new(subject), arms), span))
+}
+
+fn parse_match_arms(p: &mut Parser) -> Option<Vec<MatchArm>> {
+    let mut arms: Vec<MatchArm> = vec![];
+    while !p.next_token_is(&Token::Rbrace) {
+        p.bump();
+        let start = p.curr_span.clone();
+        let pattern = parse_pattern(p)?;
+
+        let guard = match p.next_token_is(&Token::If) {
+            true => {
+                p.bump();
+                p.bump();
+                Some(p.parse_expr(Precedence::Lowest)?)
+            }
+            false => None,
+        };
+
+        if !p.bump_expected_next(&Token::FatArrow) {
+            return None;
+        }
+        p.bump();
+
+        let body = match p.curr_token {
+            Token::Lbrace => ArmBody::Block(p.parse_block_stmt()?),
+            _ => ArmBody::Expr(p.parse_expr(Precedence::Lowest)?),
+        };
+
+        arms.push(MatchArm {
+            pattern,
+            guard,
+            body,
+            span: start.to(&p.curr_span),
+        });
+
+        if p.next_token_is(&Token::Comma) {
+            p.bump();
+        }
+    }
+    p.bump();
+    Some(arms)
+}
+
+fn parse_pattern(p: &mut Parser) -> Option<Pattern> {
+    match p.curr_token.clone() {
+        Token::Identifier(name) if name == "_" => Some(Pattern::Wildcard),
+        Token::Identifier(enum_name) if p.next_token_is(&Token::Dot) => {
+            p.bump();
+            p.bump();
+            let variant = match p.parse_identifier() {
+                Some(variant) => variant,
+                None => {
+                    p.error_handler
+                        .set_identifier_error(&p.curr_token, p.curr_span.clone());
+                    return None;
+                }
+            };
+
+            let mut payload: Vec<Pattern> = vec![];
+            if p.next_token_is(&Token::Lparen) {
+                p.bump();
+                while !p.next_token_is(&Token::Rparen) {
+                    p.bump();
+                    payload.push(parse_pattern(p)?);
+                    if !p.next_token_is(&Token::Comma) {
+                        break;
+                    }
+                    p.bump();
+                }
+                if !p.bump_expected_next(&Token::Rparen) {
+                    return None;
+                }
+            }
+            Some(Pattern::Variant(Identifier(enum_name), variant, payload))
+        }
+        Token::Identifier(name) => Some(Pattern::Binding(name)),
+        Token::Int(_) | Token::Minus => {
+            let start = parse_int_bound(p)?;
+            if !p.next_token_is(&Token::DoubleDot) {
+                return Some(Pattern::Literal(Literal::Int(start)));
+            }
+            p.bump();
+            p.bump();
+            let end = parse_int_bound(p)?;
+            Some(Pattern::Range(start, end))
+        }
+        Token::Float(val) => Some(Pattern::Literal(Literal::Float(val))),
+        Token::String(val) => Some(Pattern::Literal(Literal::String(val))),
+        Token::True => Some(Pattern::Literal(Literal::Boolean(true))),
+        Token::False => Some(Pattern::Literal(Literal::Boolean(false))),
+        Token::Null => Some(Pattern::Literal(Literal::Null)),
+        token => {
+            p.error_handler.set_error(
+                ParserErrorKind::SyntaxError,
+                format!("Invalid pattern: {}", token),
+                p.curr_span.clone(),
+            );
+            None
+        }
+    }
+}
+
+fn parse_int_bound(p: &mut Parser) -> Option<i64> {
+    let negative = p.current_token_is(&Token::Minus);
+    if negative {
+        p.bump();
+    }
+    match p.curr_token {
+        Token::Int(val) if negative => Some(-val),
+        Token::Int(val) => Some(val),
+        _ => {
+            p.error_handler.set_error(
+                ParserErrorKind::SyntaxError,
+                format!("Invalid pattern: {}", p.curr_token),
+                p.curr_span.clone(),
+            );
+            None
+        }
+    }
+}
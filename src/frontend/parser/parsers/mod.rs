@@ -1,4 +1,8 @@
+pub mod enum_parser;
 pub mod forloop_parser;
 pub mod func_parser;
 pub mod if_parser;
 pub mod let_parser;
+pub mod match_parser;
+pub mod struct_parser;
+pub mod while_parser;
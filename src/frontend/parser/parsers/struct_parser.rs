@@ -0,0 +1,72 @@
+use crate::frontend::ast::{ExprType, Identifier, StmtKind};
+use crate::frontend::parser::{Parser, ParserErrorKind};
+use crate::frontend::token::Token;
+
+use super::func_parser::parse_method;
+
+pub fn parse_struct_stmt(p: &mut Parser) -> Option<StmtKind> {
+    p.bump();
+    let name = match p.parse_identifier() {
+        Some(identifier) => identifier,
+        None => {
+            p.error_handler
+                .set_identifier_error(&p.curr_token, p.curr_span.clone());
+            return None;
+        }
+    };
+
+    if !p.bump_expected_next(&Token::Lbrace) {
+        return None;
+    }
+
+    let receiver = ExprType::Named(name.0.clone());
+    let mut fields: Vec<(Identifier, ExprType)> = vec![];
+    let mut methods = vec![];
+    let mut declared: Vec<String> = vec![];
+
+    loop {
+        p.bump();
+        let span = p.curr_span.clone();
+        let member = match p.curr_token.clone() {
+            Token::Rbrace => break,
+            Token::Func => {
+                let method = parse_method(p, &receiver)?;
+                let member = method.name.0.clone();
+                methods.push(method);
+                member
+            }
+            Token::Identifier(field) => {
+                if !p.bump_expected_next(&Token::Colon) {
+                    return None;
+                }
+                p.bump();
+                let field_type = p.parse_type()?;
+                fields.push((Identifier(field.clone()), field_type));
+                if p.next_token_is(&Token::Comma) {
+                    p.bump();
+                }
+                field
+            }
+            token => {
+                p.error_handler.set_unexpexted_token_error(&token, span);
+                return None;
+            }
+        };
+
+        if declared.contains(&member) {
+            p.error_handler.set_error(
+                ParserErrorKind::SyntaxError,
+                format!("'{}' is already declared in '{}'", member, name.0),
+                span,
+            );
+            return None;
+        }
+        declared.push(member);
+    }
+
+    Some(StmtKind::Struct {
+        name,
+        fields,
+        methods,
+    })
+}
@@ -0,0 +1,39 @@
+use crate::frontend::{
+    ast::{Expr, ExprKind, Literal, StmtKind},
+    parser::Parser,
+    token::Token,
+};
+
+pub fn parse_while_stmt(p: &mut Parser, label: Option<String>) -> Option<StmtKind> {
+    p.bump();
+    let condition = p.parse_condition()?;
+
+    if !p.bump_expected_next(&Token::Lbrace) {
+        return None;
+    }
+    let block = p.parse_block_stmt()?;
+
+    Some(StmtKind::While {
+        condition,
+        block,
+        label,
+    })
+}
+
+pub fn parse_loop_stmt(p: &mut Parser, label: Option<String>) -> Option<StmtKind> {
+    let condition = Expr::new(
+        ExprKind::Literal(Literal::Boolean(true)),
+        p.curr_span.clone(),
+    );
+
+    if !p.bump_expected_next(&Token::Lbrace) {
+        return None;
+    }
+    let block = p.parse_block_stmt()?;
+
+    Some(StmtKind::While {
+        condition,
+        block,
+        label,
+    })
+}
@@ -0,0 +1,58 @@
+use core::fmt;
+use std::rc::Rc;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct Span {
+    pub file: Rc<str>,
+    pub line: usize,
+    pub colm: usize,
+    pub start: usize,
+    pub end: usize,
+}
+
+impl Span {
+    pub fn to(&self, other: &Span) -> Span {
+        Span {
+            file: Rc::clone(&self.file),
+            line: self.line,
+            colm: self.colm,
+            start: self.start,
+            end: other.end.max(self.start),
+        }
+    }
+
+    pub fn snippet(&self, source: &str) -> String {
+        let line_text = source
+            .lines()
+            .nth(self.line.saturating_sub(1))
+            .unwrap_or("");
+        let gutter = " ".repeat(self.line.to_string().len());
+
+        let padding = line_text
+            .chars()
+            .take(self.colm.saturating_sub(1))
+            .map(|c| if c == '\t' { '\t' } else { ' ' })
+            .collect::<String>();
+
+        let width = source
+            .get(self.start..self.end)
+            .and_then(|code| code.split('\n').next())
+            .map(|code| code.chars().count())
+            .unwrap_or(0)
+            .max(1);
+
+        format!(
+            "{gutter}--> {self}\n{gutter} |\n{} | {}\n{gutter} | {}{}",
+            self.line,
+            line_text,
+            padding,
+            "^".repeat(width)
+        )
+    }
+}
+
+impl fmt::Display for Span {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}:{}", self.file, self.line, self.colm)
+    }
+}
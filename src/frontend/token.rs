@@ -1,5 +1,7 @@
 use core::fmt;
 
+use super::span::Span;
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum Token {
     Illegal(char),
@@ -14,7 +16,10 @@ pub enum Token {
     Comma,
     Colon,
     Dot,
-    
+    DoubleDot,
+    Ellipsis,
+    Arrow,
+
     Plus,
     Minus,
     Asterisk,
@@ -29,25 +34,51 @@ pub enum Token {
     LessOrEqual,
     Bang,
     NotEqual,
+    And,
+    Or,
+    Not,
+    Question,
+    DoubleQuestion,
+    QuestionDot,
     DoublePlus,
     DoubleMinus,
+    PlusEqual,
+    MinusEqual,
+    AsteriskEqual,
+    SlashEqual,
+    PercentEqual,
 
     Int(i64),
     Float(f64),
     String(String),
+    InterpolatedString(Vec<StringPart>),
     True,
     False,
 
     Let,
+    Mut,
+    Const,
     If,
     For,
+    While,
+    Loop,
+    Break,
+    Continue,
     In,
     Else,
     Func,
     Null,
     Import,
+    Struct,
+    Enum,
+    Match,
+    Type,
+    Is,
+    Pipe,
+    FatArrow,
     Return,
     ClassArray,
+    ClassMap,
     Identifier(String),
 
     TypeInt,
@@ -55,6 +86,25 @@ pub enum Token {
     TypeString,
     TypeBoolean,
     TypeVoid,
+    TypeFunction,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum StringPart {
+    Text(String),
+    Code(String, Span),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Trivia {
+    DocComment(String, Span),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TokenInfo {
+    pub token: Token,
+    pub span: Span,
+    pub trivia: Vec<Trivia>,
 }
 
 impl fmt::Display for Token {
@@ -72,8 +122,20 @@ impl fmt::Display for Token {
             Self::Asterisk => write!(f, "*"),
             Self::Slash => write!(f, "/"),
             Self::String(val) => write!(f, "{}", val),
+            Self::InterpolatedString(parts) => {
+                write!(f, "\"")?;
+                for part in parts {
+                    match part {
+                        StringPart::Text(text) => write!(f, "{}", text)?,
+                        StringPart::Code(code, _) => write!(f, "${{{}}}", code)?,
+                    }
+                }
+                write!(f, "\"")
+            }
             Self::Identifier(name) => write!(f, "{}", name),
             Self::Let => write!(f, "let"),
+            Self::Mut => write!(f, "mut"),
+            Self::Const => write!(f, "const"),
             Self::Equal => write!(f, "="),
             Self::Func => write!(f, "[Defined Function]"),
             Self::Lbrace => write!(f, "{{"),
@@ -94,19 +156,46 @@ impl fmt::Display for Token {
             Self::Else => write!(f, "else"),
             Self::Bang => write!(f, "!"),
             Self::NotEqual => write!(f, "!="),
+            Self::And => write!(f, "&&"),
+            Self::Or => write!(f, "||"),
+            Self::Question => write!(f, "?"),
+            Self::DoubleQuestion => write!(f, "??"),
+            Self::QuestionDot => write!(f, "?."),
+            Self::Not => write!(f, "not"),
             Self::DoublePlus => write!(f, "++"),
             Self::DoubleMinus => write!(f, "--"),
+            Self::PlusEqual => write!(f, "+="),
+            Self::MinusEqual => write!(f, "-="),
+            Self::AsteriskEqual => write!(f, "*="),
+            Self::SlashEqual => write!(f, "/="),
+            Self::PercentEqual => write!(f, "%="),
             Self::Percet => write!(f, "%"),
             Self::For => write!(f, "for"),
+            Self::While => write!(f, "while"),
+            Self::Loop => write!(f, "loop"),
+            Self::Break => write!(f, "break"),
+            Self::Continue => write!(f, "continue"),
             Self::In => write!(f, "in"),
             Self::TypeInt => write!(f, "[Type Annotation] int"),
             Self::TypeFloat => write!(f, "[Type Annotation] float"),
             Self::Int(val) => write!(f, "{}", val),
             Self::Float(val) => write!(f, "{}", val),
             Self::TypeVoid => write!(f, "[Type Annotation] void"),
+            Self::TypeFunction => write!(f, "[Type Annotation] function"),
             Self::ClassArray => write!(f, "[Built-in Class] Array"),
+            Self::ClassMap => write!(f, "[Built-in Class] Map"),
             Self::Dot => write!(f, "."),
+            Self::DoubleDot => write!(f, ".."),
+            Self::Ellipsis => write!(f, "..."),
+            Self::Arrow => write!(f, "->"),
             Self::Import => write!(f, "import"),
+            Self::Struct => write!(f, "struct"),
+            Self::Enum => write!(f, "enum"),
+            Self::Match => write!(f, "match"),
+            Self::Type => write!(f, "type"),
+            Self::Is => write!(f, "is"),
+            Self::Pipe => write!(f, "|"),
+            Self::FatArrow => write!(f, "=>"),
         }
     }
 }
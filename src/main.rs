@@ -5,10 +5,11 @@ mod runtime;
 mod utils;
 mod stdlib;
 mod context;
+mod typeck;
 
 use std::{env, process::exit};
 
-use commands::run_from_file;
+use commands::{check_file, run_from_file};
 use repl::repl;
 
 fn main() {
@@ -25,7 +26,15 @@ fn main() {
                 eprintln!("[ERROR]: Missing file path");
                 exit(1);
             }
-            run_from_file(&cli_args[2]);
+            let check = !cli_args[3..].iter().any(|arg| arg == "--no-check");
+            run_from_file(&cli_args[2], check);
+        },
+        "check" => {
+            if cli_args.len() <= 2 {
+                eprintln!("[ERROR]: Missing file path");
+                exit(1);
+            }
+            check_file(&cli_args[2]);
         },
         "build" => {
         },
@@ -4,12 +4,12 @@ use std::rc::Rc;
 use rustyline::error::ReadlineError;
 use rustyline::DefaultEditor;
 
+use crate::context::Context;
 use crate::frontend::lexer::Lexer;
 use crate::frontend::parser::Parser;
-use crate::context::Context;
-use crate::stdlib::builtins::builtins;
 use crate::runtime::object::Object;
 use crate::runtime::Runtime;
+use crate::stdlib::builtins::builtins;
 
 const REPL_HELPER: &str = r#"
 Helper
@@ -22,11 +22,25 @@ Helper
     let lovesCoffe = true
     let height: float = 1.87
 
+    // only `let mut` variables can be assigned again,
+    // consts are top-level and known before running
+    let mut count = 0
+    const MAX = 10
+
     // Arrays
 
     let xs: Array<int> = [1, 2, 3]
     let ys = ["y1", "y2", "y3"]
 
+    // Maps, iterated in insertion order
+
+    let mut ages: Map<string, int> = {"bob": 30}
+    ages["al"] = 21
+    ages.has("bob")
+    for name, age in ages { 
+        print(name, age) 
+    }
+
     // define function
 
     define sum(x: int, y: int): int {
@@ -34,6 +48,42 @@ Helper
         return result 
     }
 
+    define divmod(x: int, y: int): (int, int) {
+        return (x / y, x % y)
+    }
+    let (q, r) = divmod(7, 2)
+
+    // params can have defaults and args can be given by name
+    define greet(name: string, greeting: string = "Hello"): string {
+        return greeting + ", " + name
+    }
+    greet("Ana", greeting: "Hi")
+
+    // a rest param collects the extra args, `...xs` spreads an array
+    define log(parts: ...string): void {
+        print(parts)
+    }
+    log("a", ...["b", "c"])
+
+    // only optional types take null
+    let age: int? = null
+    print(age ?? 0)
+    if age != null {
+        print(age + 1)
+    }
+
+    // generic functions infer `T` from the args
+    define first<T>(xs: Array<T>): T {
+        return xs[0]
+    }
+
+    // a union takes any of its types, `is` checks which one a value has
+    type Id = int | string
+    let id: Id = 7
+    if id is int {
+        print(id + 1)
+    }
+
     define sayHello(subject: string): void {
         print("Hello, ", subject)
     }
@@ -42,6 +92,8 @@ Helper
 
     if true { 
         // do something 
+    } else if false { 
+        // do another thing 
     } else { 
         // do something else 
     }
@@ -51,6 +103,22 @@ Helper
         print(counter) 
     }
 
+    // while loops, `loop` runs until a `break`
+    while counter < 10 { 
+        counter = counter + 1 
+    }
+    outer: loop { 
+        break outer 
+    }
+
+    // enums and match
+    enum Shape { Circle(float), Rect(float, float), Empty }
+    let size = match shape {
+        Shape.Circle(r) if r > 1.0 => "big circle",
+        Shape.Rect(w, h) => "rect",
+        _ => "small"
+    }
+
     // Built-in functions
 
     len("Hello")
@@ -58,6 +126,8 @@ Helper
 
     // More: ...
     // arthimetics: +, -, /, *
+    // updates: x += 1, x -= 1, x *= 2, x /= 2, x %= 2, x++, ++x, x--, --x
+    // logical: &&, ||, ! (also and, or, not) on booleans
     // postfix: x++, x--
     // prefix: !x, -x
     
@@ -69,30 +139,37 @@ Helper
 "#;
 
 fn eval_repl_line(line: String, env: Rc<RefCell<Context>>) {
-    if line == String::from(".help") {
+    if line == ".help" {
         println!("{}", REPL_HELPER);
         return;
     }
 
-    if line == String::from("exit()") {
+    if line == "exit()" {
         println!("Exiting...");
         std::process::exit(0);
     }
 
     // @TODO: avoid creating new objects
     let input = line.chars().collect::<Vec<char>>();
-    let mut l = Lexer::new(&input);
+    let mut l = Lexer::new(&input, "<repl>");
     let mut p = Parser::new(&mut l);
     let program = p.parse();
 
-    if p.has_error() {
-        println!("{}", p.get_error().unwrap());
+    if p.has_errors() {
+        for err in p.get_errors() {
+            println!("{}", err.report(&line));
+        }
         return;
     };
 
     let mut evaltr = Runtime::new(env);
     let evaluated = evaltr.eval(program);
 
+    if let Some(report) = evaltr.error_handler.report(&line) {
+        eprintln!("{}", report);
+        return;
+    }
+
     if evaluated.is_none() {
         return;
     }
@@ -119,8 +196,8 @@ pub fn repl() {
                 if !balance_and_eval(&mut rl, state, Rc::clone(&env)) {
                     break;
                 }
-            },
-            None => break
+            }
+            None => break,
         }
     }
 }
@@ -0,0 +1,96 @@
+use std::rc::Rc;
+
+use crate::frontend::ast::{Expr, ExprKind, ExprType, Identifier};
+use crate::runtime::object::{EnumDef, Object};
+use crate::runtime::type_system::Type;
+use crate::runtime::Runtime;
+
+pub fn eval_enum_def(rt: &mut Runtime, name: String, variants: &[(Identifier, Vec<ExprType>)]) {
+    if rt.env.borrow().has(&name) {
+        rt.error_handler
+            .set_name_error(format!("'{}' is already declared", name));
+        return;
+    }
+
+    let variants = variants
+        .iter()
+        .map(|(Identifier(variant), payload)| {
+            let payload = payload.iter().map(|type_| rt.resolve_type(type_)).collect();
+            (variant.clone(), payload)
+        })
+        .collect::<Vec<(String, Vec<Type>)>>();
+
+    let def = EnumDef {
+        name: name.clone(),
+        variants,
+    };
+    rt.env
+        .borrow_mut()
+        .set(name, Type::TypeAnnot, Object::EnumDef(Rc::new(def)), false);
+}
+
+pub fn eval_enum_variant(rt: &mut Runtime, def: Rc<EnumDef>, target: Expr) -> Option<Object> {
+    let (variant, args) = match target.kind {
+        ExprKind::Identifier(Identifier(variant)) => (variant, vec![]),
+        ExprKind::Call(callee, args) => match callee.kind {
+            ExprKind::Identifier(Identifier(variant)) => (variant, args),
+            _ => {
+                rt.error_handler
+                    .set_sematic("Variant name must be an identifier".to_string());
+                return None;
+            }
+        },
+        _ => {
+            rt.error_handler
+                .set_sematic("Can only access variants of an enum".to_string());
+            return None;
+        }
+    };
+
+    let types = match def.variants.iter().find(|(name, _)| *name == variant) {
+        Some((_, types)) => types.clone(),
+        None => {
+            rt.error_handler
+                .set_name_error(format!("'{}' has no variant '{}'", def.name, variant));
+            return None;
+        }
+    };
+
+    if args.len() != types.len() {
+        rt.error_handler.set_type_error(format!(
+            "'{}.{}' expects {} value(s) but {} provided",
+            def.name,
+            variant,
+            types.len(),
+            args.len()
+        ));
+        return None;
+    }
+
+    let mut payload: Vec<Object> = vec![];
+    for (arg, expected_type) in args.into_iter().zip(types) {
+        if arg.name.is_some() {
+            rt.error_handler
+                .set_sematic("Values of a variant can't be given by name".to_string());
+            return None;
+        }
+        let value = rt.eval_expr(arg.value)?.typed_as(&expected_type);
+        if !expected_type.accepts(&value.ask_type()) {
+            rt.error_handler.set_type_error(format!(
+                "'{}.{}' expects value of type '{}' but provided value of type '{}'",
+                def.name,
+                variant,
+                expected_type,
+                value.ask_type()
+            ));
+            return None;
+        }
+        payload.push(value);
+    }
+
+    Some(Object::EnumValue {
+        def,
+        variant,
+        payload,
+    })
+}
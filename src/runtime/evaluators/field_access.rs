@@ -1,5 +1,6 @@
+use super::enum_evaluator::eval_enum_variant;
 use super::func_call_evaluator::eval_call;
-use crate::frontend::ast::{Expr, Identifier};
+use crate::frontend::ast::{Expr, ExprKind, Identifier};
 use crate::runtime::object::{Object, ObjectInfo};
 use crate::runtime::type_system::Type;
 use crate::runtime::Runtime;
@@ -7,61 +8,77 @@ use crate::stdlib::primitives::{make_integer, make_string};
 use crate::stdlib::FieldsManager;
 
 pub fn eval_field_access(rt: &mut Runtime, src: Expr, target: Expr) -> Option<Object> {
-    let src = match rt.eval_expr(src) {
-        Some(object) => object,
-        None => return None,
-    };
+    let src = rt.eval_non_null(src)?;
+    access_field(rt, src, target)
+}
 
+pub fn eval_optional_field_access(rt: &mut Runtime, src: Expr, target: Expr) -> Option<Object> {
+    match rt.eval_expr(src)? {
+        Object::Null => Some(Object::Null),
+        src => access_field(rt, src, target),
+    }
+}
+
+fn access_field(rt: &mut Runtime, src: Object, target: Expr) -> Option<Object> {
     match src.clone() {
-        Object::Int(prim) => {
-            return _eval(
-                rt,
-                prim.fields,
-                target,
-                src,
-                vec![ObjectInfo {
-                    is_mut: false,
-                    type_: Type::Int,
-                    value: Object::Int(make_integer(prim.value)),
-                }],
-            )
-        }
-        Object::String(prim) => {
-            return _eval(
-                rt,
-                prim.fields,
-                target,
-                src,
-                vec![ObjectInfo {
-                    is_mut: false,
-                    type_: Type::String,
-                    value: Object::String(make_string(prim.value)),
-                }],
-            )
-        }
-        Object::Module(m) => {
-            return _eval(rt, m.fields, target, src, vec![]);
+        Object::Int(prim) => _eval(
+            rt,
+            prim.fields,
+            target,
+            src,
+            vec![ObjectInfo {
+                is_mut: false,
+                type_: Type::Int,
+                value: Object::Int(make_integer(prim.value)),
+            }],
+        ),
+        Object::String(prim) => _eval(
+            rt,
+            prim.fields,
+            target,
+            src,
+            vec![ObjectInfo {
+                is_mut: false,
+                type_: Type::String,
+                value: Object::String(make_string(prim.value)),
+            }],
+        ),
+        Object::Module(m) => _eval(rt, m.fields, target, src, vec![]),
+        Object::Struct(ref instance) => {
+            let fields = match target.kind {
+                ExprKind::Call(..) => instance.borrow().def.methods.clone(),
+                _ => instance.borrow().fields.clone(),
+            };
+            let receiver = ObjectInfo {
+                is_mut: false,
+                type_: src.ask_type(),
+                value: src.clone(),
+            };
+            _eval(rt, fields, target, src, vec![receiver])
         }
-        Object::Array { inner, items_type } => {
-            return _eval(
-                rt,
-                inner.fields.clone(),
-                target,
-                src,
-                vec![ObjectInfo {
-                    is_mut: false,
-                    value: Object::Array {
-                        inner,
-                        items_type: items_type.clone(),
-                    },
-                    type_: Type::Array(if items_type.is_none() {
-                        None
-                    } else {
-                        Some(Box::new(items_type.unwrap()))
-                    }),
-                }],
-            );
+        Object::EnumDef(def) => eval_enum_variant(rt, def, target),
+        Object::Map { ref inner, .. } => {
+            let receiver = ObjectInfo {
+                is_mut: false,
+                type_: src.ask_type(),
+                value: src.clone(),
+            };
+            _eval(rt, inner.fields.clone(), target, src, vec![receiver])
         }
+        Object::Array { inner, items_type } => _eval(
+            rt,
+            inner.fields.clone(),
+            target,
+            src,
+            vec![ObjectInfo {
+                is_mut: false,
+                value: Object::Array {
+                    inner,
+                    items_type: items_type.clone(),
+                },
+                type_: Type::Array(items_type.map(Box::new)),
+            }],
+        ),
         _ => {
             rt.error_handler
                 .set_sematic(format!("Field access not impl for type {}", src.ask_type()));
@@ -77,10 +94,10 @@ fn _eval(
     src: Object,
     extra_args: Vec<ObjectInfo>,
 ) -> Option<Object> {
-    match target {
-        Expr::Call(expr, args) => {
-            let fn_name = match *expr {
-                Expr::Identifier(Identifier(name)) => name,
+    match target.kind {
+        ExprKind::Call(expr, args) => {
+            let fn_name = match expr.kind {
+                ExprKind::Identifier(Identifier(name)) => name,
                 _ => {
                     rt.error_handler
                         .set_sematic("Function name must be an identifier".to_string());
@@ -99,7 +116,7 @@ fn _eval(
 
             eval_call(rt, fn_name, fn_object, args, extra_args)
         }
-        Expr::Identifier(Identifier(name)) => {
+        ExprKind::Identifier(Identifier(name)) => {
             let field = fields.access(&name);
             if field.is_none() {
                 rt.error_handler
@@ -111,7 +128,7 @@ fn _eval(
         _ => {
             rt.error_handler
                 .set_sematic("Can only access fields or methods".to_string());
-            return None;
+            None
         }
     }
 }
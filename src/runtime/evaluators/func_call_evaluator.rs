@@ -4,29 +4,24 @@ use std::rc::Rc;
 use super::super::object::*;
 use crate::context::{Context, ContextType};
 use crate::runtime::type_system::Type;
-use crate::runtime::{Expr, Identifier, Runtime};
+use crate::runtime::{Arg, Expr, ExprKind, Identifier, Runtime};
+use crate::stdlib::collections::Array;
 
 pub fn eval_call_expr(
     rt: &mut Runtime,
     func_ident: Expr,
-    provided_args: Vec<Expr>,
+    provided_args: Vec<Arg>,
 ) -> Option<Object> {
-    let fn_name = match func_ident {
-        Expr::Identifier(Identifier(name)) => name,
-        _ => {
-            rt.error_handler
-                .set_name_error(format!("Function name must be an identifier"));
-            return None;
-        }
-    };
-
-    let fn_object = match rt.env.borrow().resolve(&fn_name) {
-        Some(object) => object.value,
-        None => {
-            rt.error_handler
-                .set_name_error(format!("'{}' is not declared", fn_name));
-            return None;
-        }
+    let (fn_name, fn_object) = match func_ident.kind {
+        ExprKind::Identifier(Identifier(name)) => match rt.env.borrow().resolve(&name) {
+            Some(object) => (name, object.value),
+            None => {
+                rt.error_handler
+                    .set_name_error(format!("'{}' is not declared", name));
+                return None;
+            }
+        },
+        _ => ("<anonymous>".to_string(), rt.eval_expr(func_ident)?),
     };
 
     eval_call(rt, fn_name, fn_object, provided_args, vec![])
@@ -36,29 +31,69 @@ pub fn eval_call(
     rt: &mut Runtime,
     fn_name: String,
     fn_object: Object,
-    provided_args: Vec<Expr>,
+    provided_args: Vec<Arg>,
     extra_args: Vec<ObjectInfo>,
 ) -> Option<Object> {
-    let mut checked_args: Vec<ObjectInfo> = match rt.eval_fn_call_args(provided_args) {
-        Some(args) => args,
-        None => return None,
-    };
-
-    checked_args.extend(extra_args);
+    // the receiver of a method call comes first, it is `self`
+    let mut positional: Vec<ObjectInfo> = extra_args;
+    let mut named: Vec<(String, ObjectInfo)> = vec![];
+    for Arg { name, value } in provided_args {
+        let objects = match value.kind {
+            ExprKind::Spread(spread) => rt.eval_spread(*spread)?.0,
+            _ => vec![rt.eval_expr(value)?],
+        };
+        for object in objects {
+            let arg = ObjectInfo {
+                is_mut: true,
+                type_: object.ask_type(),
+                value: object,
+            };
+            match &name {
+                Some(Identifier(name)) => named.push((name.clone(), arg)),
+                None => positional.push(arg),
+            }
+        }
+    }
 
-    let (params, body, expected_ret_type) = match fn_object {
-        Object::BuiltInFunction(builtin_fn) => match builtin_fn(checked_args) {
-            BuiltInFuncReturnValue::Object(object) => return Some(object),
-            BuiltInFuncReturnValue::Error(err) => {
-                rt.error_handler.set_error(err.kind, err.msg);
+    let (params, body, expected_ret_type, scope) = match fn_object {
+        Object::BuiltInFunction(builtin_fn) => {
+            if let Some((name, _)) = named.first() {
+                rt.error_handler.set_type_error(format!(
+                    "Function '{}' takes no named arguments but got '{}'",
+                    fn_name, name
+                ));
                 return None;
             }
-        },
+            return eval_builtin_call(rt, builtin_fn, positional);
+        }
+        Object::BuiltInFunctionWithParams { func, params } => {
+            let param_names = params
+                .iter()
+                .map(|param| (param.name.to_string(), param.default.is_some()))
+                .collect::<Vec<(String, bool)>>();
+            let (bound, _) = bind_args(rt, &fn_name, &param_names, false, positional, named)?;
+            let args = bound
+                .into_iter()
+                .zip(params)
+                .map(|(arg, param)| {
+                    arg.unwrap_or_else(|| {
+                        let value = (param.default.expect("missing args are reported"))();
+                        ObjectInfo {
+                            is_mut: true,
+                            type_: value.ask_type(),
+                            value,
+                        }
+                    })
+                })
+                .collect();
+            return eval_builtin_call(rt, func, args);
+        }
         Object::UserDefinedFunction {
             params,
             body,
             return_type,
-        } => (params, body, return_type),
+            scope,
+        } => (params, body, return_type, scope),
         _ => {
             rt.error_handler
                 .set_type_error(format!("'{}' is not callable", fn_name));
@@ -66,74 +101,212 @@ pub fn eval_call(
         }
     };
 
-    if params.len() != checked_args.len() {
-        rt.error_handler.set_type_error(format!(
-            "Function '{}' expecteds {} args but provided {}",
-            fn_name,
-            params.len(),
-            checked_args.len()
-        ));
+    let has_rest = params.last().is_some_and(|param| param.rest);
+    let param_names = params
+        .iter()
+        .filter(|param| !param.rest)
+        .map(|param| (param.name.clone(), param.default.is_some()))
+        .collect::<Vec<(String, bool)>>();
+    let (bound, rest_args) = bind_args(rt, &fn_name, &param_names, has_rest, positional, named)?;
+
+    let caller_scope = Rc::clone(&rt.env);
+    let fn_scope = Context::make_from(scope, ContextType::Function);
+    rt.env = Rc::new(RefCell::new(fn_scope));
+    let returned_value = match bind_params(rt, params, bound, rest_args) {
+        Some(()) => rt.eval_block_stmt(&body),
+        None => None,
+    };
+    let fn_scope = std::mem::replace(&mut rt.env, caller_scope);
+
+    if rt.error_handler.has_error() {
         return None;
     }
 
-    let global_scope = Rc::clone(&rt.env);
-    let mut fn_scope = Context::make_from(Rc::clone(&global_scope), ContextType::Function);
+    let returned_value_type = returned_value.clone().unwrap_or(Object::Null).ask_type();
 
-    for (_, (FunctionParam { name, type_ }, object_info)) in
-        params.into_iter().zip(checked_args).enumerate()
-    {
-        if type_ != object_info.type_ {
+    // the type params of a generic function are known by now, unless no arg
+    // told their type
+    let mut type_args = fn_scope.borrow().type_args.clone();
+    if let Some(expected_ret_type) = expected_ret_type {
+        let expected_ret_type = expected_ret_type.substitute(&type_args);
+        if !expected_ret_type.infer(&returned_value_type.instantiate(), &mut type_args)
+            && !is_types_equivalents(&expected_ret_type, &returned_value_type)
+        {
             rt.error_handler.set_type_error(format!(
-                "Passing argument of type '{}' to parameter of type '{}'",
-                object_info.type_, type_
+                "Function '{}' must return '{}' but found '{}'",
+                fn_name, expected_ret_type, returned_value_type,
             ));
             return None;
         }
+    }
 
-        if !fn_scope.set(name.clone(), object_info.type_, object_info.value, true) {
-            rt.error_handler
-                .set_name_error(format!("Param '{}' already declared", &name));
-            return None;
+    match returned_value.clone().unwrap() {
+        Object::RetVal(v) => Some(*v),
+        _ => returned_value,
+    }
+}
+
+fn eval_builtin_call(
+    rt: &mut Runtime,
+    builtin_fn: fn(Vec<ObjectInfo>) -> BuiltInFuncReturnValue,
+    args: Vec<ObjectInfo>,
+) -> Option<Object> {
+    match builtin_fn(args) {
+        BuiltInFuncReturnValue::Object(object) => Some(object),
+        BuiltInFuncReturnValue::Error(err) => {
+            rt.error_handler.set_error(err.kind, err.msg);
+            None
         }
     }
+}
 
-    rt.env = Rc::new(RefCell::new(fn_scope));
-    let returned_value = rt.eval_block_stmt(&body);
+type BoundArgs = (Vec<Option<ObjectInfo>>, Vec<ObjectInfo>);
 
-    if rt.error_handler.has_error() {
+fn bind_args(
+    rt: &mut Runtime,
+    fn_name: &str,
+    params: &[(String, bool)],
+    has_rest: bool,
+    mut positional: Vec<ObjectInfo>,
+    named: Vec<(String, ObjectInfo)>,
+) -> Option<BoundArgs> {
+    let rest_args = match has_rest && positional.len() > params.len() {
+        true => positional.split_off(params.len()),
+        false => vec![],
+    };
+    if positional.len() > params.len() {
+        rt.error_handler.set_type_error(format!(
+            "Function '{}' expects {} args but provided {}",
+            fn_name,
+            params.len(),
+            positional.len() + named.len()
+        ));
         return None;
     }
 
-    let returned_value_type = returned_value.clone().unwrap_or(Object::Null).ask_type();
+    let mut bound: Vec<Option<ObjectInfo>> = positional.into_iter().map(Some).collect();
+    bound.resize(params.len(), None);
 
-    if (expected_ret_type != returned_value_type)
-        && !is_types_equivalents(&expected_ret_type, &returned_value_type)
-    {
+    let mut unexpected: Vec<String> = vec![];
+    for (name, arg) in named {
+        match params.iter().position(|(param, _)| *param == name) {
+            Some(position) if bound[position].is_some() => {
+                rt.error_handler.set_type_error(format!(
+                    "Function '{}' got argument '{}' more than once",
+                    fn_name, name
+                ));
+                return None;
+            }
+            Some(position) => bound[position] = Some(arg),
+            None => unexpected.push(format!("'{}'", name)),
+        }
+    }
+
+    let missing = params
+        .iter()
+        .zip(&bound)
+        .filter(|((_, has_default), arg)| arg.is_none() && !has_default)
+        .map(|((name, _), _)| format!("'{}'", name))
+        .collect::<Vec<String>>();
+
+    let mut problems: Vec<String> = vec![];
+    if !missing.is_empty() {
+        problems.push(format!("is missing {}", missing.join(", ")));
+    }
+    if !unexpected.is_empty() {
+        problems.push(format!("got unexpected {}", unexpected.join(", ")));
+    }
+    if !problems.is_empty() {
         rt.error_handler.set_type_error(format!(
-            "Function '{}' must return '{}' but found '{}'",
-            fn_name, expected_ret_type, returned_value_type,
+            "Function '{}' {}",
+            fn_name,
+            problems.join(" and ")
         ));
         return None;
     }
+    Some((bound, rest_args))
+}
 
-    rt.env = global_scope;
+fn bind_params(
+    rt: &mut Runtime,
+    params: FunctionParams,
+    bound: Vec<Option<ObjectInfo>>,
+    mut rest_args: Vec<ObjectInfo>,
+) -> Option<()> {
+    let mut bound = bound.into_iter();
+    for FunctionParam {
+        name,
+        type_,
+        default,
+        rest,
+        is_mut,
+        span,
+    } in params
+    {
+        let value = if rest {
+            let Type::Array(Some(items_type)) = &type_ else {
+                unreachable!("rest params are typed arrays")
+            };
+            let mut items = vec![];
+            for arg in std::mem::take(&mut rest_args) {
+                items.push(check_arg(rt, arg.value, items_type)?);
+            }
+            let items_type = items_type.substitute(&rt.env.borrow().type_args);
+            Object::Array {
+                inner: Array::from(items),
+                items_type: Some(items_type).filter(|type_| !type_.is_generic()),
+            }
+        } else {
+            match (bound.next().flatten(), default) {
+                (Some(arg), _) => arg.value,
+                (None, Some(default)) => rt.eval_expr(default)?,
+                (None, None) => unreachable!("missing args are reported by bind_args"),
+            }
+        };
+        let value = check_arg(rt, value, &type_)?;
+        let type_ = type_.substitute(&rt.env.borrow().type_args);
 
-    match returned_value.clone().unwrap() {
-        Object::RetVal(v) => Some(*v),
-        _ => returned_value,
+        if !rt
+            .env
+            .borrow_mut()
+            .declare(name.clone(), type_, value, is_mut, &span)
+        {
+            rt.error_handler
+                .set_name_error(format!("Param '{}' already declared", &name));
+            return None;
+        }
     }
+    Some(())
 }
 
-fn is_types_equivalents(lhs: &Type, rhs: &Type) -> bool {
-    match lhs {
-        Type::Void => match rhs {
-            Type::Null => true,
-            _ => false,
-        },
-        Type::Null => match rhs {
-            Type::Void => true,
-            _ => false,
-        },
-        _ => false,
+fn check_arg(rt: &mut Runtime, value: Object, type_: &Type) -> Option<Object> {
+    let mut type_args = rt.env.borrow().type_args.clone();
+    let expected_type = type_.substitute(&type_args);
+    let value = match expected_type.is_generic() {
+        true => value,
+        false => value.typed_as(&expected_type),
+    };
+
+    if !type_.infer(&value.ask_type().instantiate(), &mut type_args) {
+        let inferred = match expected_type != *type_ {
+            true => format!(", which is '{}' in this call", expected_type),
+            false => String::new(),
+        };
+        rt.error_handler.set_type_error(format!(
+            "Passing argument of type '{}' to parameter of type '{}'{}",
+            value.ask_type(),
+            type_,
+            inferred
+        ));
+        return None;
     }
+    rt.env.borrow_mut().type_args = type_args;
+    Some(value)
+}
+
+fn is_types_equivalents(lhs: &Type, rhs: &Type) -> bool {
+    matches!(
+        (lhs, rhs),
+        (Type::Void, Type::Null) | (Type::Null, Type::Void)
+    )
 }
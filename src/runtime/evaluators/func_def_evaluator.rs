@@ -1,13 +1,16 @@
+use std::rc::Rc;
+
+use crate::frontend::span::Span;
 use crate::runtime::object::{FunctionParam, FunctionParams, Object};
-use crate::runtime::type_system::{expr_type_to_object_type, Type};
-use crate::runtime::{BlockStmt, ExprType, Identifier, Runtime};
+use crate::runtime::{BlockStmt, ExprType, Identifier, Param, Runtime};
 
 pub fn eval_func_def(
     e: &mut Runtime,
     name: String,
-    params: &Vec<(Identifier, ExprType)>,
+    params: &[Param],
     body: &BlockStmt,
-    ret_type: &ExprType,
+    ret_type: &Option<ExprType>,
+    span: &Span,
 ) {
     if e.env.borrow().has(&name) {
         e.error_handler
@@ -15,25 +18,40 @@ pub fn eval_func_def(
         return;
     }
 
+    let function_object = eval_func_expr(e, params, body, ret_type);
+    let function_type = function_object.ask_type();
+
+    e.env
+        .borrow_mut()
+        .declare(name, function_type, function_object, false, span);
+}
+
+pub fn eval_func_expr(
+    e: &mut Runtime,
+    params: &[Param],
+    body: &BlockStmt,
+    ret_type: &Option<ExprType>,
+) -> Object {
     let params = params
         .iter()
         .map(|param| {
-            let Identifier(param_name) = param.0.clone();
-            let param_type = expr_type_to_object_type(&param.1);
+            let Identifier(param_name) = param.name.clone();
+            let param_type = e.resolve_type(&param.type_);
             FunctionParam {
                 name: param_name,
                 type_: param_type,
+                default: param.default.clone(),
+                rest: param.rest,
+                is_mut: param.is_mut,
+                span: param.span.clone(),
             }
         })
         .collect::<FunctionParams>();
-    let return_type = expr_type_to_object_type(ret_type);
-    let function_object = Object::UserDefinedFunction {
+    let return_type = ret_type.as_ref().map(|ret_type| e.resolve_type(ret_type));
+    Object::UserDefinedFunction {
         params,
         body: body.clone(),
         return_type,
-    };
-
-    e.env
-        .borrow_mut()
-        .set(name, Type::Function, function_object, false);
+        scope: Rc::clone(&e.env),
+    }
 }
@@ -0,0 +1,281 @@
+use crate::frontend::ast::{Expr, ExprKind, Identifier};
+use crate::runtime::object::Object;
+use crate::runtime::type_system::Type;
+use crate::runtime::Runtime;
+use crate::stdlib::collections::{map_key, Array, Map, MapKey};
+use crate::stdlib::primitives::make_string;
+
+pub fn eval_index_expr(rt: &mut Runtime, target: Expr, index: Expr) -> Option<Object> {
+    let target = rt.eval_non_null(target)?;
+    if let Object::Map { inner, entry_types } = &target {
+        let key = rt.eval_expr(index)?;
+        let map_key = eval_map_key(rt, &key, entry_types)?;
+        let value = inner.entries.borrow().get(&map_key).cloned();
+        if value.is_none() {
+            rt.error_handler
+                .set_index_error(format!("Key {} not found in map", key));
+        }
+        return value;
+    }
+    let index = eval_index(rt, index)?;
+
+    match target {
+        Object::Array { inner, .. } => {
+            let position = check_bounds(rt, index, inner.inner.len(), "array")?;
+            Some(inner.inner[position].clone())
+        }
+        Object::Tuple(items) => {
+            let position = check_bounds(rt, index, items.len(), "tuple")?;
+            Some(items[position].clone())
+        }
+        Object::String(prim) => {
+            let chars = prim.value.chars().collect::<Vec<char>>();
+            let position = check_bounds(rt, index, chars.len(), "string")?;
+            Some(Object::String(make_string(chars[position].to_string())))
+        }
+        _ => {
+            rt.error_handler
+                .set_type_error(format!("Type '{}' can't be indexed", target.ask_type()));
+            None
+        }
+    }
+}
+
+pub fn eval_slice_expr(
+    rt: &mut Runtime,
+    target: Expr,
+    start: Option<Box<Expr>>,
+    end: Option<Box<Expr>>,
+) -> Option<Object> {
+    let target = rt.eval_expr(target)?;
+    let start = match start {
+        Some(start) => Some(eval_index(rt, *start)?),
+        None => None,
+    };
+    let end = match end {
+        Some(end) => Some(eval_index(rt, *end)?),
+        None => None,
+    };
+
+    match target {
+        Object::Array { inner, items_type } => {
+            let (start, end) = check_slice_bounds(rt, start, end, inner.inner.len(), "array")?;
+            Some(Object::Array {
+                inner: Array::from(inner.inner[start..end].to_vec()),
+                items_type,
+            })
+        }
+        Object::String(prim) => {
+            let chars = prim.value.chars().collect::<Vec<char>>();
+            let (start, end) = check_slice_bounds(rt, start, end, chars.len(), "string")?;
+            Some(Object::String(make_string(
+                chars[start..end].iter().collect(),
+            )))
+        }
+        _ => {
+            rt.error_handler
+                .set_type_error(format!("Type '{}' can't be sliced", target.ask_type()));
+            None
+        }
+    }
+}
+
+pub fn eval_index_assign(
+    rt: &mut Runtime,
+    target: Expr,
+    index: Expr,
+    value: Expr,
+) -> Option<Object> {
+    let mut indexes = vec![index];
+    let mut root = target;
+    while let ExprKind::Index(inner_target, inner_index) = root.kind {
+        indexes.push(*inner_index);
+        root = *inner_target;
+    }
+    indexes.reverse();
+
+    let name = match root.kind {
+        ExprKind::Identifier(Identifier(name)) => name,
+        _ => {
+            rt.error_handler
+                .set_sematic("Only items of variables can be assigned".to_string());
+            return None;
+        }
+    };
+
+    let variable = match rt.env.borrow().resolve(&name) {
+        Some(variable) => variable,
+        None => {
+            rt.error_handler
+                .set_name_error(format!("'{}' is not declared", &name));
+            return None;
+        }
+    };
+
+    // maps are shared like `m.set(key, value)`, only arrays need `mut`
+    if !variable.is_mut && !matches!(variable.value, Object::Map { .. }) {
+        rt.set_not_assignable(&name);
+        return None;
+    }
+
+    let mut path = vec![];
+    for index in indexes {
+        path.push(rt.eval_expr(index)?);
+    }
+
+    let mut updated = variable.value;
+    assign_at(rt, &name, &mut updated, &path, value)?;
+    rt.env.borrow_mut().mutate(name, updated);
+    None
+}
+
+fn assign_at(
+    rt: &mut Runtime,
+    name: &str,
+    slot: &mut Object,
+    path: &[Object],
+    value: Expr,
+) -> Option<()> {
+    let (index, rest) = path.split_first()?;
+    match slot {
+        Object::Array { inner, items_type } => {
+            let index = expect_int_index(rt, index)?;
+            let position = check_bounds(rt, index, inner.inner.len(), "array")?;
+            let item = &mut inner.inner[position];
+            if !rest.is_empty() {
+                return assign_at(rt, name, item, rest, value);
+            }
+            let expected_type = items_type.clone().unwrap_or_else(|| item.ask_type());
+            *item = check_item_type(rt, name, &expected_type, value)?;
+            Some(())
+        }
+        Object::Map { inner, entry_types } => {
+            let map_key = eval_map_key(rt, index, entry_types)?;
+            if rest.is_empty() {
+                let expected_type = match entry_types {
+                    Some((_, value_type)) => value_type.clone(),
+                    None => {
+                        rt.error_handler.set_type_error(
+                            "Can't set into a map of unknown types, annotate it like 'Map<string, int>'"
+                                .to_string(),
+                        );
+                        return None;
+                    }
+                };
+                let new_value = check_item_type(rt, name, &expected_type, value)?;
+                inner.entries.borrow_mut().insert(map_key, new_value);
+                return Some(());
+            }
+            let mut item = get_map_item(rt, inner, &map_key, index)?;
+            assign_at(rt, name, &mut item, rest, value)?;
+            inner.entries.borrow_mut().insert(map_key, item);
+            Some(())
+        }
+        Object::String(_) | Object::Tuple(_) => {
+            rt.error_handler.set_type_error(format!(
+                "Type '{}' can't be modified by index",
+                slot.ask_type()
+            ));
+            None
+        }
+        other => {
+            rt.error_handler
+                .set_type_error(format!("Type '{}' can't be indexed", other.ask_type()));
+            None
+        }
+    }
+}
+
+fn check_item_type(
+    rt: &mut Runtime,
+    name: &str,
+    expected_type: &Type,
+    value: Expr,
+) -> Option<Object> {
+    let new_value = rt
+        .eval_expr_as(value, expected_type)?
+        .typed_as(expected_type);
+    if !expected_type.accepts(&new_value.ask_type()) {
+        rt.error_handler.set_type_error(format!(
+            "'{}' expects items of type '{}' but provided value of type '{}'",
+            name,
+            expected_type,
+            new_value.ask_type()
+        ));
+        return None;
+    }
+    Some(new_value)
+}
+
+fn get_map_item(rt: &mut Runtime, map: &Map, map_key: &MapKey, key: &Object) -> Option<Object> {
+    let item = map.entries.borrow().get(map_key).cloned();
+    if item.is_none() {
+        rt.error_handler
+            .set_index_error(format!("Key {} not found in map", key));
+    }
+    item
+}
+
+fn eval_map_key(
+    rt: &mut Runtime,
+    key: &Object,
+    entry_types: &Option<(Type, Type)>,
+) -> Option<MapKey> {
+    let key_type = entry_types.as_ref().map(|(key_type, _)| key_type);
+    match map_key(key, key_type) {
+        Ok(map_key) => Some(map_key),
+        Err(err) => {
+            rt.error_handler.set_error(err.kind, err.msg);
+            None
+        }
+    }
+}
+
+fn eval_index(rt: &mut Runtime, index: Expr) -> Option<i64> {
+    let index = rt.eval_expr(index)?;
+    expect_int_index(rt, &index)
+}
+
+fn expect_int_index(rt: &mut Runtime, index: &Object) -> Option<i64> {
+    match index {
+        Object::Int(prim) => Some(prim.value),
+        other => {
+            rt.error_handler.set_type_error(format!(
+                "Index must be of type 'int' but found '{}'",
+                other.ask_type()
+            ));
+            None
+        }
+    }
+}
+
+fn check_bounds(rt: &mut Runtime, index: i64, length: usize, what: &str) -> Option<usize> {
+    if index < 0 || index as usize >= length {
+        rt.error_handler.set_index_error(format!(
+            "Index {} out of range for {} of length {}",
+            index, what, length
+        ));
+        return None;
+    }
+    Some(index as usize)
+}
+
+fn check_slice_bounds(
+    rt: &mut Runtime,
+    start: Option<i64>,
+    end: Option<i64>,
+    length: usize,
+    what: &str,
+) -> Option<(usize, usize)> {
+    let start = start.unwrap_or(0);
+    let end = end.unwrap_or(length as i64);
+
+    if start < 0 || end < start || end as usize > length {
+        rt.error_handler.set_index_error(format!(
+            "Slice {}..{} out of range for {} of length {}",
+            start, end, what, length
+        ));
+        return None;
+    }
+    Some((start as usize, end as usize))
+}
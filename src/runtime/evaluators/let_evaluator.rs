@@ -1,16 +1,20 @@
 use crate::frontend::ast::{Expr, ExprType};
-use crate::runtime::type_system::expr_type_to_object_type;
+use crate::frontend::span::Span;
 use crate::runtime::type_system::Type;
-use crate::runtime::Runtime;
 use crate::runtime::Object;
-use crate::stdlib::collections::Array;
+use crate::runtime::Runtime;
+use crate::stdlib::collections::{Array, Map, MapEntries};
 
 pub fn eval_let_stmt(
     rt: &mut Runtime,
     name: String,
     expr_type: Option<ExprType>,
     expr: Option<Expr>,
+    is_mut: bool,
+    span: &Span,
 ) {
+    let declaration = Declaration { is_mut, span };
+
     if rt.env.borrow().has(&name) {
         rt.error_handler
             .set_name_error(format!("'{}' already declared", name));
@@ -26,22 +30,22 @@ pub fn eval_let_stmt(
     }
 
     if expr_type.is_none() {
-        eval_let_by_type_inference(rt, name, expr.unwrap());
+        eval_let_by_type_inference(rt, name, expr.unwrap(), declaration);
         return;
     }
 
-    let expected_type = expr_type_to_object_type(&expr_type.clone().unwrap());
+    let expected_type = rt.resolve_type(&expr_type.clone().unwrap());
 
     if Type::Void == expected_type {
         rt.error_handler
-            .set_type_error(format!("Can't declared var of type 'void'"));
+            .set_type_error("Can't declared var of type 'void'".to_string());
         return;
     }
 
     if let Type::Array(Some(generic)) = expected_type.clone() {
         if Type::Void == *generic {
             rt.error_handler
-                .set_type_error(format!("Can't declared array of type 'void'"));
+                .set_type_error("Can't declared array of type 'void'".to_string());
             return;
         }
 
@@ -54,58 +58,50 @@ pub fn eval_let_stmt(
                     items_type: Some(*generic),
                 },
                 expected_type,
+                declaration,
             );
             return;
         }
-
-        let evaluated_expr = match rt.eval_expr(expr.unwrap()) {
-            Some(object) => object,
-            None => return,
-        };
-
-        let evaluated_expr_type = evaluated_expr.ask_type();
-
-        if let Type::Array(None) = evaluated_expr_type {
-            add_to_env(
-                rt,
-                &name,
-                Object::Array {
-                    inner: Array::make_empty(),
-                    items_type: Some(*generic),
-                },
-                expected_type,
-            );
-            return;
-        }
-        add_to_env(rt, &name, evaluated_expr, evaluated_expr_type);
-        return;
     }
 
     if expr.is_none() {
-        add_to_env(rt, &name, Object::Null, expected_type);
+        let object = match &expected_type {
+            Type::Map(Some((key_type, value_type))) => Object::Map {
+                inner: Map::from(MapEntries::default()),
+                entry_types: Some((*key_type.clone(), *value_type.clone())),
+            },
+            Type::Optional(_) => Object::Null,
+            _ => {
+                rt.error_handler.set_type_error(format!(
+                    "'{}' of type '{}' must be initialized, declare it as '{}?' to start with null",
+                    name, expected_type, expected_type
+                ));
+                return;
+            }
+        };
+        add_to_env(rt, &name, object, expected_type, declaration);
         return;
     }
 
-    let evaluated_expr = match rt.eval_expr(expr.unwrap()) {
-        Some(evaluated_expr) => evaluated_expr,
+    let evaluated_expr = match rt.eval_expr_as(expr.unwrap(), &expected_type) {
+        Some(evaluated_expr) => evaluated_expr.typed_as(&expected_type),
         None => return,
     };
 
     let evaluated_expr_type = evaluated_expr.ask_type();
 
-    if expected_type != evaluated_expr_type {
+    if !expected_type.accepts(&evaluated_expr_type) {
         rt.error_handler.set_type_error(format!(
             "Assigning value of type {} to variable '{name}' which has type {}",
-            expected_type,
-            evaluated_expr.ask_type()
+            evaluated_expr_type, expected_type
         ));
         return;
     }
 
-    add_to_env(rt, &name, evaluated_expr, expected_type);
+    add_to_env(rt, &name, evaluated_expr, expected_type, declaration);
 }
 
-fn eval_let_by_type_inference(e: &mut Runtime, name: String, expr: Expr) {
+fn eval_let_by_type_inference(e: &mut Runtime, name: String, expr: Expr, declaration: Declaration) {
     let evaluated_expr = match e.eval_expr(expr) {
         Some(evaluated_expr) => evaluated_expr,
         None => return,
@@ -121,10 +117,30 @@ fn eval_let_by_type_inference(e: &mut Runtime, name: String, expr: Expr) {
         return;
     }
 
+    if let Object::Map {
+        entry_types: None, ..
+    } = evaluated_expr
+    {
+        e.error_handler
+            .set_type_error(format!("Can't infer type of map '{}'", name));
+        return;
+    }
+
     let infered_type = evaluated_expr.ask_type();
-    add_to_env(e, &name, evaluated_expr, infered_type);
+    add_to_env(e, &name, evaluated_expr, infered_type, declaration);
+}
+
+struct Declaration<'a> {
+    is_mut: bool,
+    span: &'a Span,
 }
 
-fn add_to_env(e: &mut Runtime, name: &String, object: Object, type_: Type) {
-    e.env.borrow_mut().set(name.clone(), type_, object, true);
+fn add_to_env(e: &mut Runtime, name: &str, object: Object, type_: Type, declaration: Declaration) {
+    e.env.borrow_mut().declare(
+        name.to_string(),
+        type_,
+        object,
+        declaration.is_mut,
+        declaration.span,
+    );
 }
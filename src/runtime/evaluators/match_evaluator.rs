@@ -0,0 +1,147 @@
+use std::{cell::RefCell, rc::Rc};
+
+use crate::context::{Context, ContextType};
+use crate::frontend::ast::{ArmBody, Expr, Identifier, Literal, MatchArm, Pattern};
+use crate::runtime::object::Object;
+use crate::runtime::type_system::Type;
+use crate::runtime::Runtime;
+
+pub fn eval_match_expr(rt: &mut Runtime, subject: Expr, arms: Vec<MatchArm>) -> Option<Object> {
+    let subject = rt.eval_expr(subject)?;
+
+    for arm in arms {
+        let mut bindings: Vec<(String, Object)> = vec![];
+        if !match_pattern(rt, &arm.pattern, &subject, &mut bindings)? {
+            continue;
+        }
+
+        let parent_scope = Rc::clone(&rt.env);
+        let arm_scope = Context::make_from(Rc::clone(&parent_scope), ContextType::IfElse);
+        rt.env = Rc::new(RefCell::new(arm_scope));
+        for (name, value) in bindings {
+            rt.env
+                .borrow_mut()
+                .declare(name, value.ask_type(), value, false, &arm.span);
+        }
+
+        let passed = match arm.guard {
+            Some(guard) => eval_guard(rt, guard),
+            None => Some(true),
+        };
+        let evaluated = match (passed, arm.body) {
+            (Some(true), ArmBody::Expr(expr)) => rt.eval_expr(expr),
+            (Some(true), ArmBody::Block(block)) => rt.eval_block_stmt(&block),
+            _ => None,
+        };
+
+        rt.env = parent_scope;
+        if rt.error_handler.has_error() {
+            rt.error_handler.locate(&arm.span);
+            return None;
+        }
+        if passed == Some(true) {
+            return evaluated;
+        }
+    }
+
+    rt.error_handler
+        .set_sematic(format!("No arm of the match handles {}", subject));
+    None
+}
+
+fn eval_guard(rt: &mut Runtime, guard: Expr) -> Option<bool> {
+    match rt.eval_expr(guard)? {
+        Object::Boolean(val) => Some(val),
+        other => {
+            rt.error_handler.set_type_error(format!(
+                "Match guard must be of type 'boolean' but found '{}'",
+                other.ask_type()
+            ));
+            None
+        }
+    }
+}
+
+fn match_pattern(
+    rt: &mut Runtime,
+    pattern: &Pattern,
+    value: &Object,
+    bindings: &mut Vec<(String, Object)>,
+) -> Option<bool> {
+    match pattern {
+        Pattern::Wildcard => Some(true),
+        Pattern::Binding(name) => {
+            bindings.push((name.clone(), value.clone()));
+            Some(true)
+        }
+        Pattern::Literal(Literal::Null) => Some(matches!(value, Object::Null)),
+        Pattern::Literal(literal) => {
+            let expected = rt.eval_literal_expr(literal.clone())?;
+            if expected.ask_type() != value.ask_type() {
+                rt.error_handler.set_type_error(format!(
+                    "Pattern of type '{}' can't match value of type '{}'",
+                    expected.ask_type(),
+                    value.ask_type()
+                ));
+                return None;
+            }
+            Some(expected.to_string() == value.to_string())
+        }
+        Pattern::Range(start, end) => match value {
+            Object::Int(prim) => Some(*start <= prim.value && prim.value < *end),
+            _ => {
+                rt.error_handler.set_type_error(format!(
+                    "Pattern of type 'range' can't match value of type '{}'",
+                    value.ask_type()
+                ));
+                None
+            }
+        },
+        Pattern::Variant(Identifier(enum_name), Identifier(variant), patterns) => {
+            let (def, found_variant, payload) = match value {
+                Object::EnumValue {
+                    def,
+                    variant,
+                    payload,
+                } if def.name == *enum_name => (def, variant, payload),
+                _ => {
+                    rt.error_handler.set_type_error(format!(
+                        "Pattern of type '{}' can't match value of type '{}'",
+                        Type::Enum(enum_name.clone()),
+                        value.ask_type()
+                    ));
+                    return None;
+                }
+            };
+
+            let types = match def.variants.iter().find(|(name, _)| name == variant) {
+                Some((_, types)) => types,
+                None => {
+                    rt.error_handler
+                        .set_name_error(format!("'{}' has no variant '{}'", def.name, variant));
+                    return None;
+                }
+            };
+            if patterns.len() != types.len() {
+                rt.error_handler.set_type_error(format!(
+                    "'{}.{}' holds {} value(s) but the pattern has {}",
+                    def.name,
+                    variant,
+                    types.len(),
+                    patterns.len()
+                ));
+                return None;
+            }
+
+            if found_variant != variant {
+                return Some(false);
+            }
+            for (pattern, value) in patterns.iter().zip(payload) {
+                if !match_pattern(rt, pattern, value, bindings)? {
+                    return Some(false);
+                }
+            }
+            Some(true)
+        }
+    }
+}
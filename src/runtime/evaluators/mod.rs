@@ -2,3 +2,8 @@ pub mod let_evaluator;
 pub mod func_call_evaluator;
 pub mod func_def_evaluator;
 pub mod field_access;
+pub mod index_evaluator;
+pub mod struct_evaluator;
+pub mod enum_evaluator;
+pub mod match_evaluator;
+pub mod tuple_evaluator;
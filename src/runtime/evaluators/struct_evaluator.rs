@@ -0,0 +1,179 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use super::func_def_evaluator::eval_func_expr;
+use crate::frontend::ast::{Expr, ExprType, Identifier, Method};
+use crate::runtime::object::{Object, StructDef, StructInstance};
+use crate::runtime::type_system::Type;
+use crate::runtime::Runtime;
+use crate::stdlib::FieldsManager;
+
+pub fn eval_struct_def(
+    rt: &mut Runtime,
+    name: String,
+    fields: &[(Identifier, ExprType)],
+    methods: &[Method],
+) {
+    if rt.env.borrow().has(&name) {
+        rt.error_handler
+            .set_name_error(format!("'{}' is already declared", name));
+        return;
+    }
+
+    let fields = fields
+        .iter()
+        .map(|(Identifier(field), type_)| (field.clone(), rt.resolve_type(type_)))
+        .collect::<Vec<(String, Type)>>();
+
+    let mut method_objects: HashMap<String, Object> = HashMap::new();
+    for method in methods {
+        let Identifier(method_name) = &method.name;
+        let function = eval_func_expr(rt, &method.params, &method.body, &method.ret_type);
+        method_objects.insert(method_name.clone(), function);
+    }
+
+    let def = StructDef {
+        name: name.clone(),
+        fields,
+        methods: FieldsManager::make(method_objects),
+    };
+    rt.env.borrow_mut().set(
+        name,
+        Type::TypeAnnot,
+        Object::StructDef(Rc::new(def)),
+        false,
+    );
+}
+
+pub fn eval_struct_literal(
+    rt: &mut Runtime,
+    name: Identifier,
+    values: Vec<(Identifier, Expr)>,
+) -> Option<Object> {
+    let Identifier(name) = name;
+    let def = match rt.env.borrow().resolve(&name) {
+        Some(object) => object.value,
+        None => {
+            rt.error_handler
+                .set_name_error(format!("'{}' is not declared", name));
+            return None;
+        }
+    };
+    let def = match def {
+        Object::StructDef(def) => def,
+        _ => {
+            rt.error_handler
+                .set_type_error(format!("'{}' is not a struct", name));
+            return None;
+        }
+    };
+
+    let mut fields: HashMap<String, Object> = HashMap::new();
+    for (Identifier(field), expr) in values {
+        let expected_type = match def.fields.iter().find(|(name, _)| *name == field) {
+            Some((_, type_)) => type_.clone(),
+            None => {
+                rt.error_handler
+                    .set_name_error(format!("'{}' has no field '{}'", name, field));
+                return None;
+            }
+        };
+        if fields.contains_key(&field) {
+            rt.error_handler
+                .set_name_error(format!("Field '{}' is given more than once", field));
+            return None;
+        }
+
+        let value = rt
+            .eval_expr_as(expr, &expected_type)?
+            .typed_as(&expected_type);
+        if !expected_type.accepts(&value.ask_type()) {
+            rt.error_handler.set_type_error(format!(
+                "Field '{}.{}' expects value of type '{}' but provided value of type '{}'",
+                name,
+                field,
+                expected_type,
+                value.ask_type()
+            ));
+            return None;
+        }
+        fields.insert(field, value);
+    }
+
+    let missing = def
+        .fields
+        .iter()
+        .filter(|(field, _)| !fields.contains_key(field))
+        .map(|(field, _)| format!("'{}'", field))
+        .collect::<Vec<String>>();
+    if !missing.is_empty() {
+        rt.error_handler.set_type_error(format!(
+            "Missing fields {} in '{}'",
+            missing.join(", "),
+            name
+        ));
+        return None;
+    }
+
+    Some(Object::Struct(Rc::new(RefCell::new(StructInstance {
+        def,
+        fields: FieldsManager::make(fields),
+    }))))
+}
+
+pub fn eval_field_assign(
+    rt: &mut Runtime,
+    src: Expr,
+    field: Identifier,
+    value: Expr,
+) -> Option<Object> {
+    let Identifier(field) = field;
+    let instance = match rt.eval_expr(src)? {
+        Object::Struct(instance) => instance,
+        other => {
+            rt.error_handler.set_type_error(format!(
+                "Can't assign field '{}' of type '{}'",
+                field,
+                other.ask_type()
+            ));
+            return None;
+        }
+    };
+
+    let (struct_name, expected_type) = {
+        let instance = instance.borrow();
+        let expected_type = instance
+            .def
+            .fields
+            .iter()
+            .find(|(name, _)| *name == field)
+            .map(|(_, type_)| type_.clone());
+        (instance.def.name.clone(), expected_type)
+    };
+    let expected_type = match expected_type {
+        Some(type_) => type_,
+        None => {
+            rt.error_handler
+                .set_name_error(format!("'{}' has no field '{}'", struct_name, field));
+            return None;
+        }
+    };
+
+    let value = rt
+        .eval_expr_as(value, &expected_type)?
+        .typed_as(&expected_type);
+    if !expected_type.accepts(&value.ask_type()) {
+        rt.error_handler.set_type_error(format!(
+            "Field '{}.{}' expects value of type '{}' but provided value of type '{}'",
+            struct_name,
+            field,
+            expected_type,
+            value.ask_type()
+        ));
+        return None;
+    }
+
+    instance.borrow_mut().fields.fields.insert(field, value);
+    None
+}
@@ -0,0 +1,103 @@
+use crate::frontend::ast::{Binding, Expr, ExprType};
+use crate::frontend::span::Span;
+use crate::runtime::object::Object;
+use crate::runtime::type_system::Type;
+use crate::runtime::Runtime;
+
+pub fn eval_let_tuple(
+    rt: &mut Runtime,
+    names: Vec<Binding>,
+    expr_type: Option<ExprType>,
+    expr: Expr,
+    is_mut: bool,
+    span: &Span,
+) {
+    let value = match rt.eval_expr(expr) {
+        Some(value) => value,
+        None => return,
+    };
+
+    let value_type = match expr_type {
+        Some(expr_type) => {
+            let expected_type = rt.resolve_type(&expr_type);
+            let found_type = value.ask_type();
+            if !expected_type.accepts(&found_type) {
+                rt.error_handler.set_type_error(format!(
+                    "Assigning value of type {} to a tuple of type {}",
+                    found_type, expected_type
+                ));
+                return;
+            }
+            expected_type
+        }
+        None => value.ask_type(),
+    };
+    let value = value.typed_as(&value_type);
+
+    let mut variables = vec![];
+    if destructure(
+        rt,
+        &Binding::Tuple(names),
+        value_type,
+        value,
+        &mut variables,
+    )
+    .is_none()
+    {
+        return;
+    }
+
+    for (name, type_, value) in variables {
+        if rt.env.borrow().has(&name) {
+            rt.error_handler
+                .set_name_error(format!("'{}' already declared", name));
+            return;
+        }
+        rt.env
+            .borrow_mut()
+            .declare(name, type_, value, is_mut, span);
+    }
+}
+
+pub fn destructure(
+    rt: &mut Runtime,
+    binding: &Binding,
+    type_: Type,
+    value: Object,
+    variables: &mut Vec<(String, Type, Object)>,
+) -> Option<()> {
+    let names = match binding {
+        Binding::Name(name) if name == "_" => return Some(()),
+        Binding::Name(name) => {
+            if variables.iter().any(|(bound, _, _)| bound == name) {
+                rt.error_handler
+                    .set_name_error(format!("'{}' is bound more than once", name));
+                return None;
+            }
+            variables.push((name.clone(), type_, value));
+            return Some(());
+        }
+        Binding::Tuple(names) => names,
+    };
+
+    let items = match value {
+        Object::Tuple(items) if items.len() == names.len() => items,
+        _ => {
+            rt.error_handler.set_type_error(format!(
+                "Can't destructure value of type '{}' into {} names",
+                type_,
+                names.len()
+            ));
+            return None;
+        }
+    };
+    let types = match type_ {
+        Type::Tuple(types) => types,
+        _ => items.iter().map(Object::ask_type).collect(),
+    };
+
+    for ((name, type_), item) in names.iter().zip(types).zip(items) {
+        destructure(rt, name, type_, item, variables)?;
+    }
+    Some(())
+}
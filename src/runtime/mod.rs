@@ -3,20 +3,34 @@ pub mod object;
 pub mod runtime_error;
 pub mod type_system;
 
-use std::{cell::RefCell, rc::Rc};
+use std::{
+    cell::{Cell, RefCell},
+    rc::Rc,
+};
 
-use self::evaluators::field_access::eval_field_access;
-use self::object::ObjectInfo;
+use self::evaluators::field_access::{eval_field_access, eval_optional_field_access};
 use crate::context::{Context, ContextType};
 use crate::frontend::ast::*;
-use crate::stdlib::collections::Array;
+use crate::frontend::span::Span;
+use crate::stdlib::collections::{map_key, Array, Map, MapEntries};
 use crate::stdlib::primitives::{make_integer, make_string};
+use evaluators::enum_evaluator::eval_enum_def;
 use evaluators::func_call_evaluator::eval_call_expr;
-use evaluators::func_def_evaluator::eval_func_def;
+use evaluators::func_def_evaluator::{eval_func_def, eval_func_expr};
+use evaluators::index_evaluator::{eval_index_assign, eval_index_expr, eval_slice_expr};
 use evaluators::let_evaluator::eval_let_stmt;
+use evaluators::match_evaluator::eval_match_expr;
+use evaluators::struct_evaluator::{eval_field_assign, eval_struct_def, eval_struct_literal};
+use evaluators::tuple_evaluator::{destructure, eval_let_tuple};
 use object::Object;
 use runtime_error::RuntimeErrorHandler;
-use type_system::Type;
+use type_system::{expr_type_to_object_type, Type};
+
+enum LoopFlow {
+    Next,
+    Stop,
+    Exit(Option<Object>),
+}
 
 pub struct Runtime {
     env: Rc<RefCell<Context>>,
@@ -31,12 +45,24 @@ impl Runtime {
         }
     }
 
+    pub fn resolve_type(&self, expr_type: &ExprType) -> Type {
+        let env = self.env.borrow();
+        expr_type_to_object_type(expr_type)
+            .substitute(&env.type_args)
+            .replace(&|type_| match type_ {
+                Type::UserDefined(name) => match env.resolve(name)?.value {
+                    Object::TypeAlias { type_, .. } => Some(type_),
+                    _ => None,
+                },
+                _ => None,
+            })
+    }
+
     pub fn eval(&mut self, program: Program) -> Option<Object> {
         let mut output: Option<Object> = None;
         for stmt in program {
             let object = self.eval_stmt(stmt);
             if self.error_handler.has_error() {
-                eprintln!("{}", self.error_handler.get_error().unwrap());
                 return None;
             }
             output = object;
@@ -45,29 +71,106 @@ impl Runtime {
     }
 
     fn eval_stmt(&mut self, stmt: Stmt) -> Option<Object> {
-        match stmt {
-            Stmt::Let(Identifier(name), type_, expr) => {
-                eval_let_stmt(self, name, type_, expr);
+        let object = match stmt.kind {
+            StmtKind::Let(Identifier(name), type_, expr, is_mut) => {
+                eval_let_stmt(self, name, type_, expr, is_mut, &stmt.span);
+                None
+            }
+            StmtKind::LetTuple(names, type_, expr, is_mut) => {
+                eval_let_tuple(self, names, type_, expr, is_mut, &stmt.span);
+                None
+            }
+            StmtKind::Const(Identifier(name), type_, expr) => {
+                eval_let_stmt(self, name, type_, Some(expr), false, &stmt.span);
                 None
             }
-            Stmt::Func(Identifier(name), params, body, ret_type) => {
-                eval_func_def(self, name, &params, &body, &ret_type);
+            StmtKind::Func(Identifier(name), params, body, ret_type) => {
+                eval_func_def(self, name, &params, &body, &ret_type, &stmt.span);
                 None
             }
-            Stmt::Return(expr) => self.eval_return(expr),
-            Stmt::Expr(expr) => self.eval_expr(expr),
-            Stmt::If {
+            StmtKind::Return(expr) => self.eval_return(expr),
+            // a match statement can leave the loop or function it is in
+            StmtKind::Expr(Expr {
+                kind: ExprKind::Match(subject, arms),
+                ..
+            }) => eval_match_expr(self, *subject, arms),
+            StmtKind::Expr(expr) => self.eval_expr(expr),
+            StmtKind::If {
                 condition,
                 consequence,
                 alternative,
             } => self.eval_if_stmt(condition, consequence, alternative),
-            Stmt::ForLoop {
+            StmtKind::ForLoop {
                 cursor,
+                value_cursor,
                 iterable,
                 block,
-            } => self.eval_forloop_stmt(cursor, iterable, block),
-            Stmt::Import(target) => self.eval_import_stmt(target),
+                label,
+            } => {
+                let value_cursor = value_cursor.map(|cursor| *cursor);
+                self.eval_forloop_stmt(cursor, value_cursor, iterable, block, label)
+            }
+            StmtKind::While {
+                condition,
+                block,
+                label,
+            } => self.eval_while_stmt(condition, block, label),
+            StmtKind::Break(label) => self.eval_loop_jump("break", label),
+            StmtKind::Continue(label) => self.eval_loop_jump("continue", label),
+            StmtKind::Import(target) => self.eval_import_stmt(target),
+            StmtKind::Struct {
+                name: Identifier(name),
+                fields,
+                methods,
+            } => {
+                eval_struct_def(self, name, &fields, &methods);
+                None
+            }
+            StmtKind::Enum {
+                name: Identifier(name),
+                variants,
+            } => {
+                eval_enum_def(self, name, &variants);
+                None
+            }
+            StmtKind::TypeAlias {
+                name: Identifier(name),
+                type_,
+            } => {
+                self.eval_type_alias(name, &type_);
+                None
+            }
+        };
+        self.error_handler.locate(&stmt.span);
+        object
+    }
+
+    fn eval_type_alias(&mut self, name: String, type_: &ExprType) {
+        if self.env.borrow().has(&name) {
+            self.error_handler
+                .set_name_error(format!("'{}' is already declared", name));
+            return;
         }
+        let type_ = self.resolve_type(type_);
+        let cyclic = Cell::new(false);
+        type_.replace(&|type_| {
+            if matches!(type_, Type::UserDefined(found) if *found == name) {
+                cyclic.set(true);
+            }
+            None
+        });
+        if cyclic.get() {
+            self.error_handler
+                .set_type_error(format!("Type alias '{}' refers to itself", name));
+            return;
+        }
+        let alias = Object::TypeAlias {
+            name: name.clone(),
+            type_,
+        };
+        self.env
+            .borrow_mut()
+            .set(name, Type::TypeAnnot, alias, false);
     }
 
     fn eval_import_stmt(&mut self, target: String) -> Option<Object> {
@@ -86,123 +189,228 @@ impl Runtime {
 
     fn eval_forloop_stmt(
         &mut self,
-        cursor: String,
+        cursor: Cursor,
+        value_cursor: Option<Cursor>,
         iterable: Expr,
         block: BlockStmt,
+        label: Option<String>,
     ) -> Option<Object> {
-        let iterable_object = match self.eval_expr(iterable) {
-            Some(object) => object,
-            None => return None,
-        };
-        match iterable_object {
+        match self.eval_expr(iterable)? {
+            Object::Map { inner, entry_types } => {
+                self.eval_map_loop(cursor, value_cursor, inner, entry_types, block, label)
+            }
+            Object::Array { inner, items_type } => {
+                self.eval_array_loop(cursor, value_cursor, inner.inner, items_type, block, label)
+            }
+            object if value_cursor.is_some() => {
+                self.error_handler.set_type_error(format!(
+                    "Only maps and arrays can be iterated with two cursors but found '{}'",
+                    object.ask_type()
+                ));
+                None
+            }
             Object::Range { start, end, step } => {
-                self.eval_range_forloop(cursor, start, end, step, block)
+                self.eval_range_forloop(cursor, start, end, step, block, label)
             }
-            Object::Array {
-                inner,
-                items_type: _,
-            } => self.eval_array_loop(cursor, inner.inner, block),
-            _ => {
+            object => {
+                self.error_handler.set_type_error(format!(
+                    "Type '{}' is not iterable, for loops go through ranges, arrays and maps",
+                    object.ask_type()
+                ));
+                None
+            }
+        }
+    }
+
+    fn bind_cursor(
+        &mut self,
+        cursor: &Cursor,
+        items_type: Type,
+        item: Object,
+        cursors: &mut Vec<(String, Type, Object, Span)>,
+    ) -> Option<()> {
+        let type_ = match &cursor.type_ {
+            Some(annotation) => {
+                let expected = self.resolve_type(annotation);
+                if !expected.accepts(&items_type) {
+                    self.error_handler.set_type_error(format!(
+                        "Cursor of type '{}' can't take items of type '{}'",
+                        expected, items_type
+                    ));
+                    return None;
+                }
+                expected
+            }
+            None => items_type,
+        };
+        let mut variables = vec![];
+        destructure(self, &cursor.binding, type_, item, &mut variables)?;
+        for (name, type_, value) in variables {
+            if cursors.iter().any(|(bound, ..)| *bound == name) {
                 self.error_handler
-                    .set_type_error(format!("for loop works only with range (for now)"));
+                    .set_name_error(format!("'{}' is bound more than once", name));
                 return None;
             }
+            cursors.push((name, type_, value, cursor.span.clone()));
         }
+        Some(())
     }
 
     fn eval_array_loop(
         &mut self,
-        cursor: String,
-        mut array: Vec<Object>,
-        block: Vec<Stmt>,
+        cursor: Cursor,
+        value_cursor: Option<Cursor>,
+        array: Vec<Object>,
+        items_type: Option<Type>,
+        block: BlockStmt,
+        label: Option<String>,
     ) -> Option<Object> {
-        if array.is_empty() {
-            return None;
-        }
-
-        let parent_scope = Rc::clone(&self.env);
-        let loop_scope = Context::make_from(Rc::clone(&parent_scope), ContextType::Loop);
-        self.env = Rc::new(RefCell::new(loop_scope));
-
-        self.env
-            .borrow_mut()
-            .set(cursor.clone(), Type::Int, array.remove(0), true);
-
-        loop {
-            let evalted_block = self.eval_block_stmt(&block);
-
-            if self.error_handler.has_error() {
-                return None;
-            }
-
-            match evalted_block.unwrap() {
-                Object::RetVal(val) => return Some(Object::RetVal(val)),
-                _ => {}
+        for (index, item) in array.into_iter().enumerate() {
+            let item_type = items_type.clone().unwrap_or_else(|| item.ask_type());
+            let mut cursors = vec![];
+            match &value_cursor {
+                Some(value_cursor) => {
+                    let index = Object::Int(make_integer(index as i64));
+                    self.bind_cursor(&cursor, Type::Int, index, &mut cursors)?;
+                    self.bind_cursor(value_cursor, item_type, item, &mut cursors)?;
+                }
+                None => self.bind_cursor(&cursor, item_type, item, &mut cursors)?,
             }
-
-            if array.is_empty() {
-                break;
+            match self.eval_loop_body(&label, cursors, &block) {
+                LoopFlow::Next => continue,
+                LoopFlow::Stop => break,
+                LoopFlow::Exit(object) => return object,
             }
-
-            // update counter
-            self.env
-                .borrow_mut()
-                .mutate(cursor.clone(), array.remove(0));
         }
-
-        self.env = parent_scope;
         None
     }
 
     fn eval_range_forloop(
         &mut self,
-        cursor: String,
+        cursor: Cursor,
         start: i64,
         end: i64,
         step: i64,
         block: BlockStmt,
+        label: Option<String>,
+    ) -> Option<Object> {
+        let step = if step == 0 { 1 } else { step };
+        let mut counter = start;
+
+        while (step > 0 && counter < end) || (step < 0 && counter > end) {
+            let item = Object::Int(make_integer(counter));
+            let mut cursors = vec![];
+            self.bind_cursor(&cursor, Type::Int, item, &mut cursors)?;
+            match self.eval_loop_body(&label, cursors, &block) {
+                LoopFlow::Next => counter += step,
+                LoopFlow::Stop => break,
+                LoopFlow::Exit(object) => return object,
+            }
+        }
+        None
+    }
+
+    fn eval_map_loop(
+        &mut self,
+        key_cursor: Cursor,
+        value_cursor: Option<Cursor>,
+        map: Map,
+        entry_types: Option<(Type, Type)>,
+        block: BlockStmt,
+        label: Option<String>,
     ) -> Option<Object> {
+        let entries = map.entries.borrow().items().to_vec();
+        for (key, value) in entries {
+            let key = key.to_object();
+            let (key_type, value_type) = entry_types
+                .clone()
+                .unwrap_or_else(|| (key.ask_type(), value.ask_type()));
+
+            let mut cursors = vec![];
+            self.bind_cursor(&key_cursor, key_type, key, &mut cursors)?;
+            if let Some(value_cursor) = &value_cursor {
+                self.bind_cursor(value_cursor, value_type, value, &mut cursors)?;
+            }
+            match self.eval_loop_body(&label, cursors, &block) {
+                LoopFlow::Next => continue,
+                LoopFlow::Stop => break,
+                LoopFlow::Exit(object) => return object,
+            }
+        }
+        None
+    }
+
+    fn eval_while_stmt(
+        &mut self,
+        condition: Expr,
+        block: BlockStmt,
+        label: Option<String>,
+    ) -> Option<Object> {
+        loop {
+            let evaluated_cond = self.eval_expr(condition.clone())?;
+            if !self.is_truthy(evaluated_cond) {
+                return None;
+            }
+            match self.eval_loop_body(&label, vec![], &block) {
+                LoopFlow::Next => continue,
+                LoopFlow::Stop => return None,
+                LoopFlow::Exit(object) => return object,
+            }
+        }
+    }
+
+    fn eval_loop_body(
+        &mut self,
+        label: &Option<String>,
+        cursors: Vec<(String, Type, Object, Span)>,
+        block: &BlockStmt,
+    ) -> LoopFlow {
         let parent_scope = Rc::clone(&self.env);
-        let loop_scope = Context::make_from(Rc::clone(&parent_scope), ContextType::Loop);
+        let loop_scope = Context::make_loop(Rc::clone(&parent_scope), label.clone());
         self.env = Rc::new(RefCell::new(loop_scope));
 
-        self.env.borrow_mut().set(
-            cursor.clone(),
-            Type::Int,
-            Object::Int(make_integer(start)),
-            true,
-        );
+        for (name, type_, value, span) in cursors {
+            self.env
+                .borrow_mut()
+                .declare(name, type_, value, false, &span);
+        }
 
-        for _ in (start..end).step_by(step as usize) {
-            let evalted_block = self.eval_block_stmt(&block);
+        let evaluated_block = self.eval_block_stmt(block);
+        self.env = parent_scope;
 
-            if self.error_handler.has_error() {
-                return None;
-            }
+        if self.error_handler.has_error() {
+            return LoopFlow::Exit(None);
+        }
 
-            if evalted_block.is_none() {
-                continue;
+        match evaluated_block {
+            Some(Object::Break(target)) if target.is_none() || target == *label => LoopFlow::Stop,
+            Some(Object::Continue(target)) if target.is_none() || target == *label => {
+                LoopFlow::Next
             }
-
-            match evalted_block.unwrap() {
-                Object::RetVal(val) => return Some(Object::RetVal(val)),
-                _ => {}
+            Some(flow @ (Object::RetVal(_) | Object::Break(_) | Object::Continue(_))) => {
+                LoopFlow::Exit(Some(flow))
             }
+            _ => LoopFlow::Next,
+        }
+    }
 
-            // update counter
-            let old_val = match self.env.borrow().resolve(&cursor).unwrap().value {
-                Object::Int(val) => val,
-                _ => return None,
-            };
-            let incrementor = if step == 0 { 1 } else { step };
-            self.env.borrow_mut().mutate(
-                cursor.clone(),
-                Object::Int(make_integer(old_val.value + incrementor)),
-            );
+    fn eval_loop_jump(&mut self, keyword: &str, label: Option<String>) -> Option<Object> {
+        if !self.env.borrow().in_loop(label.as_deref()) {
+            match label {
+                Some(label) => self
+                    .error_handler
+                    .set_sematic(format!("No loop labelled '{}' around '{}'", label, keyword)),
+                None => self
+                    .error_handler
+                    .set_sematic(format!("'{}' outside of loop", keyword)),
+            }
+            return None;
         }
 
-        self.env = parent_scope;
-        None
+        match keyword {
+            "break" => Some(Object::Break(label)),
+            _ => Some(Object::Continue(label)),
+        }
     }
 
     fn is_truthy(&mut self, object: Object) -> bool {
@@ -218,99 +426,259 @@ impl Runtime {
         &mut self,
         condition: Expr,
         consequence: BlockStmt,
-        alternative: Option<BlockStmt>,
+        alternative: Option<Else>,
     ) -> Option<Object> {
-        let evaluated_cond = match self.eval_expr(condition) {
-            Some(object) => object,
-            None => return None,
-        };
-
-        let parent_scope = Rc::clone(&self.env);
-        let ifelse_scope = Context::make_from(Rc::clone(&parent_scope), ContextType::IfElse);
-        self.env = Rc::new(RefCell::new(ifelse_scope));
+        let evaluated_cond = self.eval_expr(condition.clone())?;
 
         if self.is_truthy(evaluated_cond) {
-            return self.eval_block_stmt(&consequence);
+            let narrowed = self.narrowed_types(&condition, true);
+            return self.eval_ifelse_block(&consequence, &narrowed);
         }
 
-        if alternative.is_some() {
-            return self.eval_block_stmt(&alternative.unwrap());
+        let narrowed = self.narrowed_types(&condition, false);
+        match alternative {
+            Some(Else::Block(block)) => self.eval_ifelse_block(&block, &narrowed),
+            Some(Else::If(next)) if narrowed.is_empty() => self.eval_stmt(*next),
+            Some(Else::If(next)) => self.eval_ifelse_block(&vec![*next], &narrowed),
+            None => None,
         }
+    }
 
-        self.env = parent_scope;
-        None
+    fn eval_ifelse_block(
+        &mut self,
+        block: &BlockStmt,
+        narrowed: &[(String, Type)],
+    ) -> Option<Object> {
+        self.with_narrowed(narrowed, |rt| rt.eval_block_stmt(block))
     }
 
-    fn eval_expr(&mut self, expr: Expr) -> Option<Object> {
-        match expr {
-            Expr::Literal(literal) => self.eval_literal_expr(literal),
-            Expr::Identifier(identifier) => self.resolve_identfier(identifier),
-            Expr::Call(func, args) => eval_call_expr(self, *func, args),
-            Expr::Infix(lhs, infix, rhs) => self.eval_infix_expr(*lhs, infix, *rhs),
-            Expr::Prefix(prefix, expr) => self.eval_prefix_expr(prefix, *expr),
-            Expr::Postfix(expr, postfix) => self.eval_postfix_expr(*expr, postfix),
-            Expr::Assign(identifier, expr) => self.eval_assign_expr(identifier, *expr),
-            Expr::FieldAcc(src, target) => eval_field_access(self, *src, *target),
-        }
-    }
-
-    fn eval_fn_call_args(&mut self, args: Vec<Expr>) -> Option<Vec<ObjectInfo>> {
-        let mut checked_args: Vec<ObjectInfo> = vec![];
-        for arg in args {
-            let arg = match self.eval_expr(arg) {
-                Some(object) => ObjectInfo {
-                    is_mut: true,
-                    type_: object.ask_type(),
-                    value: object,
-                },
-                None => return None,
-            };
-            checked_args.push(arg);
+    fn with_narrowed<T>(
+        &mut self,
+        narrowed: &[(String, Type)],
+        eval: impl FnOnce(&mut Self) -> T,
+    ) -> T {
+        let parent_scope = Rc::clone(&self.env);
+        let mut ifelse_scope = Context::make_from(Rc::clone(&parent_scope), ContextType::IfElse);
+        for (name, type_) in narrowed {
+            ifelse_scope.narrow(name, type_.clone());
         }
-        Some(checked_args)
+        self.env = Rc::new(RefCell::new(ifelse_scope));
+
+        let evaluated = eval(self);
+
+        self.env = parent_scope;
+        evaluated
     }
 
-    fn eval_postfix_expr(&mut self, expr: Expr, postfix: Postfix) -> Option<Object> {
-        let evaluated_expr = match self.eval_expr(expr) {
-            Some(object) => object,
-            None => return None,
+    fn narrowed_types(&self, condition: &Expr, holds: bool) -> Vec<(String, Type)> {
+        narrowings(condition, holds)
+            .into_iter()
+            .filter_map(|(name, narrowing)| {
+                let type_ = self.narrow_type(&name, &narrowing)?;
+                Some((name, type_))
+            })
+            .collect()
+    }
+
+    fn narrow_type(&self, name: &str, narrowing: &Narrowing) -> Option<Type> {
+        let declared_type = self.env.borrow().resolve(name)?.type_;
+        declared_type.narrow(narrowing, |type_| self.resolve_type(type_))
+    }
+
+    pub fn set_not_assignable(&mut self, name: &str) {
+        let msg = match self.env.borrow().declaration(name) {
+            Some(span) => format!(
+                "'{}' is not assignable, it's declared immutable at {}",
+                name, span
+            ),
+            None => format!("'{}' is not assignable", name),
         };
+        self.error_handler.set_name_error(msg);
+    }
 
-        let old_value = match evaluated_expr {
-            Object::Int(val) => val,
-            _ => {
+    pub fn eval_non_null(&mut self, expr: Expr) -> Option<Object> {
+        let span = expr.span.clone();
+        let name = match &expr.kind {
+            ExprKind::Identifier(Identifier(name)) => Some(name.clone()),
+            _ => None,
+        };
+        let object = self.eval_expr(expr)?;
+        if let (Object::Null, Some(name)) = (&object, name) {
+            let declared_type = self.env.borrow().resolve(&name).map(|info| info.type_);
+            if let Some(type_ @ Type::Optional(_)) = declared_type {
                 self.error_handler.set_type_error(format!(
-                    "'{}' operation is only allowed for type 'number'",
-                    postfix
+                    "'{}' of type '{}' is null, check it with 'if {} != null' or use '??'",
+                    name, type_, name
                 ));
+                self.error_handler.locate(&span);
                 return None;
             }
-        };
+        }
+        Some(object)
+    }
 
-        match postfix {
-            Postfix::Increment => Some(Object::Int(make_integer(old_value.value + 1))),
-            Postfix::Decrement => Some(Object::Int(make_integer(old_value.value - 1))),
+    pub fn eval_expr_as(&mut self, expr: Expr, expected: &Type) -> Option<Object> {
+        match (expr.kind, expected) {
+            (ExprKind::Literal(Literal::Array(items)), Type::Array(Some(items_type))) => {
+                let array = self.eval_array_literal(items, Some(items_type));
+                self.error_handler.locate(&expr.span);
+                array
+            }
+            (ExprKind::Literal(Literal::Map(entries)), Type::Map(Some((key_type, value_type)))) => {
+                let map = self.eval_map_literal(entries, Some((key_type, value_type)));
+                self.error_handler.locate(&expr.span);
+                map
+            }
+            (kind, Type::Optional(inner)) => self.eval_expr_as(
+                Expr {
+                    kind,
+                    span: expr.span,
+                },
+                inner,
+            ),
+            (kind, _) => self.eval_expr(Expr {
+                kind,
+                span: expr.span,
+            }),
         }
     }
 
+    fn eval_expr(&mut self, expr: Expr) -> Option<Object> {
+        let object = match expr.kind {
+            ExprKind::Literal(literal) => self.eval_literal_expr(literal),
+            ExprKind::Identifier(identifier) => self.resolve_identfier(identifier),
+            ExprKind::Call(func, args) => eval_call_expr(self, *func, args),
+            ExprKind::Infix(lhs, infix, rhs) => self.eval_infix_expr(*lhs, infix, *rhs),
+            ExprKind::Prefix(prefix, expr) => self.eval_prefix_expr(prefix, *expr),
+            ExprKind::Postfix(expr, postfix) => self.eval_postfix_expr(*expr, postfix),
+            ExprKind::Assign(identifier, expr) => self.eval_assign_expr(identifier, *expr),
+            ExprKind::FieldAcc(src, target) => eval_field_access(self, *src, *target),
+            ExprKind::OptionalFieldAcc(src, target) => {
+                eval_optional_field_access(self, *src, *target)
+            }
+            ExprKind::Index(target, index) => eval_index_expr(self, *target, *index),
+            ExprKind::Slice(target, start, end) => eval_slice_expr(self, *target, start, end),
+            ExprKind::IndexAssign(target, index, value) => {
+                eval_index_assign(self, *target, *index, *value)
+            }
+            ExprKind::Interpolated(parts) => self.eval_interpolated_string(parts),
+            ExprKind::StructLit(name, fields) => eval_struct_literal(self, name, fields),
+            ExprKind::FieldAssign(src, field, value) => {
+                eval_field_assign(self, *src, field, *value)
+            }
+            ExprKind::Func(params, body, ret_type) => {
+                Some(eval_func_expr(self, &params, &body, &ret_type))
+            }
+            ExprKind::Match(subject, arms) => {
+                let keyword = match eval_match_expr(self, *subject, arms)? {
+                    Object::RetVal(_) => "return",
+                    Object::Break(_) => "break",
+                    Object::Continue(_) => "continue",
+                    value => return Some(value),
+                };
+                self.error_handler
+                    .set_sematic(format!("'{}' can't leave a match used as a value", keyword));
+                None
+            }
+            ExprKind::Is(expr, type_) => {
+                let value = self.eval_expr(*expr)?;
+                let type_ = self.resolve_type(&type_);
+                Some(Object::Boolean(type_.accepts(&value.ask_type())))
+            }
+            ExprKind::Spread(_) => {
+                self.error_handler.set_sematic(
+                    "Spread '...' is only allowed in calls and array literals".to_string(),
+                );
+                None
+            }
+        };
+        self.error_handler.locate(&expr.span);
+        object
+    }
+
+    fn eval_postfix_expr(&mut self, expr: Expr, postfix: Postfix) -> Option<Object> {
+        let step = match postfix {
+            Postfix::Increment => 1,
+            Postfix::Decrement => -1,
+        };
+        let (old_value, _) = self.eval_update_expr(expr, step, &postfix.to_string())?;
+        Some(old_value)
+    }
+
     fn eval_prefix_expr(&mut self, prefix: Prefix, expr: Expr) -> Option<Object> {
-        let evaluated_expr = match self.eval_expr(expr) {
-            Some(object) => object,
-            None => return None,
+        let step = match prefix {
+            Prefix::Increment => Some(1),
+            Prefix::Decrement => Some(-1),
+            _ => None,
         };
+        if let Some(step) = step {
+            let (_, new_value) = self.eval_update_expr(expr, step, &prefix.to_string())?;
+            return Some(new_value);
+        }
+
+        let evaluated_expr = self.eval_expr(expr)?;
 
         match prefix {
             Prefix::Not => self.eval_not_prefix(evaluated_expr),
             Prefix::Plus => self.eval_plus_prefix(prefix, evaluated_expr),
             Prefix::Minus => self.eval_minus_prefix(prefix, evaluated_expr),
+            Prefix::Increment | Prefix::Decrement => unreachable!(),
         }
     }
 
+    fn eval_update_expr(
+        &mut self,
+        expr: Expr,
+        step: i64,
+        operator: &str,
+    ) -> Option<(Object, Object)> {
+        let ExprKind::Identifier(Identifier(name)) = expr.kind else {
+            self.error_handler
+                .set_type_error(format!("'{}' can only be applied to a variable", operator));
+            return None;
+        };
+
+        let old_value = match self.env.borrow().resolve(&name) {
+            Some(object) => object,
+            None => {
+                self.error_handler
+                    .set_name_error(format!("'{}' is not declared", &name));
+                return None;
+            }
+        };
+
+        if !old_value.is_mut {
+            self.set_not_assignable(&name);
+            return None;
+        }
+
+        let new_value = match old_value.value {
+            Object::Int(ref val) => Object::Int(make_integer(val.value + step)),
+            Object::Float(val) => Object::Float(val + step as f64),
+            _ => {
+                self.error_handler.set_type_error(format!(
+                    "'{}' operation is only allowed for type 'number'",
+                    operator
+                ));
+                return None;
+            }
+        };
+
+        self.env.borrow_mut().mutate(name, new_value.clone());
+        Some((old_value.value, new_value))
+    }
+
     fn eval_not_prefix(&mut self, evaluated_expr: Object) -> Option<Object> {
         match evaluated_expr {
-            Object::Null => Some(Object::Boolean(true)),
             Object::Boolean(val) => Some(Object::Boolean(!val)),
-            _ => Some(Object::Boolean(false)),
+            object => {
+                self.error_handler.set_type_error(format!(
+                    "'{}' prefix expects type boolean but found {}",
+                    Prefix::Not,
+                    object.ask_type()
+                ));
+                None
+            }
         }
     }
 
@@ -321,7 +689,7 @@ impl Runtime {
             _ => {
                 self.error_handler
                     .set_type_error(format!("'{}' prefix is for type number", prefix));
-                return None;
+                None
             }
         }
     }
@@ -333,7 +701,7 @@ impl Runtime {
             _ => {
                 self.error_handler
                     .set_type_error(format!("'{}' prefix is for type number", prefix));
-                return None;
+                None
             }
         }
     }
@@ -348,10 +716,8 @@ impl Runtime {
         if expr.is_none() {
             return Some(Object::RetVal(Box::new(Object::Null)));
         }
-        match self.eval_expr(expr.unwrap()) {
-            Some(object) => Some(Object::RetVal(Box::new(object))),
-            None => None,
-        }
+        self.eval_expr(expr.unwrap())
+            .map(|object| Object::RetVal(Box::new(object)))
     }
 
     fn eval_assign_expr(&mut self, identifier: Identifier, expr: Expr) -> Option<Object> {
@@ -366,31 +732,33 @@ impl Runtime {
         };
 
         if !old_value.is_mut {
-            self.error_handler
-                .set_name_error(format!("'{}' is not assignable", name));
+            self.set_not_assignable(&name);
             return None;
         }
 
-        let new_value = match self.eval_expr(expr) {
-            Some(value) => value,
-            None => return None,
-        };
+        let declared_type = self
+            .env
+            .borrow()
+            .declared_type(&name)
+            .unwrap_or(old_value.type_);
+        let new_value = self.eval_expr_as(expr, &declared_type)?;
 
-        if let Type::Array(Some(old_array_items_type)) = old_value.type_ {
+        if let Type::Array(Some(old_array_items_type)) = declared_type {
             self.assign_array(name, *old_array_items_type, new_value);
             return None;
         }
 
         let new_value_type = new_value.ask_type();
 
-        if old_value.type_ != new_value_type {
+        if !declared_type.accepts(&new_value_type) {
             self.error_handler.set_type_error(format!(
                 "'{}' expects value of type '{}' but provided value of type '{}'",
-                name, old_value.type_, new_value_type,
+                name, declared_type, new_value_type,
             ));
             return None;
         }
 
+        self.env.borrow_mut().widen(&name);
         self.env.borrow_mut().mutate(name, new_value);
         None
     }
@@ -425,7 +793,7 @@ impl Runtime {
 
         let new_array_items_type = *new_array_items_type.unwrap();
 
-        if new_array_items_type != old_array_items_type {
+        if !old_array_items_type.accepts(&new_array_items_type) {
             self.error_handler.set_type_error(format!(
                 "'{}' expects array of type '{}' but provided array of type '{}'",
                 name, old_array_items_type, new_array_items_type
@@ -439,8 +807,9 @@ impl Runtime {
 
     fn eval_block_stmt(&mut self, block: &BlockStmt) -> Option<Object> {
         for stmt in block {
-            if let Some(Object::RetVal(object)) = self.eval_stmt(stmt.clone()) {
-                return Some(Object::RetVal(object));
+            let object = self.eval_stmt(stmt.clone());
+            if let Some(Object::RetVal(_) | Object::Break(_) | Object::Continue(_)) = object {
+                return object;
             }
 
             if self.error_handler.has_error() {
@@ -451,8 +820,20 @@ impl Runtime {
     }
 
     fn eval_infix_expr(&mut self, lhs: Expr, infix: Infix, rhs: Expr) -> Option<Object> {
-        let lhs = self.eval_expr(lhs);
-        let rhs = self.eval_expr(rhs);
+        if let Infix::And | Infix::Or = infix {
+            return self.eval_logical_expr(lhs, infix, rhs);
+        }
+        if let Infix::NullCoalescing = infix {
+            return match self.eval_expr(lhs)? {
+                Object::Null => self.eval_expr(rhs),
+                value => Some(value),
+            };
+        }
+
+        let (lhs, rhs) = match infix {
+            Infix::Equal | Infix::NotEqual => (self.eval_expr(lhs), self.eval_expr(rhs)),
+            _ => (self.eval_non_null(lhs), self.eval_non_null(rhs)),
+        };
 
         if lhs.is_none() || rhs.is_none() {
             return None;
@@ -461,12 +842,21 @@ impl Runtime {
         let lhs = lhs.unwrap();
         let rhs = rhs.unwrap();
 
-        if lhs.ask_type() != rhs.ask_type() {
+        if matches!(lhs, Object::Null) || matches!(rhs, Object::Null) {
+            let both_null = matches!((&lhs, &rhs), (Object::Null, Object::Null));
+            if let Infix::Equal | Infix::NotEqual = infix {
+                return Some(Object::Boolean(both_null == matches!(infix, Infix::Equal)));
+            }
+        }
+
+        let (lhs_type, rhs_type) = (lhs.ask_type(), rhs.ask_type());
+        // `[1] == xs` where `xs` is an `Array<int?>` still compares the items
+        let comparable = matches!(infix, Infix::Equal | Infix::NotEqual)
+            && (lhs_type.accepts(&rhs_type) || rhs_type.accepts(&lhs_type));
+        if lhs_type != rhs_type && !comparable {
             self.error_handler.set_type_error(format!(
                 "'{}' operation not allowed between types {} and {}",
-                infix,
-                lhs.ask_type(),
-                rhs.ask_type(),
+                infix, lhs_type, rhs_type,
             ));
             return None;
         }
@@ -496,7 +886,76 @@ impl Runtime {
                 }
                 None
             }
-            _ => None,
+            Object::EnumValue { .. } => self.eval_infix_enum_expr(lhs, infix, rhs),
+            _ => self.eval_infix_equality_expr(lhs, infix, rhs),
+        }
+    }
+
+    fn eval_infix_equality_expr(
+        &mut self,
+        lhs: Object,
+        infix: Infix,
+        rhs: Object,
+    ) -> Option<Object> {
+        match (&infix, lhs.equals(&rhs)) {
+            (Infix::Equal, Some(equal)) => Some(Object::Boolean(equal)),
+            (Infix::NotEqual, Some(equal)) => Some(Object::Boolean(!equal)),
+            _ => {
+                self.error_handler.set_type_error(format!(
+                    "'{}' operation not allowed between types {} and {}",
+                    infix,
+                    lhs.ask_type(),
+                    rhs.ask_type(),
+                ));
+                None
+            }
+        }
+    }
+
+    fn eval_logical_expr(&mut self, lhs: Expr, infix: Infix, rhs: Expr) -> Option<Object> {
+        // the rhs only runs when the lhs is true for `&&` and false for `||`
+        let narrowed = self.narrowed_types(&lhs, matches!(infix, Infix::And));
+        let lhs = self.eval_logical_operand(lhs, &infix)?;
+        match (&infix, lhs) {
+            (Infix::And, false) => return Some(Object::Boolean(false)),
+            (Infix::Or, true) => return Some(Object::Boolean(true)),
+            _ => {}
+        }
+        let rhs = match narrowed.is_empty() {
+            true => self.eval_logical_operand(rhs, &infix)?,
+            false => self.with_narrowed(&narrowed, |rt| rt.eval_logical_operand(rhs, &infix))?,
+        };
+        Some(Object::Boolean(rhs))
+    }
+
+    fn eval_logical_operand(&mut self, operand: Expr, infix: &Infix) -> Option<bool> {
+        match self.eval_expr(operand)? {
+            Object::Boolean(val) => Some(val),
+            object => {
+                self.error_handler.set_type_error(format!(
+                    "'{}' operation expects type boolean but found {}",
+                    infix,
+                    object.ask_type()
+                ));
+                None
+            }
+        }
+    }
+
+    fn eval_infix_enum_expr(&mut self, lhs: Object, infix: Infix, rhs: Object) -> Option<Object> {
+        let equal = lhs.to_string() == rhs.to_string();
+        match infix {
+            Infix::Equal => Some(Object::Boolean(equal)),
+            Infix::NotEqual => Some(Object::Boolean(!equal)),
+            _ => {
+                self.error_handler.set_type_error(format!(
+                    "'{}' operation not allowed between types {} and {}",
+                    infix,
+                    lhs.ask_type(),
+                    rhs.ask_type(),
+                ));
+                None
+            }
         }
     }
 
@@ -528,6 +987,9 @@ impl Runtime {
             Infix::GratherThan => Object::Boolean(lhs_val > rhs_val),
             Infix::GratherOrEqual => Object::Boolean(lhs_val >= rhs_val),
             Infix::NotEqual => Object::Boolean(lhs_val != rhs_val),
+            Infix::And | Infix::Or | Infix::NullCoalescing => {
+                unreachable!("logical operators are short-circuited")
+            }
         }
     }
 
@@ -544,15 +1006,18 @@ impl Runtime {
             Infix::GratherThan => Object::Boolean(lhs_val > rhs_val),
             Infix::GratherOrEqual => Object::Boolean(lhs_val >= rhs_val),
             Infix::NotEqual => Object::Boolean(lhs_val != rhs_val),
+            Infix::And | Infix::Or | Infix::NullCoalescing => {
+                unreachable!("logical operators are short-circuited")
+            }
         }
     }
 
     fn eval_infix_bool_expr(&mut self, lhs_val: bool, infix: Infix, rhs_val: bool) -> Object {
         match infix {
             Infix::Equal => Object::Boolean(lhs_val == rhs_val),
-            Infix::LessThan => Object::Boolean(lhs_val < rhs_val),
+            Infix::LessThan => Object::Boolean(!lhs_val & rhs_val),
             Infix::LessOrEqual => Object::Boolean(lhs_val <= rhs_val),
-            Infix::GratherThan => Object::Boolean(lhs_val > rhs_val),
+            Infix::GratherThan => Object::Boolean(lhs_val & !rhs_val),
             Infix::GratherOrEqual => Object::Boolean(lhs_val >= rhs_val),
             Infix::NotEqual => Object::Boolean(lhs_val != rhs_val),
             _ => {
@@ -572,46 +1037,132 @@ impl Runtime {
             Literal::Null => Some(Object::Null),
             Literal::Int(val) => Some(Object::Int(make_integer(val))),
             Literal::Float(val) => Some(Object::Float(val)),
-            Literal::Array(val) => self.eval_array_literal(val),
+            Literal::Array(val) => self.eval_array_literal(val, None),
+            Literal::Map(entries) => self.eval_map_literal(entries, None),
+            Literal::Tuple(items) => {
+                let mut objects: Vec<Object> = vec![];
+                for item in items {
+                    objects.push(self.eval_expr(item)?);
+                }
+                Some(Object::Tuple(objects))
+            }
         }
     }
 
-    fn eval_array_literal(&mut self, array_literal: Vec<Expr>) -> Option<Object> {
-        if array_literal.is_empty() {
-            return Some(Object::Array {
-                inner: Array::make_empty(),
-                items_type: None,
-            });
+    fn eval_interpolated_string(&mut self, parts: Vec<Expr>) -> Option<Object> {
+        let mut output = String::new();
+        for part in parts {
+            match self.eval_expr(part)? {
+                Object::String(val) => output.push_str(&val.value),
+                object => output.push_str(&object.to_string()),
+            }
         }
+        Some(Object::String(make_string(output)))
+    }
 
-        let mut expr_array = array_literal.to_owned();
-        let first_item = match self.eval_expr(expr_array.remove(0)) {
-            Some(object) => object,
-            None => return None,
-        };
-
-        let first_item_type = first_item.ask_type();
-
-        let mut objects: Vec<Object> = vec![first_item];
-
-        for expr in expr_array {
-            let item = match self.eval_expr(expr) {
-                Some(obj) => obj,
-                None => return None,
+    fn eval_array_literal(
+        &mut self,
+        array_literal: Vec<Expr>,
+        expected: Option<&Type>,
+    ) -> Option<Object> {
+        let mut items_type: Option<Type> = expected.cloned();
+        let mut objects: Vec<Object> = vec![];
+
+        for expr in array_literal {
+            let (items, type_) = match expr.kind {
+                ExprKind::Spread(spread) => match self.eval_spread(*spread)? {
+                    (items, Some(type_)) => (items, type_),
+                    (_, None) => continue,
+                },
+                _ => {
+                    let item = self.eval_expr(expr)?;
+                    let type_ = item.ask_type();
+                    (vec![item], type_)
+                }
             };
 
-            if first_item_type != item.ask_type() {
+            if !items_type
+                .get_or_insert_with(|| type_.clone())
+                .accepts(&type_)
+            {
                 self.error_handler
                     .set_type_error("Array item's type mismatch".to_string());
                 return None;
             }
-            objects.push(item);
+            objects.extend(items);
         }
 
-        return Some(Object::Array {
+        Some(Object::Array {
             inner: Array::from(objects),
-            items_type: Some(first_item_type),
-        });
+            items_type,
+        })
+    }
+
+    pub fn eval_spread(&mut self, expr: Expr) -> Option<(Vec<Object>, Option<Type>)> {
+        let span = expr.span.clone();
+        let spread = self.eval_expr(expr)?;
+        match spread {
+            Object::Array { inner, items_type } => {
+                let items_type =
+                    items_type.or_else(|| inner.inner.first().map(|item| item.ask_type()));
+                Some((inner.inner, items_type))
+            }
+            other => {
+                self.error_handler.set_type_error(format!(
+                    "Only arrays can be spread but found '{}'",
+                    other.ask_type()
+                ));
+                self.error_handler.locate(&span);
+                None
+            }
+        }
+    }
+
+    fn eval_map_literal(
+        &mut self,
+        entries: Vec<(Expr, Expr)>,
+        expected: Option<(&Type, &Type)>,
+    ) -> Option<Object> {
+        let mut map_entries = MapEntries::default();
+        let mut entry_types: Option<(Type, Type)> =
+            expected.map(|(key_type, value_type)| (key_type.clone(), value_type.clone()));
+
+        for (key_expr, value_expr) in entries {
+            let key = self.eval_expr(key_expr)?;
+            let value = self.eval_expr(value_expr)?;
+
+            let map_key = match map_key(&key, None) {
+                Ok(map_key) => map_key,
+                Err(err) => {
+                    self.error_handler.set_error(err.kind, err.msg);
+                    return None;
+                }
+            };
+            let (key_type, value_type) = entry_types
+                .get_or_insert_with(|| (key.ask_type(), value.ask_type()))
+                .clone();
+            if !key_type.accepts(&key.ask_type()) {
+                self.error_handler
+                    .set_type_error("Map key's type mismatch".to_string());
+                return None;
+            }
+            if !value_type.accepts(&value.ask_type()) {
+                self.error_handler
+                    .set_type_error("Map value's type mismatch".to_string());
+                return None;
+            }
+            if map_entries.has(&map_key) {
+                self.error_handler
+                    .set_sematic(format!("Key {} is given more than once", key));
+                return None;
+            }
+            map_entries.insert(map_key, value);
+        }
+
+        Some(Object::Map {
+            inner: Map::from(map_entries),
+            entry_types,
+        })
     }
 
     fn resolve_identfier(&mut self, identifier: Identifier) -> Option<Object> {
@@ -627,3 +1178,400 @@ impl Runtime {
         Some(meta_object.value)
     }
 }
+
+pub enum Narrowing {
+    NotNull,
+    Is(ExprType),
+    IsNot(ExprType),
+}
+
+pub fn narrowings(condition: &Expr, holds: bool) -> Vec<(String, Narrowing)> {
+    match &condition.kind {
+        ExprKind::Infix(lhs, infix, rhs) => match (infix, holds) {
+            (Infix::NotEqual, true) | (Infix::Equal, false) => match (&lhs.kind, &rhs.kind) {
+                (ExprKind::Identifier(Identifier(name)), ExprKind::Literal(Literal::Null))
+                | (ExprKind::Literal(Literal::Null), ExprKind::Identifier(Identifier(name))) => {
+                    vec![(name.clone(), Narrowing::NotNull)]
+                }
+                _ => vec![],
+            },
+            (Infix::And, true) | (Infix::Or, false) => {
+                let mut found = narrowings(lhs, holds);
+                found.extend(narrowings(rhs, holds));
+                found
+            }
+            _ => vec![],
+        },
+        ExprKind::Is(expr, type_) => match &expr.kind {
+            ExprKind::Identifier(Identifier(name)) => {
+                let narrowing = match holds {
+                    true => Narrowing::Is(type_.clone()),
+                    false => Narrowing::IsNot(type_.clone()),
+                };
+                vec![(name.clone(), narrowing)]
+            }
+            _ => vec![],
+        },
+        ExprKind::Prefix(Prefix::Not, operand) => narrowings(operand, !holds),
+        _ => vec![],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, rc::Rc};
+
+    use super::Runtime;
+    use crate::context::Context;
+    use crate::frontend::lexer::Lexer;
+    use crate::frontend::parser::Parser;
+    use crate::stdlib::builtins::builtins;
+
+    fn eval_source(input: &str) -> String {
+        let chars = input.chars().collect::<Vec<char>>();
+        let mut l = Lexer::new(&chars, "test.fl");
+        let mut p = Parser::new(&mut l);
+        let program = p.parse();
+        assert!(!p.has_errors());
+
+        let env = Context::make_global(builtins());
+        let mut rt = Runtime::new(Rc::new(RefCell::new(env)));
+        let value = rt.eval(program);
+        match rt.error_handler.report(input) {
+            Some(report) => report.lines().next().unwrap().to_string(),
+            None => value.map(|value| value.to_string()).unwrap_or_default(),
+        }
+    }
+
+    #[test]
+    fn test_functions_see_the_scope_they_are_defined_in() {
+        let input = r#"
+let y = 1
+define show(): int { return y }
+define caller(): int {
+    let y = 2
+    return show()
+}
+caller()
+"#;
+        assert_eq!("1", eval_source(input));
+    }
+
+    #[test]
+    fn test_logical_operators_short_circuit() {
+        let input = r#"
+let mut calls = 0
+define side(): boolean {
+    calls += 1
+    return true
+}
+let a = false && side()
+let b = true || side()
+let c = true && side()
+(a, b, c, calls)
+"#;
+        assert_eq!("(false, true, true, 1)", eval_source(input));
+    }
+
+    #[test]
+    fn test_update_operators_store_the_new_value() {
+        let input = r#"
+let mut x = 1
+let old = x++
+let new = ++x
+let mut y = 5
+y--
+(old, new, x, y)
+"#;
+        assert_eq!("(1, 3, 3, 4)", eval_source(input));
+    }
+
+    #[test]
+    fn test_compares_compound_values_by_their_items() {
+        let cases = [
+            ("(1, 2) == (1, 2)", "true"),
+            ("(1, 2) != (1, 3)", "true"),
+            ("[1, 2] == [1, 2]", "true"),
+            ("[(1, \"a\")] == [(1, \"b\")]", "false"),
+            ("{\"a\": 1} == {\"a\": 1}", "true"),
+            ("{\"a\": 1} == {\"a\": 1, \"b\": 2}", "false"),
+            ("struct P { x: int }\nP { x: 1 } == P { x: 1 }", "true"),
+            ("let xs: Array<int?> = [1]\n[1] == xs", "true"),
+            (
+                "define f(): int { return 1 }\nf == f",
+                "[Type Error] '==' operation not allowed between types () -> int and () -> int",
+            ),
+        ];
+        for (input, expected) in cases {
+            assert_eq!(expected, eval_source(input), "{}", input);
+        }
+    }
+
+    #[test]
+    fn test_shared_values_change_through_immutable_variables() {
+        let input = r#"
+struct P { x: int }
+let m: Map<string, int> = {"a": 1}
+m["b"] = 2
+m.set("c", 3)
+let p = P { x: 1 }
+p.x = 3
+(m, p.x)
+"#;
+        assert_eq!("({\"a\": 1, \"b\": 2, \"c\": 3}, 3)", eval_source(input));
+
+        let input = "let xs = [1, 2]\nxs[0] = 3";
+        assert_eq!(
+            "[Name error] 'xs' is not assignable, it's declared immutable at test.fl:1:1",
+            eval_source(input)
+        );
+    }
+
+    #[test]
+    fn test_not_takes_booleans_only() {
+        assert_eq!("true", eval_source("not false"));
+        assert_eq!("false", eval_source("!(1 < 2)"));
+        assert_eq!(
+            "[Type Error] '!' prefix expects type boolean but found int",
+            eval_source("not 1")
+        );
+    }
+
+    #[test]
+    fn test_reports_cyclic_type_aliases() {
+        assert_eq!(
+            "[Type Error] Type alias 'A' refers to itself",
+            eval_source("type A = A | int")
+        );
+        assert_eq!(
+            "[Type Error] Type alias 'B' refers to itself",
+            eval_source("type A = B\ntype B = A")
+        );
+    }
+
+    #[test]
+    fn test_optionals_are_used_after_an_early_exit_guard() {
+        let input = r#"
+define f(x: int?): int {
+    if x == null { return 0 }
+    return x + 1
+}
+(f(2), f(null))
+"#;
+        assert_eq!("(3, 0)", eval_source(input));
+
+        let input = "let x: int? = null\nx + 1";
+        assert_eq!(
+            "[Type Error] 'x' of type 'int?' is null, check it with 'if x != null' or use '??'",
+            eval_source(input)
+        );
+    }
+
+    #[test]
+    fn test_functions_without_return_type_fit_typed_slots() {
+        let input = r#"
+define f(x: int) { return x }
+define apply(h: (int) -> int, x: int): int { return h(x) }
+let g: (int) -> int = f
+(g(1), apply(f, 2), typeof(f))
+"#;
+        assert_eq!("(1, 2, function)", eval_source(input));
+    }
+
+    #[test]
+    fn test_passes_generic_functions_as_values() {
+        let input = r#"
+define id<T>(x: T): T { return x }
+define apply(f: (int) -> int, x: int): int { return f(x) }
+define twice<U>(f: (U) -> U, x: U): U { return f(f(x)) }
+let same = id(id)
+(apply(id, 3), same(4), twice(id, "a"))
+"#;
+        assert_eq!("(3, 4, \"a\")", eval_source(input));
+
+        let input = r#"
+define id<T>(x: T): T { return x }
+define shout(f: (int) -> string): string { return f(1) }
+shout(id)
+"#;
+        assert_eq!(
+            "[Type Error] Passing argument of type '(T) -> T' to parameter of type '(int) -> string'",
+            eval_source(input)
+        );
+    }
+
+    #[test]
+    fn test_types_for_cursors_from_the_iterable() {
+        let input = r#"
+let mut fruit: string = ""
+for f in ["Apple"] { fruit = f }
+let mut last: int = -1
+for i, x in ["a", "b"] { last = i }
+(fruit, last)
+"#;
+        assert_eq!("(\"Apple\", 1)", eval_source(input));
+
+        let cases = [
+            (
+                "for f: int in [\"Apple\"] { }",
+                "[Type Error] Cursor of type 'int' can't take items of type 'string'",
+            ),
+            (
+                "for i, x in [\"a\"] { let s: string = i }",
+                "[Type Error] Assigning value of type int to variable 's' which has type string",
+            ),
+        ];
+        for (input, expected) in cases {
+            assert_eq!(expected, eval_source(input), "{}", input);
+        }
+    }
+
+    #[test]
+    fn test_interpolates_evaluated_exprs() {
+        let input = r#"
+let name = "Ada"
+define twice(x: int): int { return x * 2 }
+"hi ${name}, ${twice(2) + 1} \${name} ${[1, 2][1]}"
+"#;
+        assert_eq!("\"hi Ada, 5 ${name} 2\"", eval_source(input));
+    }
+
+    #[test]
+    fn test_break_and_continue_leave_the_labelled_loop() {
+        let input = r#"
+let mut out = ""
+for i in range(0, 10) {
+    if i == 1 { continue }
+    if i == 4 { break }
+    out += "${i}"
+}
+outer: for x in [1, 2, 3] {
+    for y in [10, 20] {
+        if y == 20 { continue outer }
+        if x == 3 { break outer }
+        out += " ${x}:${y}"
+    }
+}
+let mut n = 0
+outer: while true {
+    loop {
+        n += 1
+        if n == 3 { break outer }
+        continue outer
+    }
+}
+(out, n)
+"#;
+        assert_eq!("(\"023 1:10 2:10\", 3)", eval_source(input));
+
+        let cases = [
+            ("break", "[Semantic Error] 'break' outside of loop"),
+            (
+                "define f() { continue }\nf()",
+                "[Semantic Error] 'continue' outside of loop",
+            ),
+            (
+                "while true { break outer }",
+                "[Semantic Error] No loop labelled 'outer' around 'break'",
+            ),
+        ];
+        for (input, expected) in cases {
+            assert_eq!(expected, eval_source(input), "{}", input);
+        }
+    }
+
+    #[test]
+    fn test_reports_indexes_out_of_range() {
+        let cases = [
+            ("[1, 2][1]", "2"),
+            ("\"abc\"[1..]", "\"bc\""),
+            (
+                "[1, 2][2]",
+                "[Index Error] Index 2 out of range for array of length 2",
+            ),
+            (
+                "[1, 2][-1]",
+                "[Index Error] Index -1 out of range for array of length 2",
+            ),
+            (
+                "\"ab\"[5]",
+                "[Index Error] Index 5 out of range for string of length 2",
+            ),
+            (
+                "[1, 2, 3][1..5]",
+                "[Index Error] Slice 1..5 out of range for array of length 3",
+            ),
+            (
+                "let mut xs = [1]\nxs[1] = 2",
+                "[Index Error] Index 1 out of range for array of length 1",
+            ),
+        ];
+        for (input, expected) in cases {
+            assert_eq!(expected, eval_source(input), "{}", input);
+        }
+    }
+
+    #[test]
+    fn test_methods_change_their_instance() {
+        let input = r#"
+struct Counter {
+    count: int
+
+    define bump(self, by: int): int {
+        self.count += by
+        return self.count
+    }
+    define reset(self): void { self.count = 0 }
+}
+let c = Counter { count: 1 }
+let bumped = c.bump(2)
+let same = c
+same.bump(3)
+let before = c.count
+c.reset()
+(bumped, before, same.count)
+"#;
+        assert_eq!("(3, 6, 0)", eval_source(input));
+    }
+
+    #[test]
+    fn test_maps_loop_in_insertion_order_and_remove_keys() {
+        let input = r#"
+let m: Map<string, int> = {"b": 2, "a": 1}
+m["c"] = 3
+m.set("a", 10)
+let mut entries = ""
+for k, v in m { entries += "${k}=${v} " }
+let gone = m.remove("b")
+let missing = m.remove("z")
+let mut keys = ""
+for k in m { keys += k }
+(entries, keys, gone, missing, m.length())
+"#;
+        assert_eq!(
+            "(\"b=2 a=10 c=3 \", \"ac\", 2, null, 2)",
+            eval_source(input)
+        );
+    }
+
+    #[test]
+    fn test_reports_where_cursors_and_functions_are_declared() {
+        let cases = [
+            (
+                "define f() { }\nf = 3",
+                "[Name error] 'f' is not assignable, it's declared immutable at test.fl:1:1",
+            ),
+            (
+                "for i in range(0, 2) { i = 3 }",
+                "[Name error] 'i' is not assignable, it's declared immutable at test.fl:1:5",
+            ),
+            (
+                "for k, (a, b) in [(1, 2)] { b = 1 }",
+                "[Name error] 'b' is not assignable, it's declared immutable at test.fl:1:8",
+            ),
+        ];
+        for (input, expected) in cases {
+            assert_eq!(expected, eval_source(input), "{}", input);
+        }
+    }
+}
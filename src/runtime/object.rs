@@ -1,11 +1,16 @@
 use core::fmt;
+use std::{cell::RefCell, rc::Rc};
 
 use super::runtime_error::RuntimeError;
 use super::type_system::Type;
 use super::BlockStmt;
+use crate::context::Context;
+use crate::frontend::ast::Expr;
+use crate::frontend::span::Span;
+use crate::stdlib::collections::{Array, Map};
 use crate::stdlib::modules::Module;
 use crate::stdlib::primitives::Primitive;
-use crate::stdlib::collections::Array;
+use crate::stdlib::FieldsManager;
 
 pub enum BuiltInFuncReturnValue {
     Object(Object),
@@ -13,13 +18,23 @@ pub enum BuiltInFuncReturnValue {
 }
 type BuiltInFunction = fn(Vec<ObjectInfo>) -> BuiltInFuncReturnValue;
 
-#[derive(PartialEq, Clone, Debug)]
+#[derive(Clone, Debug)]
 pub struct FunctionParam {
     pub name: String,
     pub type_: Type,
+    pub default: Option<Expr>,
+    pub rest: bool,
+    pub is_mut: bool,
+    pub span: Span,
 }
 pub type FunctionParams = Vec<FunctionParam>;
 
+#[derive(Debug)]
+pub struct BuiltInParam {
+    pub name: &'static str,
+    pub default: Option<fn() -> Object>,
+}
+
 #[derive(Clone, Debug)]
 pub enum Object {
     Null,
@@ -29,37 +44,193 @@ pub enum Object {
     Boolean(bool),
     String(Primitive<String>),
     RetVal(Box<Object>),
+    Break(Option<String>),
+    Continue(Option<String>),
     Array {
         inner: Array,
         items_type: Option<Type>,
     },
+    Map {
+        inner: Map,
+        entry_types: Option<(Type, Type)>,
+    },
+    Tuple(Vec<Object>),
     UserDefinedFunction {
         params: FunctionParams,
         body: BlockStmt,
-        return_type: Type,
+        return_type: Option<Type>,
+        scope: Rc<RefCell<Context>>,
     },
     BuiltInFunction(BuiltInFunction),
+    BuiltInFunctionWithParams {
+        func: BuiltInFunction,
+        params: &'static [BuiltInParam],
+    },
     Range {
         start: i64,
         end: i64,
         step: i64,
     },
     Module(Module),
+    StructDef(Rc<StructDef>),
+    Struct(Rc<RefCell<StructInstance>>),
+    EnumDef(Rc<EnumDef>),
+    TypeAlias {
+        name: String,
+        type_: Type,
+    },
+    EnumValue {
+        def: Rc<EnumDef>,
+        variant: String,
+        payload: Vec<Object>,
+    },
+}
+
+#[derive(Debug)]
+pub struct StructDef {
+    pub name: String,
+    pub fields: Vec<(String, Type)>,
+    pub methods: FieldsManager,
+}
+
+#[derive(Debug)]
+pub struct StructInstance {
+    pub def: Rc<StructDef>,
+    pub fields: FieldsManager,
+}
+
+#[derive(Debug)]
+pub struct EnumDef {
+    pub name: String,
+    pub variants: Vec<(String, Vec<Type>)>,
 }
 
 impl Object {
+    pub fn typed_as(self, expected: &Type) -> Object {
+        match (self, expected) {
+            (
+                Object::Array {
+                    inner,
+                    items_type: None,
+                },
+                Type::Array(Some(items_type)),
+            ) => Object::Array {
+                inner,
+                items_type: Some(*items_type.clone()),
+            },
+            (
+                Object::Map {
+                    inner,
+                    entry_types: None,
+                },
+                Type::Map(Some((key_type, value_type))),
+            ) => Object::Map {
+                inner,
+                entry_types: Some((*key_type.clone(), *value_type.clone())),
+            },
+            (Object::Tuple(items), Type::Tuple(types)) if items.len() == types.len() => {
+                Object::Tuple(
+                    items
+                        .into_iter()
+                        .zip(types)
+                        .map(|(item, type_)| item.typed_as(type_))
+                        .collect(),
+                )
+            }
+            (object, Type::Optional(type_)) => object.typed_as(type_),
+            // a copy of an array can hold items of a wider type
+            (
+                Object::Array {
+                    inner,
+                    items_type: Some(items_type),
+                },
+                Type::Array(Some(expected)),
+            ) if expected.accepts(&items_type) => Object::Array {
+                inner,
+                items_type: Some(*expected.clone()),
+            },
+            (object, _) => object,
+        }
+    }
+
+    pub fn equals(&self, other: &Object) -> Option<bool> {
+        match (self, other) {
+            (Object::Null, Object::Null) => Some(true),
+            (Object::Null, _) | (_, Object::Null) => Some(false),
+            (Object::Int(lhs), Object::Int(rhs)) => Some(lhs.value == rhs.value),
+            (Object::Float(lhs), Object::Float(rhs)) => Some(lhs == rhs),
+            (Object::Boolean(lhs), Object::Boolean(rhs)) => Some(lhs == rhs),
+            (Object::String(lhs), Object::String(rhs)) => Some(lhs.value == rhs.value),
+            (Object::Array { inner: lhs, .. }, Object::Array { inner: rhs, .. }) => {
+                all_equal(&lhs.inner, &rhs.inner)
+            }
+            (Object::Tuple(lhs), Object::Tuple(rhs)) => all_equal(lhs, rhs),
+            (Object::Map { inner: lhs, .. }, Object::Map { inner: rhs, .. }) => {
+                let (lhs, rhs) = (lhs.entries.borrow(), rhs.entries.borrow());
+                if lhs.len() != rhs.len() {
+                    return Some(false);
+                }
+                for (key, value) in lhs.items() {
+                    match rhs.get(key) {
+                        Some(other) if value.equals(other)? => {}
+                        _ => return Some(false),
+                    }
+                }
+                Some(true)
+            }
+            (Object::Struct(lhs), Object::Struct(rhs)) => {
+                let (lhs, rhs) = (lhs.borrow(), rhs.borrow());
+                if !Rc::ptr_eq(&lhs.def, &rhs.def) {
+                    return Some(false);
+                }
+                for (field, _) in &lhs.def.fields {
+                    match (lhs.fields.fields.get(field), rhs.fields.fields.get(field)) {
+                        (Some(lhs), Some(rhs)) if lhs.equals(rhs)? => {}
+                        _ => return Some(false),
+                    }
+                }
+                Some(true)
+            }
+            (
+                Object::EnumValue {
+                    def,
+                    variant,
+                    payload,
+                },
+                Object::EnumValue {
+                    def: other_def,
+                    variant: other_variant,
+                    payload: other_payload,
+                },
+            ) => match Rc::ptr_eq(def, other_def) && variant == other_variant {
+                true => all_equal(payload, other_payload),
+                false => Some(false),
+            },
+            _ => None,
+        }
+    }
+
     pub fn ask_type(&self) -> Type {
         match self {
             Object::Null => Type::Null,
             Object::String(_) => Type::String,
             Object::Boolean(_) => Type::Boolean,
-            Object::BuiltInFunction(_) => Type::Function,
+            Object::BuiltInFunction(_) | Object::BuiltInFunctionWithParams { .. } => {
+                Type::Function(None)
+            }
             Object::UserDefinedFunction {
-                params: _,
-                body: _,
-                return_type: _,
-            } => Type::Function,
+                params,
+                return_type,
+                ..
+            } => match return_type {
+                Some(return_type) => Type::Function(Some((
+                    params.iter().map(|param| param.type_.clone()).collect(),
+                    Box::new(return_type.clone()),
+                ))),
+                None => Type::Function(None),
+            },
             Object::RetVal(val) => val.ask_type(),
+            Object::Break(_) | Object::Continue(_) => Type::Void,
             Object::Type(_) => Type::TypeAnnot,
             Object::Range {
                 start: _,
@@ -75,10 +246,19 @@ impl Object {
                 if items_type.is_none() {
                     return Type::Array(None);
                 }
-                return Type::Array(Some(Box::new(items_type.clone().unwrap())));
+                Type::Array(Some(Box::new(items_type.clone().unwrap())))
             }
-
+            Object::Map { entry_types, .. } => Type::Map(
+                entry_types
+                    .clone()
+                    .map(|(key_type, value_type)| (Box::new(key_type), Box::new(value_type))),
+            ),
+            Object::Tuple(items) => Type::Tuple(items.iter().map(Object::ask_type).collect()),
             Object::Module(_) => Type::Module,
+            Object::StructDef(_) => Type::TypeAnnot,
+            Object::Struct(instance) => Type::UserDefined(instance.borrow().def.name.clone()),
+            Object::EnumDef(_) | Object::TypeAlias { .. } => Type::TypeAnnot,
+            Object::EnumValue { def, .. } => Type::Enum(def.name.clone()),
         }
     }
 }
@@ -96,22 +276,60 @@ impl fmt::Display for Object {
             Self::String(val) => write!(f, "\"{}\"", val.value),
             Self::Int(val) => write!(f, "{}", val.value),
             Self::Float(val) => write!(f, "{}", val),
-            Self::BuiltInFunction(_) => write!(f, "[Builtin Function]"),
+            Self::BuiltInFunction(_) | Self::BuiltInFunctionWithParams { .. } => {
+                write!(f, "[Builtin Function]")
+            }
             Self::Null => write!(f, "null"),
             Self::RetVal(val) => write!(f, "{}", val),
+            Self::Break(_) => write!(f, "break"),
+            Self::Continue(_) => write!(f, "continue"),
             Self::Boolean(val) => write!(f, "{}", val),
             Self::Type(val) => write!(f, "{}", val),
             Self::Range { start, end, step } => write!(f, "range({start}, {end}, {step})"),
-            Self::UserDefinedFunction {
-                params: _,
-                body: _,
-                return_type: _,
-            } => write!(f, "[User Defined Function]"),
+            Self::UserDefinedFunction { .. } => write!(f, "[User Defined Function]"),
             Self::Array {
                 inner,
                 items_type: _,
             } => write!(f, "{}", inner),
+            Self::Map { inner, .. } => write!(f, "{}", inner),
+            Self::Tuple(items) => {
+                let items = items
+                    .iter()
+                    .map(|item| item.to_string())
+                    .collect::<Vec<String>>();
+                write!(f, "({})", items.join(", "))
+            }
             Self::Module(m) => write!(f, "[Module] {}", m.name),
+            Self::StructDef(def) => write!(f, "[Struct] {}", def.name),
+            Self::Struct(instance) => {
+                let instance = instance.borrow();
+                write!(f, "{} {{", instance.def.name)?;
+                for (index, (name, _)) in instance.def.fields.iter().enumerate() {
+                    let separator = if index == 0 { " " } else { ", " };
+                    match instance.fields.access(name) {
+                        Some(value) => write!(f, "{}{}: {}", separator, name, value)?,
+                        None => write!(f, "{}{}: null", separator, name)?,
+                    }
+                }
+                write!(f, " }}")
+            }
+            Self::EnumDef(def) => write!(f, "[Enum] {}", def.name),
+            Self::TypeAlias { name, type_ } => write!(f, "[Type] {} = {}", name, type_),
+            Self::EnumValue {
+                def,
+                variant,
+                payload,
+            } => {
+                write!(f, "{}.{}", def.name, variant)?;
+                if payload.is_empty() {
+                    return Ok(());
+                }
+                let payload = payload
+                    .iter()
+                    .map(|value| value.to_string())
+                    .collect::<Vec<String>>();
+                write!(f, "({})", payload.join(", "))
+            }
         }
     }
 }
@@ -125,16 +343,61 @@ impl fmt::Display for Type {
             Self::Float => write!(f, "float"),
             Self::Boolean => write!(f, "boolean"),
             Self::String => write!(f, "string"),
-            Self::Function => write!(f, "function"),
+            Self::Function(None) => write!(f, "function"),
+            Self::Function(Some((params, return_type))) => {
+                write!(f, "(")?;
+                for (index, param) in params.iter().enumerate() {
+                    if index > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}", param)?;
+                }
+                write!(f, ") -> {}", return_type)
+            }
             Self::TypeAnnot => write!(f, "[Type Annotation]"),
-            Self::Range => write!(f, "{}", self),
+            Self::Range => write!(f, "range"),
             Self::Array(items_type) => {
                 if let Some(items_type) = items_type {
                     return write!(f, "Array<{}>", items_type);
                 }
                 write!(f, "Array<any>")
             }
+            Self::Map(Some((key_type, value_type))) => {
+                write!(f, "Map<{}, {}>", key_type, value_type)
+            }
+            Self::Map(None) => write!(f, "Map<any, any>"),
+            Self::Tuple(items) => {
+                let items = items
+                    .iter()
+                    .map(|item| item.to_string())
+                    .collect::<Vec<String>>();
+                write!(f, "({})", items.join(", "))
+            }
             Self::Module => write!(f, "[Module]"),
+            Self::UserDefined(name) | Self::Enum(name) | Self::TypeParam(name) => {
+                write!(f, "{}", name)
+            }
+            Self::Optional(type_) if matches!(**type_, Type::Union(_)) => write!(f, "({})?", type_),
+            Self::Optional(type_) => write!(f, "{}?", type_),
+            Self::Union(members) => {
+                let members = members
+                    .iter()
+                    .map(|member| member.to_string())
+                    .collect::<Vec<String>>();
+                write!(f, "{}", members.join(" | "))
+            }
+        }
+    }
+}
+
+fn all_equal(lhs: &[Object], rhs: &[Object]) -> Option<bool> {
+    if lhs.len() != rhs.len() {
+        return Some(false);
+    }
+    for (lhs, rhs) in lhs.iter().zip(rhs) {
+        if !lhs.equals(rhs)? {
+            return Some(false);
         }
     }
+    Some(true)
 }
@@ -1,12 +1,16 @@
 use core::fmt;
 
+use crate::frontend::span::Span;
+
 #[derive(Clone)]
+#[allow(clippy::enum_variant_names)]
 pub enum ErrorKind {
     NameError,
     TypeError,
     ArgumentError,
     SemanticError,
     IOError,
+    IndexError,
 }
 
 #[derive(Clone)]
@@ -18,21 +22,21 @@ pub struct RuntimeError {
 #[derive(Clone)]
 pub struct RuntimeErrorHandler {
     error: Option<RuntimeError>,
+    span: Option<Span>,
 }
 
 impl RuntimeErrorHandler {
     pub fn new() -> Self {
-        Self { error: None }
+        Self {
+            error: None,
+            span: None,
+        }
     }
 
     pub fn has_error(&mut self) -> bool {
         self.error.is_some()
     }
 
-    pub fn get_error(&mut self) -> Option<RuntimeError> {
-        self.error.clone()
-    }
-
     pub fn set_name_error(&mut self, msg: String) {
         self.set_error(ErrorKind::NameError, msg)
     }
@@ -41,15 +45,33 @@ impl RuntimeErrorHandler {
         self.set_error(ErrorKind::TypeError, msg)
     }
 
+    pub fn set_index_error(&mut self, msg: String) {
+        self.set_error(ErrorKind::IndexError, msg);
+    }
+
     pub fn set_sematic(&mut self, msg: String) {
         self.set_error(ErrorKind::SemanticError, msg);
     }
 
     pub fn set_error(&mut self, kind: ErrorKind, msg: String) {
-        self.error = Some(RuntimeError {
-            kind,
-            msg,
-        });
+        self.error = Some(RuntimeError { kind, msg });
+        self.span = None;
+    }
+
+    // Attaches `span` to the pending error unless a narrower node already
+    // claimed it, so the innermost node being evaluated wins.
+    pub fn locate(&mut self, span: &Span) {
+        if self.error.is_some() && self.span.is_none() {
+            self.span = Some(span.clone());
+        }
+    }
+
+    pub fn report(&self, source: &str) -> Option<String> {
+        let error = self.error.as_ref()?;
+        match &self.span {
+            Some(span) => Some(format!("{}\n{}", error, span.snippet(source))),
+            None => Some(error.to_string()),
+        }
     }
 }
 
@@ -67,6 +89,7 @@ impl fmt::Display for ErrorKind {
             Self::ArgumentError => write!(f, "[Argument Error]"),
             Self::SemanticError => write!(f, "[Semantic Error]"),
             Self::IOError => write!(f, "[IO Error]"),
+            Self::IndexError => write!(f, "[Index Error]"),
         }
     }
 }
@@ -1,6 +1,10 @@
+use std::collections::HashMap;
+
+use super::Narrowing;
 use crate::frontend::ast::ExprType;
 
 #[derive(PartialEq, Clone, Debug)]
+#[allow(clippy::enum_variant_names)]
 pub enum Type {
     Null,
     Void,
@@ -8,11 +12,18 @@ pub enum Type {
     Float,
     String,
     Boolean,
-    Function,
+    Function(Option<(Vec<Type>, Box<Type>)>),
     Range,
     TypeAnnot,
     Module,
     Array(Option<Box<Type>>),
+    Map(Option<(Box<Type>, Box<Type>)>),
+    Tuple(Vec<Type>),
+    UserDefined(String),
+    Enum(String),
+    TypeParam(String),
+    Optional(Box<Type>),
+    Union(Vec<Type>),
 }
 
 pub fn expr_type_to_object_type(var_type: &ExprType) -> Type {
@@ -22,8 +33,201 @@ pub fn expr_type_to_object_type(var_type: &ExprType) -> Type {
         ExprType::Void => Type::Void,
         ExprType::Int => Type::Int,
         ExprType::Float => Type::Float,
+        ExprType::Named(name) => Type::UserDefined(name.clone()),
+        ExprType::Function(signature) => {
+            Type::Function(signature.as_ref().map(|(params, return_type)| {
+                let params = params.iter().map(expr_type_to_object_type).collect();
+                (params, Box::new(expr_type_to_object_type(return_type)))
+            }))
+        }
         ExprType::Array(items_type) => {
-            Type::Array(Some(Box::new(expr_type_to_object_type(&items_type))))
+            Type::Array(Some(Box::new(expr_type_to_object_type(items_type))))
+        }
+        ExprType::Map(key_type, value_type) => Type::Map(Some((
+            Box::new(expr_type_to_object_type(key_type)),
+            Box::new(expr_type_to_object_type(value_type)),
+        ))),
+        ExprType::Tuple(items) => Type::Tuple(items.iter().map(expr_type_to_object_type).collect()),
+        ExprType::TypeParam(name) => Type::TypeParam(name.clone()),
+        ExprType::Optional(type_) => Type::Optional(Box::new(expr_type_to_object_type(type_))),
+        ExprType::Union(members) => {
+            Type::Union(members.iter().map(expr_type_to_object_type).collect())
+        }
+    }
+}
+
+impl Type {
+    // plain `function` takes any function and one declared without return
+    // type fits any signature, annotations only know the name of a declared
+    // type, which can just as well be an enum
+    pub fn accepts(&self, found: &Type) -> bool {
+        match (self, found) {
+            (expected, Type::Union(found)) => found.iter().all(|found| expected.accepts(found)),
+            (Type::Union(expected), found) => {
+                expected.iter().any(|expected| expected.accepts(found))
+            }
+            (Type::Array(Some(expected)), Type::Array(Some(found))) => expected.accepts(found),
+            (Type::Optional(_), Type::Null) => true,
+            (Type::Optional(expected), Type::Optional(found)) => expected.accepts(found),
+            (Type::Optional(expected), found) => expected.accepts(found),
+            (Type::Function(None), Type::Function(_))
+            | (Type::Function(_), Type::Function(None)) => true,
+            (Type::Function(Some(_)), Type::Function(Some(_))) if found.is_generic() => {
+                self.infer(&found.instantiate(), &mut HashMap::new())
+            }
+            (Type::UserDefined(expected), Type::Enum(found)) => expected == found,
+            (Type::Tuple(expected), Type::Tuple(found)) => {
+                expected.len() == found.len()
+                    && expected
+                        .iter()
+                        .zip(found)
+                        .all(|(expected, found)| expected.accepts(found))
+            }
+            (expected, found) => expected == found,
+        }
+    }
+
+    pub fn narrow(
+        &self,
+        narrowing: &Narrowing,
+        resolve: impl FnOnce(&ExprType) -> Type,
+    ) -> Option<Type> {
+        match (narrowing, self) {
+            (Narrowing::NotNull, Type::Optional(type_)) => Some(*type_.clone()),
+            (Narrowing::Is(type_), _) => Some(resolve(type_)),
+            (Narrowing::IsNot(type_), Type::Union(members)) => {
+                let excluded = resolve(type_);
+                let mut left = members
+                    .iter()
+                    .filter(|member| !excluded.accepts(member))
+                    .cloned()
+                    .collect::<Vec<Type>>();
+                match left.len() {
+                    0 => None,
+                    1 => left.pop(),
+                    _ => Some(Type::Union(left)),
+                }
+            }
+            _ => None,
+        }
+    }
+
+    // Like `accepts`, but the type params in `self` take the type found in
+    // their place the first time and must match it from then on.
+    pub fn infer(&self, found: &Type, type_args: &mut HashMap<String, Type>) -> bool {
+        match (self, found) {
+            (Type::TypeParam(name), Type::TypeParam(found)) if name == found => true,
+            (Type::TypeParam(name), found) => match type_args.get(name).cloned() {
+                Some(bound) => bound.infer(found, type_args),
+                None => {
+                    type_args.insert(name.clone(), found.clone());
+                    true
+                }
+            },
+            // a type param of a generic function passed along, see `instantiate`
+            (expected, Type::TypeParam(name)) => match type_args.get(name).cloned() {
+                Some(bound) => expected.infer(&bound, type_args),
+                None => {
+                    type_args.insert(name.clone(), expected.clone());
+                    true
+                }
+            },
+            (Type::Union(_), found) if !self.is_generic() => self.accepts(found),
+            // the first member that fits decides the type params
+            (Type::Union(members), found) => members.iter().any(|member| {
+                let mut tried = type_args.clone();
+                let fits = member.infer(found, &mut tried);
+                if fits {
+                    *type_args = tried;
+                }
+                fits
+            }),
+            (Type::Optional(_), Type::Null) => true,
+            (Type::Optional(expected), Type::Optional(found)) => expected.infer(found, type_args),
+            (Type::Optional(expected), found) => expected.infer(found, type_args),
+            (Type::Array(Some(_)), Type::Array(None)) | (Type::Map(Some(_)), Type::Map(None)) => {
+                true
+            }
+            (Type::Array(Some(expected)), Type::Array(Some(found))) => {
+                expected.infer(found, type_args)
+            }
+            (Type::Map(Some((expected_key, expected_value))), Type::Map(Some((key, value)))) => {
+                expected_key.infer(key, type_args) && expected_value.infer(value, type_args)
+            }
+            (Type::Tuple(expected), Type::Tuple(found)) => {
+                expected.len() == found.len()
+                    && expected
+                        .iter()
+                        .zip(found)
+                        .all(|(expected, found)| expected.infer(found, type_args))
+            }
+            (
+                Type::Function(Some((expected_params, expected_ret))),
+                Type::Function(Some((params, ret))),
+            ) => {
+                expected_params.len() == params.len()
+                    && expected_params
+                        .iter()
+                        .zip(params)
+                        .all(|(expected, found)| expected.infer(found, type_args))
+                    && expected_ret.infer(ret, type_args)
+            }
+            (expected, found) => expected.accepts(found),
+        }
+    }
+
+    // The type of a generic function passed as a value, with its type params
+    // renamed apart from the ones of the function it's passed to since
+    // they take the types of each use of it.
+    pub fn instantiate(&self) -> Type {
+        self.replace(&|type_| match type_ {
+            Type::TypeParam(name) => Some(Type::TypeParam(format!("{}'", name))),
+            _ => None,
+        })
+    }
+
+    pub fn substitute(&self, type_args: &HashMap<String, Type>) -> Type {
+        self.replace(&|type_| match type_ {
+            Type::TypeParam(name) => type_args.get(name).cloned(),
+            _ => None,
+        })
+    }
+
+    pub fn replace(&self, f: &impl Fn(&Type) -> Option<Type>) -> Type {
+        if let Some(replacement) = f(self) {
+            return replacement;
+        }
+        match self {
+            Type::Array(Some(items_type)) => Type::Array(Some(Box::new(items_type.replace(f)))),
+            Type::Optional(type_) => Type::Optional(Box::new(type_.replace(f))),
+            Type::Map(Some((key_type, value_type))) => Type::Map(Some((
+                Box::new(key_type.replace(f)),
+                Box::new(value_type.replace(f)),
+            ))),
+            Type::Tuple(items) => Type::Tuple(items.iter().map(|item| item.replace(f)).collect()),
+            Type::Union(members) => {
+                Type::Union(members.iter().map(|member| member.replace(f)).collect())
+            }
+            Type::Function(Some((params, return_type))) => Type::Function(Some((
+                params.iter().map(|param| param.replace(f)).collect(),
+                Box::new(return_type.replace(f)),
+            ))),
+            other => other.clone(),
+        }
+    }
+
+    pub fn is_generic(&self) -> bool {
+        match self {
+            Type::TypeParam(_) => true,
+            Type::Array(Some(items_type)) | Type::Optional(items_type) => items_type.is_generic(),
+            Type::Map(Some((key_type, value_type))) => {
+                key_type.is_generic() || value_type.is_generic()
+            }
+            Type::Tuple(items) | Type::Union(items) => items.iter().any(Type::is_generic),
+            Type::Function(Some((params, return_type))) => {
+                params.iter().any(Type::is_generic) || return_type.is_generic()
+            }
+            _ => false,
         }
     }
 }
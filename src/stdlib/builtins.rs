@@ -1,6 +1,8 @@
-use crate::runtime::object::{BuiltInFuncReturnValue, Object, ObjectInfo};
+use crate::runtime::object::{BuiltInFuncReturnValue, BuiltInParam, Object, ObjectInfo};
 use crate::runtime::runtime_error::{ErrorKind, RuntimeError};
 use crate::runtime::type_system::Type;
+use crate::stdlib::primitives::make_integer;
+use crate::stdlib::{Member, Signature};
 use std::collections::HashMap;
 
 pub fn builtins() -> HashMap<String, ObjectInfo> {
@@ -10,7 +12,7 @@ pub fn builtins() -> HashMap<String, ObjectInfo> {
         "typeof".to_string(),
         ObjectInfo {
             is_mut: false,
-            type_: Type::Function,
+            type_: Type::Function(None),
             value: Object::BuiltInFunction(filipe_typeof),
         },
     );
@@ -19,8 +21,24 @@ pub fn builtins() -> HashMap<String, ObjectInfo> {
         "range".to_string(),
         ObjectInfo {
             is_mut: false,
-            type_: Type::Function,
-            value: Object::BuiltInFunction(filipe_range),
+            type_: Type::Function(None),
+            value: Object::BuiltInFunctionWithParams {
+                func: filipe_range,
+                params: &[
+                    BuiltInParam {
+                        name: "start",
+                        default: None,
+                    },
+                    BuiltInParam {
+                        name: "end",
+                        default: None,
+                    },
+                    BuiltInParam {
+                        name: "step",
+                        default: Some(|| Object::Int(make_integer(1))),
+                    },
+                ],
+            },
         },
     );
 
@@ -54,6 +72,33 @@ pub fn builtins() -> HashMap<String, ObjectInfo> {
     builtin_list
 }
 
+pub fn builtin_signatures() -> HashMap<String, Member> {
+    let mut signatures: HashMap<String, Member> = HashMap::new();
+    signatures.insert(
+        "typeof".to_string(),
+        Member::Function(Signature::make(vec![("value", None)], Type::TypeAnnot)),
+    );
+    signatures.insert(
+        "range".to_string(),
+        Member::Function(
+            Signature::make(
+                vec![
+                    ("start", Some(Type::Int)),
+                    ("end", Some(Type::Int)),
+                    ("step", Some(Type::Int)),
+                ],
+                Type::Range,
+            )
+            .optional(1)
+            .by_name(),
+        ),
+    );
+    signatures.insert("true".to_string(), Member::Value(Type::Boolean));
+    signatures.insert("false".to_string(), Member::Value(Type::Boolean));
+    signatures.insert("null".to_string(), Member::Value(Type::Null));
+    signatures
+}
+
 fn filipe_typeof(args: Vec<ObjectInfo>) -> BuiltInFuncReturnValue {
     if args.len() != 1 {
         return BuiltInFuncReturnValue::Error(RuntimeError {
@@ -83,7 +128,7 @@ fn filipe_range(args: Vec<ObjectInfo>) -> BuiltInFuncReturnValue {
             return BuiltInFuncReturnValue::Error({
                 RuntimeError {
                     kind: ErrorKind::TypeError,
-                    msg: format!("args for function 'range' must be of type number"),
+                    msg: "args for function 'range' must be of type number".to_string(),
                 }
             });
         }
@@ -1,11 +1,15 @@
 use crate::runtime::{
     object::{BuiltInFuncReturnValue, Object, ObjectInfo},
     runtime_error::{ErrorKind, RuntimeError},
+    type_system::Type,
 };
 use core::fmt;
-use std::collections::HashMap;
+use std::{cell::RefCell, collections::HashMap, rc::Rc};
 
-use super::{primitives::make_integer, FieldsManager};
+use super::{
+    primitives::{make_integer, make_string},
+    FieldsManager, Signature,
+};
 
 #[derive(Clone, Debug)]
 pub struct Array {
@@ -35,6 +39,10 @@ impl Array {
     }
 }
 
+pub fn array_methods() -> HashMap<&'static str, Signature> {
+    HashMap::from([("length", Signature::make(vec![], Type::Int))])
+}
+
 fn array_length(args: Vec<ObjectInfo>) -> BuiltInFuncReturnValue {
     if args.len() != 1 {
         return BuiltInFuncReturnValue::Error(RuntimeError {
@@ -51,7 +59,7 @@ fn array_length(args: Vec<ObjectInfo>) -> BuiltInFuncReturnValue {
         _ => {
             return BuiltInFuncReturnValue::Error(RuntimeError {
                 kind: ErrorKind::ArgumentError,
-                msg: format!("method length accept arrays only"),
+                msg: "method length accept arrays only".to_string(),
             });
         }
     };
@@ -72,3 +80,304 @@ impl fmt::Display for Array {
         write!(f, "]")
     }
 }
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum MapKey {
+    Int(i64),
+    String(String),
+    Boolean(bool),
+}
+
+impl MapKey {
+    pub fn from_object(object: &Object) -> Option<MapKey> {
+        match object {
+            Object::Int(prim) => Some(MapKey::Int(prim.value)),
+            Object::String(prim) => Some(MapKey::String(prim.value.clone())),
+            Object::Boolean(val) => Some(MapKey::Boolean(*val)),
+            _ => None,
+        }
+    }
+
+    pub fn to_object(&self) -> Object {
+        match self {
+            MapKey::Int(val) => Object::Int(make_integer(*val)),
+            MapKey::String(val) => Object::String(make_string(val.clone())),
+            MapKey::Boolean(val) => Object::Boolean(*val),
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct MapEntries {
+    positions: HashMap<MapKey, usize>,
+    items: Vec<(MapKey, Object)>,
+}
+
+impl MapEntries {
+    pub fn get(&self, key: &MapKey) -> Option<&Object> {
+        self.positions
+            .get(key)
+            .map(|position| &self.items[*position].1)
+    }
+
+    pub fn has(&self, key: &MapKey) -> bool {
+        self.positions.contains_key(key)
+    }
+
+    pub fn insert(&mut self, key: MapKey, value: Object) {
+        match self.positions.get(&key) {
+            Some(position) => self.items[*position].1 = value,
+            None => {
+                self.positions.insert(key.clone(), self.items.len());
+                self.items.push((key, value));
+            }
+        }
+    }
+
+    pub fn remove(&mut self, key: &MapKey) -> Option<Object> {
+        let position = self.positions.remove(key)?;
+        let (_, value) = self.items.remove(position);
+        for (key, _) in &self.items[position..] {
+            if let Some(later) = self.positions.get_mut(key) {
+                *later -= 1;
+            }
+        }
+        Some(value)
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn items(&self) -> &[(MapKey, Object)] {
+        &self.items
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Map {
+    pub entries: Rc<RefCell<MapEntries>>,
+    pub fields: FieldsManager,
+}
+
+impl Map {
+    pub fn from(entries: MapEntries) -> Self {
+        Self {
+            entries: Rc::new(RefCell::new(entries)),
+            fields: FieldsManager::make(Self::setup_fields()),
+        }
+    }
+
+    fn setup_fields() -> HashMap<String, Object> {
+        let mut fields: HashMap<String, Object> = HashMap::new();
+        fields.insert("get".to_string(), Object::BuiltInFunction(map_get));
+        fields.insert("set".to_string(), Object::BuiltInFunction(map_set));
+        fields.insert("has".to_string(), Object::BuiltInFunction(map_has));
+        fields.insert("remove".to_string(), Object::BuiltInFunction(map_remove));
+        fields.insert("keys".to_string(), Object::BuiltInFunction(map_keys));
+        fields.insert("values".to_string(), Object::BuiltInFunction(map_values));
+        fields.insert("length".to_string(), Object::BuiltInFunction(map_length));
+        fields
+    }
+}
+
+pub fn map_methods(key_type: &Type, value_type: &Type) -> HashMap<&'static str, Signature> {
+    let key = || ("key", Some(key_type.clone()));
+    let maybe_value = Type::Optional(Box::new(value_type.clone()));
+    HashMap::from([
+        ("get", Signature::make(vec![key()], maybe_value.clone())),
+        (
+            "set",
+            Signature::make(vec![key(), ("value", Some(value_type.clone()))], Type::Null),
+        ),
+        ("has", Signature::make(vec![key()], Type::Boolean)),
+        ("remove", Signature::make(vec![key()], maybe_value)),
+        (
+            "keys",
+            Signature::make(vec![], Type::Array(Some(Box::new(key_type.clone())))),
+        ),
+        (
+            "values",
+            Signature::make(vec![], Type::Array(Some(Box::new(value_type.clone())))),
+        ),
+        ("length", Signature::make(vec![], Type::Int)),
+    ])
+}
+
+type MapReceiver = (Rc<RefCell<MapEntries>>, Option<(Type, Type)>);
+
+fn map_receiver(
+    args: &[ObjectInfo],
+    method: &str,
+    arity: usize,
+) -> Result<MapReceiver, RuntimeError> {
+    if args.len() != arity + 1 {
+        return Err(RuntimeError {
+            kind: ErrorKind::ArgumentError,
+            msg: format!(
+                "method {} takes {} args but provided {}",
+                method,
+                arity,
+                args.len() - 1
+            ),
+        });
+    }
+
+    match &args[0].value {
+        Object::Map { inner, entry_types } => Ok((Rc::clone(&inner.entries), entry_types.clone())),
+        _ => Err(RuntimeError {
+            kind: ErrorKind::ArgumentError,
+            msg: format!("method {} accept maps only", method),
+        }),
+    }
+}
+
+pub fn map_key(key: &Object, key_type: Option<&Type>) -> Result<MapKey, RuntimeError> {
+    let found = key.ask_type();
+    match (MapKey::from_object(key), key_type) {
+        (Some(map_key), None) => Ok(map_key),
+        (Some(map_key), Some(key_type)) if *key_type == found => Ok(map_key),
+        (_, Some(key_type)) => Err(RuntimeError {
+            kind: ErrorKind::TypeError,
+            msg: format!("Map keys are of type '{}' but found '{}'", key_type, found),
+        }),
+        (None, None) => Err(RuntimeError {
+            kind: ErrorKind::TypeError,
+            msg: format!(
+                "Map keys must be of type 'int', 'string' or 'boolean' but found '{}'",
+                found
+            ),
+        }),
+    }
+}
+
+fn map_get(args: Vec<ObjectInfo>) -> BuiltInFuncReturnValue {
+    let (entries, entry_types) = match map_receiver(&args, "get", 1) {
+        Ok(receiver) => receiver,
+        Err(err) => return BuiltInFuncReturnValue::Error(err),
+    };
+    let key = match map_key(&args[1].value, entry_types.as_ref().map(|(key, _)| key)) {
+        Ok(key) => key,
+        Err(err) => return BuiltInFuncReturnValue::Error(err),
+    };
+    let value = entries.borrow().get(&key).cloned();
+    BuiltInFuncReturnValue::Object(value.unwrap_or(Object::Null))
+}
+
+fn map_set(args: Vec<ObjectInfo>) -> BuiltInFuncReturnValue {
+    let (entries, entry_types) = match map_receiver(&args, "set", 2) {
+        Ok(receiver) => receiver,
+        Err(err) => return BuiltInFuncReturnValue::Error(err),
+    };
+    let (key_type, value_type) = match entry_types {
+        Some(entry_types) => entry_types,
+        None => {
+            return BuiltInFuncReturnValue::Error(RuntimeError {
+                kind: ErrorKind::TypeError,
+                msg: "Can't set into a map of unknown types, annotate it like 'Map<string, int>'"
+                    .to_string(),
+            });
+        }
+    };
+    let key = match map_key(&args[1].value, Some(&key_type)) {
+        Ok(key) => key,
+        Err(err) => return BuiltInFuncReturnValue::Error(err),
+    };
+
+    let value = args[2].value.clone().typed_as(&value_type);
+    if !value_type.accepts(&value.ask_type()) {
+        return BuiltInFuncReturnValue::Error(RuntimeError {
+            kind: ErrorKind::TypeError,
+            msg: format!(
+                "Map values are of type '{}' but found '{}'",
+                value_type,
+                value.ask_type()
+            ),
+        });
+    }
+
+    entries.borrow_mut().insert(key, value);
+    BuiltInFuncReturnValue::Object(Object::Null)
+}
+
+fn map_has(args: Vec<ObjectInfo>) -> BuiltInFuncReturnValue {
+    let (entries, entry_types) = match map_receiver(&args, "has", 1) {
+        Ok(receiver) => receiver,
+        Err(err) => return BuiltInFuncReturnValue::Error(err),
+    };
+    let key = match map_key(&args[1].value, entry_types.as_ref().map(|(key, _)| key)) {
+        Ok(key) => key,
+        Err(err) => return BuiltInFuncReturnValue::Error(err),
+    };
+    let has = entries.borrow().has(&key);
+    BuiltInFuncReturnValue::Object(Object::Boolean(has))
+}
+
+fn map_remove(args: Vec<ObjectInfo>) -> BuiltInFuncReturnValue {
+    let (entries, entry_types) = match map_receiver(&args, "remove", 1) {
+        Ok(receiver) => receiver,
+        Err(err) => return BuiltInFuncReturnValue::Error(err),
+    };
+    let key = match map_key(&args[1].value, entry_types.as_ref().map(|(key, _)| key)) {
+        Ok(key) => key,
+        Err(err) => return BuiltInFuncReturnValue::Error(err),
+    };
+    let removed = entries.borrow_mut().remove(&key);
+    BuiltInFuncReturnValue::Object(removed.unwrap_or(Object::Null))
+}
+
+fn map_keys(args: Vec<ObjectInfo>) -> BuiltInFuncReturnValue {
+    let (entries, entry_types) = match map_receiver(&args, "keys", 0) {
+        Ok(receiver) => receiver,
+        Err(err) => return BuiltInFuncReturnValue::Error(err),
+    };
+    let keys = entries
+        .borrow()
+        .items()
+        .iter()
+        .map(|(key, _)| key.to_object())
+        .collect();
+    BuiltInFuncReturnValue::Object(Object::Array {
+        inner: Array::from(keys),
+        items_type: entry_types.map(|(key_type, _)| key_type),
+    })
+}
+
+fn map_values(args: Vec<ObjectInfo>) -> BuiltInFuncReturnValue {
+    let (entries, entry_types) = match map_receiver(&args, "values", 0) {
+        Ok(receiver) => receiver,
+        Err(err) => return BuiltInFuncReturnValue::Error(err),
+    };
+    let values = entries
+        .borrow()
+        .items()
+        .iter()
+        .map(|(_, value)| value.clone())
+        .collect();
+    BuiltInFuncReturnValue::Object(Object::Array {
+        inner: Array::from(values),
+        items_type: entry_types.map(|(_, value_type)| value_type),
+    })
+}
+
+fn map_length(args: Vec<ObjectInfo>) -> BuiltInFuncReturnValue {
+    let (entries, _) = match map_receiver(&args, "length", 0) {
+        Ok(receiver) => receiver,
+        Err(err) => return BuiltInFuncReturnValue::Error(err),
+    };
+    let len = entries.borrow().len();
+    BuiltInFuncReturnValue::Object(Object::Int(make_integer(len as i64)))
+}
+
+impl fmt::Display for Map {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{{")?;
+        for (index, (key, value)) in self.entries.borrow().items().iter().enumerate() {
+            if index > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{}: {}", key.to_object(), value)?;
+        }
+        write!(f, "}}")
+    }
+}
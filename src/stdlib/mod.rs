@@ -1,9 +1,10 @@
+pub mod builtins;
+pub mod collections;
 pub mod modules;
 pub mod primitives;
-pub mod collections;
-pub mod builtins;
 
 use crate::runtime::object::Object;
+use crate::runtime::type_system::Type;
 use std::collections::HashMap;
 
 #[derive(Clone, Debug)]
@@ -18,9 +19,52 @@ impl FieldsManager {
 
     pub fn access(&self, name: &str) -> Option<Object> {
         let field = self.fields.get(name);
-        if field.is_none() {
-            return None;
-        }
+        field?;
         Some(field.unwrap().clone())
     }
 }
+
+#[derive(Clone, Debug)]
+pub struct Signature {
+    pub params: Vec<(&'static str, Option<Type>)>,
+    pub optional: usize,
+    pub variadic: bool,
+    pub named: bool,
+    pub returns: Type,
+}
+
+impl Signature {
+    pub fn make(params: Vec<(&'static str, Option<Type>)>, returns: Type) -> Self {
+        Self {
+            params,
+            optional: 0,
+            variadic: false,
+            named: false,
+            returns,
+        }
+    }
+
+    pub fn variadic(returns: Type) -> Self {
+        Self {
+            variadic: true,
+            ..Self::make(vec![], returns)
+        }
+    }
+
+    pub fn optional(self, optional: usize) -> Self {
+        Self { optional, ..self }
+    }
+
+    pub fn by_name(self) -> Self {
+        Self {
+            named: true,
+            ..self
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub enum Member {
+    Value(Type),
+    Function(Signature),
+}
@@ -1,17 +1,45 @@
 use super::Module;
-use crate::runtime::object::{BuiltInFuncReturnValue, Object, ObjectInfo};
+use crate::runtime::object::{BuiltInFuncReturnValue, BuiltInParam, Object, ObjectInfo};
 use crate::runtime::runtime_error::{ErrorKind, RuntimeError};
+use crate::runtime::type_system::Type;
 use crate::stdlib::primitives::make_string;
+use crate::stdlib::{Member, Signature};
 use std::collections::HashMap;
 use std::io::Write;
 
 pub fn module_io() -> Object {
     let mut io_fields: HashMap<String, Object> = HashMap::new();
     io_fields.insert("puts".to_string(), Object::BuiltInFunction(io_puts));
-    io_fields.insert("gets".to_string(), Object::BuiltInFunction(io_gets));
+    io_fields.insert(
+        "gets".to_string(),
+        Object::BuiltInFunctionWithParams {
+            func: io_gets,
+            params: &[BuiltInParam {
+                name: "prompt",
+                default: Some(|| Object::String(make_string(String::new()))),
+            }],
+        },
+    );
     Object::Module(Module::make("io".to_string(), io_fields))
 }
 
+pub fn module_io_signatures() -> HashMap<String, Member> {
+    HashMap::from([
+        (
+            "puts".to_string(),
+            Member::Function(Signature::variadic(Type::Null)),
+        ),
+        (
+            "gets".to_string(),
+            Member::Function(
+                Signature::make(vec![("prompt", Some(Type::String))], Type::String)
+                    .optional(1)
+                    .by_name(),
+            ),
+        ),
+    ])
+}
+
 fn io_puts(args: Vec<ObjectInfo>) -> BuiltInFuncReturnValue {
     for arg in args {
         match &arg.value {
@@ -19,13 +47,12 @@ fn io_puts(args: Vec<ObjectInfo>) -> BuiltInFuncReturnValue {
             Object::Float(val) => print!("{}", val),
             Object::String(val) => print!("{}", val.value),
             Object::Null => print!("null"),
-            Object::BuiltInFunction(_) => print!("[Builtin Function]"),
-            Object::UserDefinedFunction {
-                params: _,
-                body: _,
-                return_type: _,
-            } => print!("{}", arg.value),
+            Object::BuiltInFunction(_) | Object::BuiltInFunctionWithParams { .. } => {
+                print!("[Builtin Function]")
+            }
+            Object::UserDefinedFunction { .. } => print!("{}", arg.value),
             Object::RetVal(val) => print!("{}", val),
+            Object::Break(_) | Object::Continue(_) => print!("{}", arg.value),
             Object::Boolean(val) => print!("{}", val),
             Object::Type(val) => print!("{}", val),
             Object::Range {
@@ -37,7 +64,16 @@ fn io_puts(args: Vec<ObjectInfo>) -> BuiltInFuncReturnValue {
                 inner,
                 items_type: _,
             } => print!("{}", inner),
-            Object::Module(_) => print!("{}", arg.value),
+            Object::Module(_)
+            | Object::StructDef(_)
+            | Object::Struct(_)
+            | Object::Map { .. }
+            | Object::Tuple(_)
+            | Object::EnumDef(_)
+            | Object::TypeAlias { .. }
+            | Object::EnumValue { .. } => {
+                print!("{}", arg.value)
+            }
         }
     }
     println!();
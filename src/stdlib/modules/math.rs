@@ -2,7 +2,9 @@ use super::Module;
 use crate::runtime::{
     object::{BuiltInFuncReturnValue, Object, ObjectInfo},
     runtime_error::{ErrorKind, RuntimeError},
+    type_system::Type,
 };
+use crate::stdlib::{Member, Signature};
 use std::{collections::HashMap, f64::consts::PI};
 
 pub fn module_math() -> Object {
@@ -15,6 +17,17 @@ pub fn module_math() -> Object {
     Object::Module(Module::make("math".to_string(), math_fields))
 }
 
+pub fn module_math_signatures() -> HashMap<String, Member> {
+    let number = Type::Union(vec![Type::Int, Type::Float]);
+    HashMap::from([
+        ("PI".to_string(), Member::Value(Type::Float)),
+        (
+            "sqrt".to_string(),
+            Member::Function(Signature::make(vec![("x", Some(number))], Type::Float)),
+        ),
+    ])
+}
+
 fn module_math_sqrt(args: Vec<ObjectInfo>) -> BuiltInFuncReturnValue {
     if args.len() != 1 {
         return BuiltInFuncReturnValue::Error(RuntimeError {
@@ -31,7 +44,7 @@ fn module_math_sqrt(args: Vec<ObjectInfo>) -> BuiltInFuncReturnValue {
         Object::Float(x) => BuiltInFuncReturnValue::Object(Object::Float(f64::sqrt(x))),
         _ => BuiltInFuncReturnValue::Error(RuntimeError {
             kind: ErrorKind::ArgumentError,
-            msg: format!("math.sqrt(x) expects argument of type int or float"),
+            msg: "math.sqrt(x) expects argument of type int or float".to_string(),
         }),
     }
 }
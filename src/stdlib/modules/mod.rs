@@ -5,13 +5,13 @@ mod sys;
 
 use std::collections::HashMap;
 
-use super::FieldsManager;
+use super::{FieldsManager, Member};
 use crate::runtime::object::Object;
 
-use io::module_io;
-use math::module_math;
-use random::module_random;
-use sys::module_sys;
+use io::{module_io, module_io_signatures};
+use math::{module_math, module_math_signatures};
+use random::{module_random, module_random_signatures};
+use sys::{module_sys, module_sys_signatures};
 
 #[derive(Clone, Debug)]
 pub struct Module {
@@ -29,27 +29,33 @@ impl Module {
 }
 
 type ModInit = fn() -> Object;
+type ModSignatures = fn() -> HashMap<String, Member>;
 
 #[derive(Debug, Clone)]
 pub struct ModulesManager {
-    modules: HashMap<String, ModInit>,
+    modules: HashMap<String, (ModInit, ModSignatures)>,
 }
 
 impl ModulesManager {
     pub fn setup() -> Self {
-        let mut modules: HashMap<String, ModInit> = HashMap::new();
-        modules.insert("math".to_string(), module_math);
-        modules.insert("io".to_string(), module_io);
-        modules.insert("random".to_string(), module_random);
-        modules.insert("sys".to_string(), module_sys);
+        let mut modules: HashMap<String, (ModInit, ModSignatures)> = HashMap::new();
+        modules.insert("math".to_string(), (module_math, module_math_signatures));
+        modules.insert("io".to_string(), (module_io, module_io_signatures));
+        modules.insert(
+            "random".to_string(),
+            (module_random, module_random_signatures),
+        );
+        modules.insert("sys".to_string(), (module_sys, module_sys_signatures));
         Self { modules }
     }
 
     pub fn access(&self, name: &str) -> Option<ModInit> {
-        let mod_init = self.modules.get(name);
-        if mod_init.is_none() {
-            return None;
-        }
-        Some(mod_init.unwrap().clone())
+        let (mod_init, _) = self.modules.get(name)?;
+        Some(*mod_init)
+    }
+
+    pub fn signatures(&self, name: &str) -> Option<HashMap<String, Member>> {
+        let (_, signatures) = self.modules.get(name)?;
+        Some(signatures())
     }
 }
@@ -5,6 +5,7 @@ use crate::runtime::object::{BuiltInFuncReturnValue, Object, ObjectInfo};
 use crate::runtime::runtime_error::{ErrorKind, RuntimeError};
 use crate::runtime::type_system::Type;
 use crate::stdlib::primitives::make_integer;
+use crate::stdlib::{Member, Signature};
 use std::collections::HashMap;
 
 pub fn module_random() -> Object {
@@ -13,6 +14,14 @@ pub fn module_random() -> Object {
     Object::Module(Module::make("random".to_string(), fields))
 }
 
+pub fn module_random_signatures() -> HashMap<String, Member> {
+    let randint = Signature::make(
+        vec![("start", Some(Type::Int)), ("end", Some(Type::Int))],
+        Type::Int,
+    );
+    HashMap::from([("randint".to_string(), Member::Function(randint))])
+}
+
 fn randint(args: Vec<ObjectInfo>) -> BuiltInFuncReturnValue {
     if args.len() != 2 {
         return BuiltInFuncReturnValue::Error(RuntimeError {
@@ -24,7 +33,7 @@ fn randint(args: Vec<ObjectInfo>) -> BuiltInFuncReturnValue {
     if args[0].value.ask_type() != Type::Int || args[1].value.ask_type() != Type::Int {
         return BuiltInFuncReturnValue::Error(RuntimeError {
             kind: ErrorKind::ArgumentError,
-            msg: format!("randint expects values of type int"),
+            msg: "randint expects values of type int".to_string(),
         });
     }
 
@@ -39,10 +48,10 @@ fn randint(args: Vec<ObjectInfo>) -> BuiltInFuncReturnValue {
     if begin >= end {
         return BuiltInFuncReturnValue::Error(RuntimeError {
             kind: ErrorKind::ArgumentError,
-            msg: format!("Invalid range: end must be bigger than start"),
+            msg: "Invalid range: end must be bigger than start".to_string(),
         });
     }
 
     let out = rand::thread_rng().gen_range(begin..end);
-    return BuiltInFuncReturnValue::Object(Object::Int(make_integer(out)));
+    BuiltInFuncReturnValue::Object(Object::Int(make_integer(out)))
 }
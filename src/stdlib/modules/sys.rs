@@ -1,6 +1,8 @@
 use super::Module;
 use crate::runtime::object::{BuiltInFuncReturnValue, Object, ObjectInfo};
 use crate::runtime::runtime_error::{ErrorKind, RuntimeError};
+use crate::runtime::type_system::Type;
+use crate::stdlib::{Member, Signature};
 use std::collections::HashMap;
 
 pub fn module_sys() -> Object {
@@ -9,6 +11,11 @@ pub fn module_sys() -> Object {
     Object::Module(Module::make("sys".to_string(), fields))
 }
 
+pub fn module_sys_signatures() -> HashMap<String, Member> {
+    let exit = Signature::make(vec![("code", Some(Type::Int))], Type::Void).optional(1);
+    HashMap::from([("exit".to_string(), Member::Function(exit))])
+}
+
 fn exit(args: Vec<ObjectInfo>) -> BuiltInFuncReturnValue {
     if args.is_empty() {
         std::process::exit(0);
@@ -1,6 +1,7 @@
-use super::FieldsManager;
+use super::{FieldsManager, Signature};
 use crate::runtime::object::{BuiltInFuncReturnValue, Object, ObjectInfo};
 use crate::runtime::runtime_error::{ErrorKind, RuntimeError};
+use crate::runtime::type_system::Type;
 use std::collections::HashMap;
 
 #[derive(Clone, Debug)]
@@ -11,11 +12,10 @@ pub struct Primitive<T> {
 
 impl<T> Primitive<T> {
     pub fn make(value: T, fields: HashMap<String, Object>) -> Primitive<T> {
-        let primitive = Primitive {
+        Primitive {
             value,
             fields: FieldsManager::make(fields),
-        };
-        primitive
+        }
     }
 }
 
@@ -29,6 +29,13 @@ pub fn make_string(value: String) -> Primitive<String> {
     Primitive::<String>::make(value, fields)
 }
 
+pub fn string_methods() -> HashMap<&'static str, Signature> {
+    HashMap::from([
+        ("length", Signature::make(vec![], Type::Int)),
+        ("as_int", Signature::make(vec![], Type::Int)),
+    ])
+}
+
 fn string_as_integer(args: Vec<ObjectInfo>) -> BuiltInFuncReturnValue {
     if args.len() != 1 {
         return BuiltInFuncReturnValue::Error(RuntimeError {
@@ -68,7 +75,7 @@ fn string_length(args: Vec<ObjectInfo>) -> BuiltInFuncReturnValue {
         _ => {
             return BuiltInFuncReturnValue::Error(RuntimeError {
                 kind: ErrorKind::ArgumentError,
-                msg: format!("method length accept string only"),
+                msg: "method length accept string only".to_string(),
             });
         }
     };
@@ -85,6 +92,10 @@ pub fn make_integer(value: i64) -> Primitive<i64> {
     Primitive::<i64>::make(value, fields)
 }
 
+pub fn integer_methods() -> HashMap<&'static str, Signature> {
+    HashMap::from([("as_float", Signature::make(vec![], Type::Float))])
+}
+
 fn integer_as_float(args: Vec<ObjectInfo>) -> BuiltInFuncReturnValue {
     if args.len() != 1 {
         return BuiltInFuncReturnValue::Error(RuntimeError {
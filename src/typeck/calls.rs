@@ -0,0 +1,499 @@
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use super::scope::{Callable, Entry, EnumInfo};
+use super::{fits, is_generic_function, is_vague, Checker};
+use crate::frontend::ast::{Arg, Expr, ExprKind, Identifier};
+use crate::frontend::span::Span;
+use crate::runtime::type_system::Type;
+use crate::stdlib::collections::{array_methods, map_methods};
+use crate::stdlib::primitives::{integer_methods, string_methods};
+use crate::stdlib::Member;
+
+struct CheckedArg<'a> {
+    name: Option<&'a str>,
+    type_: Option<Type>,
+    span: Span,
+}
+
+impl<'p> Checker<'p> {
+    pub(super) fn check_call_expr(
+        &mut self,
+        callee: &'p Expr,
+        args: &'p [Arg],
+        span: &Span,
+    ) -> Option<Type> {
+        let (fn_name, callable) = match &callee.kind {
+            ExprKind::Identifier(Identifier(name)) => {
+                let entry = self.scope.borrow().resolve(name);
+                match entry {
+                    Some(Entry::Var(var)) => match (var.callable, var.type_) {
+                        (Some(callable), _) => (name.clone(), Some(callable)),
+                        // bound by a cursor, a destructuring or a pattern
+                        (None, Some(type_ @ Type::Function(Some(_)))) => {
+                            (name.clone(), Callable::from_type(&type_).map(Rc::new))
+                        }
+                        (None, Some(type_)) if !is_vague(&type_) => {
+                            self.type_error(format!("'{}' is not callable", name), &callee.span);
+                            (name.clone(), None)
+                        }
+                        _ => (name.clone(), None),
+                    },
+                    Some(_) => {
+                        self.type_error(format!("'{}' is not callable", name), &callee.span);
+                        (name.clone(), None)
+                    }
+                    None => {
+                        self.name_error(format!("'{}' is not declared", name), &callee.span);
+                        (name.clone(), None)
+                    }
+                }
+            }
+            _ => {
+                let name = "<anonymous>".to_string();
+                match self.check_expr(callee) {
+                    Some(type_ @ Type::Function(Some(_))) => {
+                        (name, Callable::from_type(&type_).map(Rc::new))
+                    }
+                    Some(type_) if !is_vague(&type_) => {
+                        self.type_error(format!("'{}' is not callable", name), &callee.span);
+                        (name, None)
+                    }
+                    _ => (name, None),
+                }
+            }
+        };
+        self.check_call(&fn_name, callable.as_deref(), args, None, span)
+    }
+
+    pub(super) fn infer_returns_of(&mut self, callable: &Callable) {
+        if !callable.inferred {
+            return;
+        }
+        let position = self
+            .deferred
+            .iter()
+            .position(|deferred| std::ptr::eq(Rc::as_ptr(&deferred.callable), callable));
+        if let Some(position) = position {
+            let deferred = self.deferred.remove(position);
+            self.check_body(deferred);
+        }
+    }
+
+    pub(super) fn check_call(
+        &mut self,
+        fn_name: &str,
+        callable: Option<&Callable>,
+        args: &'p [Arg],
+        receiver: Option<Type>,
+        span: &Span,
+    ) -> Option<Type> {
+        let mut positional: Vec<CheckedArg> = vec![];
+        if let Some(receiver) = receiver {
+            positional.push(CheckedArg {
+                name: None,
+                type_: Some(receiver),
+                span: span.clone(),
+            });
+        }
+        let mut named: Vec<CheckedArg> = vec![];
+        let mut has_spread = false;
+        for Arg { name, value } in args {
+            let type_ = match &value.kind {
+                ExprKind::Spread(spread) => {
+                    self.check_spread(spread);
+                    has_spread = true;
+                    continue;
+                }
+                _ => self.check_expr(value),
+            };
+            let arg = CheckedArg {
+                name: name.as_ref().map(|Identifier(name)| name.as_str()),
+                type_,
+                span: value.span.clone(),
+            };
+            match arg.name {
+                Some(_) => named.push(arg),
+                None => positional.push(arg),
+            }
+        }
+
+        let callable = callable?;
+        self.infer_returns_of(callable);
+        let returns = || callable.returns().filter(|returns| !returns.is_generic());
+        let names_known = callable.params.iter().all(|param| param.name.is_some());
+        if callable.variadic || has_spread || (!named.is_empty() && !names_known) {
+            return returns();
+        }
+        if let (Some(arg), false) = (named.first(), callable.named) {
+            self.type_error(
+                format!(
+                    "Function '{}' takes no named arguments but got '{}'",
+                    fn_name,
+                    arg.name.unwrap_or_default()
+                ),
+                &arg.span,
+            );
+            return returns();
+        }
+
+        let has_rest = callable.params.last().is_some_and(|param| param.rest);
+        let params = callable
+            .params
+            .iter()
+            .filter(|param| !param.rest)
+            .collect::<Vec<_>>();
+        let arity = params.len();
+        let rest_args = match has_rest && positional.len() > arity {
+            true => positional.split_off(arity),
+            false => vec![],
+        };
+        if positional.len() > arity {
+            self.type_error(
+                format!(
+                    "Function '{}' expects {} args but provided {}",
+                    fn_name,
+                    arity,
+                    positional.len() + named.len()
+                ),
+                span,
+            );
+            return returns();
+        }
+
+        let mut bound: Vec<Option<CheckedArg>> = positional.into_iter().map(Some).collect();
+        bound.resize_with(arity, || None);
+        let mut unexpected: Vec<String> = vec![];
+        for arg in named {
+            let name = arg.name.unwrap_or_default();
+            match params
+                .iter()
+                .position(|param| param.name.as_deref() == Some(name))
+            {
+                Some(position) if bound[position].is_some() => {
+                    self.type_error(
+                        format!(
+                            "Function '{}' got argument '{}' more than once",
+                            fn_name, name
+                        ),
+                        &arg.span,
+                    );
+                    return returns();
+                }
+                Some(position) => bound[position] = Some(arg),
+                None => unexpected.push(format!("'{}'", name)),
+            }
+        }
+
+        let missing = params
+            .iter()
+            .zip(&bound)
+            .filter(|(param, arg)| arg.is_none() && !param.has_default)
+            .map(|(param, _)| format!("'{}'", param.name.as_deref().unwrap_or_default()))
+            .collect::<Vec<String>>();
+        let mut problems: Vec<String> = vec![];
+        if !missing.is_empty() {
+            problems.push(format!("is missing {}", missing.join(", ")));
+        }
+        if !unexpected.is_empty() {
+            problems.push(format!("got unexpected {}", unexpected.join(", ")));
+        }
+        if !problems.is_empty() {
+            self.type_error(
+                format!("Function '{}' {}", fn_name, problems.join(" and ")),
+                span,
+            );
+            return returns();
+        }
+
+        let mut type_args: HashMap<String, Type> = HashMap::new();
+        for (param, arg) in params.iter().zip(bound) {
+            if let (
+                Some(expected),
+                Some(CheckedArg {
+                    type_: Some(found),
+                    span,
+                    ..
+                }),
+            ) = (&param.type_, arg)
+            {
+                self.check_passed(expected, &found, &mut type_args, &span);
+            }
+        }
+        if let Some(Type::Array(Some(items_type))) = callable
+            .params
+            .last()
+            .filter(|param| param.rest)
+            .and_then(|param| param.type_.as_ref())
+        {
+            for arg in rest_args {
+                if let Some(found) = &arg.type_ {
+                    self.check_passed(items_type, found, &mut type_args, &arg.span);
+                }
+            }
+        }
+
+        let returns = callable.returns()?.substitute(&type_args);
+        Some(returns).filter(|returns| !returns.is_generic())
+    }
+
+    pub(super) fn check_passed(
+        &mut self,
+        expected: &Type,
+        found: &Type,
+        type_args: &mut HashMap<String, Type>,
+        span: &Span,
+    ) {
+        if is_vague(found) && !is_generic_function(found) {
+            return;
+        }
+        let substituted = expected.substitute(type_args);
+        if expected.infer(&found.instantiate(), type_args) {
+            return;
+        }
+        let inferred = match substituted != *expected {
+            true => format!(", which is '{}' in this call", substituted),
+            false => String::new(),
+        };
+        self.type_error(
+            format!(
+                "Passing argument of type '{}' to parameter of type '{}'{}",
+                found, expected, inferred
+            ),
+            span,
+        );
+    }
+
+    pub(super) fn check_field_access(
+        &mut self,
+        src: &'p Expr,
+        target: &'p Expr,
+        optional: bool,
+        span: &Span,
+    ) -> Option<Type> {
+        if let ExprKind::Identifier(Identifier(name)) = &src.kind {
+            let entry = self.scope.borrow().resolve(name);
+            match entry {
+                Some(Entry::Enum(def)) => return self.check_variant(&def, target, span),
+                Some(Entry::Module(module)) => {
+                    let members = self.modules.signatures(&module).unwrap_or_default();
+                    let src = format!("[Module] {}", module);
+                    return self.check_module_member(&members, &src, target, span);
+                }
+                _ => {}
+            }
+        }
+
+        let src_type = match optional {
+            true => self.check_expr(src),
+            false => self.check_non_null(src),
+        };
+        let src_type = match src_type {
+            Some(Type::Optional(inner)) => Some(*inner),
+            Some(Type::Null) if optional => Some(Type::Null),
+            type_ => type_,
+        };
+        let found = match src_type {
+            Some(src_type) if !is_vague(&src_type) => self.check_member(&src_type, target, span),
+            _ => {
+                self.check_target_args(target);
+                None
+            }
+        };
+        match found {
+            Some(type_ @ (Type::Optional(_) | Type::Null)) => Some(type_),
+            Some(type_) if optional => Some(Type::Optional(Box::new(type_))),
+            found => found,
+        }
+    }
+
+    fn check_target_args(&mut self, target: &'p Expr) {
+        if let ExprKind::Call(_, args) = &target.kind {
+            for Arg { value, .. } in args {
+                match &value.kind {
+                    ExprKind::Spread(spread) => {
+                        self.check_spread(spread);
+                    }
+                    _ => {
+                        self.check_expr(value);
+                    }
+                }
+            }
+        }
+    }
+
+    fn check_member(&mut self, src_type: &Type, target: &'p Expr, span: &Span) -> Option<Type> {
+        let methods = match src_type {
+            Type::Int => integer_methods(),
+            Type::String => string_methods(),
+            Type::Array(_) => array_methods(),
+            Type::Map(Some((key_type, value_type))) => map_methods(key_type, value_type),
+            Type::Map(None) => {
+                self.check_target_args(target);
+                return None;
+            }
+            Type::UserDefined(name) => return self.check_struct_member(name, target, span),
+            _ => {
+                self.semantic_error(format!("Field access not impl for type {}", src_type), span);
+                self.check_target_args(target);
+                return None;
+            }
+        };
+        let members = methods
+            .into_iter()
+            .map(|(name, signature)| (name.to_string(), Member::Function(signature)))
+            .collect();
+        let src = format!("type '{}'", src_type);
+        self.check_module_member(&members, &src, target, span)
+    }
+
+    fn check_module_member(
+        &mut self,
+        members: &HashMap<String, Member>,
+        src: &str,
+        target: &'p Expr,
+        span: &Span,
+    ) -> Option<Type> {
+        let (name, args) = match self.split_target(target, span)? {
+            (name, Some(args)) => (name, args),
+            (name, None) => {
+                return match members.get(name) {
+                    Some(Member::Value(type_)) => Some(type_.clone()),
+                    Some(Member::Function(_)) => Some(Type::Function(None)),
+                    None => {
+                        self.name_error(
+                            format!("No field '{}' associated with {}", name, src),
+                            &target.span,
+                        );
+                        None
+                    }
+                };
+            }
+        };
+        let signature = match members.get(name) {
+            Some(Member::Function(signature)) => signature,
+            Some(Member::Value(type_)) if !is_vague(type_) => {
+                self.type_error(format!("'{}' is not callable", name), &target.span);
+                self.check_target_args(target);
+                return None;
+            }
+            Some(Member::Value(_)) => {
+                self.check_target_args(target);
+                return None;
+            }
+            None => {
+                self.name_error(
+                    format!("No method '{}' associated with {}", name, src),
+                    &target.span,
+                );
+                self.check_target_args(target);
+                return None;
+            }
+        };
+        let callable = Callable::from_signature(signature);
+        self.check_call(name, Some(&callable), args, None, span)
+    }
+
+    fn check_struct_member(&mut self, name: &str, target: &'p Expr, span: &Span) -> Option<Type> {
+        let entry = self.scope.borrow().resolve(name);
+        let Some(Entry::Struct(def)) = entry else {
+            self.check_target_args(target);
+            return None;
+        };
+        let src = format!("type '{}'", name);
+        match self.split_target(target, span)? {
+            (method, Some(args)) => {
+                let Some(callable) = def.methods.get(method) else {
+                    self.name_error(
+                        format!("No method '{}' associated with {}", method, src),
+                        &target.span,
+                    );
+                    self.check_target_args(target);
+                    return None;
+                };
+                let receiver = Type::UserDefined(name.to_string());
+                self.check_call(method, Some(callable), args, Some(receiver), span)
+            }
+            (field, None) => match def.fields.iter().find(|(name, _)| name == field) {
+                Some((_, type_)) => Some(type_.clone()),
+                None => {
+                    self.name_error(
+                        format!("No field '{}' associated with {}", field, src),
+                        &target.span,
+                    );
+                    None
+                }
+            },
+        }
+    }
+
+    fn split_target(
+        &mut self,
+        target: &'p Expr,
+        span: &Span,
+    ) -> Option<(&'p str, Option<&'p [Arg]>)> {
+        match &target.kind {
+            ExprKind::Identifier(Identifier(name)) => Some((name, None)),
+            ExprKind::Call(callee, args) => match &callee.kind {
+                ExprKind::Identifier(Identifier(name)) => Some((name, Some(args))),
+                _ => {
+                    self.semantic_error("Function name must be an identifier".to_string(), span);
+                    None
+                }
+            },
+            _ => {
+                self.semantic_error("Can only access fields or methods".to_string(), span);
+                None
+            }
+        }
+    }
+
+    fn check_variant(&mut self, def: &EnumInfo, target: &'p Expr, span: &Span) -> Option<Type> {
+        let (variant, args) = self.split_target(target, span)?;
+        let args = args.unwrap_or_default();
+        let enum_type = Some(Type::Enum(def.name.clone()));
+
+        let Some((_, types)) = def.variants.iter().find(|(name, _)| name == variant) else {
+            self.name_error(
+                format!("'{}' has no variant '{}'", def.name, variant),
+                &target.span,
+            );
+            self.check_target_args(target);
+            return enum_type;
+        };
+        if args.len() != types.len() {
+            self.type_error(
+                format!(
+                    "'{}.{}' expects {} value(s) but {} provided",
+                    def.name,
+                    variant,
+                    types.len(),
+                    args.len()
+                ),
+                span,
+            );
+        }
+
+        for (arg, expected) in args.iter().zip(types) {
+            if arg.name.is_some() {
+                self.semantic_error(
+                    "Values of a variant can't be given by name".to_string(),
+                    &arg.value.span,
+                );
+            }
+            let Some(found) = self.check_expr(&arg.value) else {
+                continue;
+            };
+            if !fits(expected, &found) {
+                self.type_error(
+                    format!(
+                        "'{}.{}' expects value of type '{}' but provided value of type '{}'",
+                        def.name, variant, expected, found
+                    ),
+                    &arg.value.span,
+                );
+            }
+        }
+        enum_type
+    }
+}
@@ -0,0 +1,961 @@
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+
+use super::flow::leaving_jump;
+use super::scope::{Entry, Scope, ScopeKind, Var};
+use super::{fits, is_vague, join, Checker};
+use crate::frontend::ast::*;
+use crate::frontend::span::Span;
+use crate::runtime::runtime_error::ErrorKind;
+use crate::runtime::type_system::Type;
+
+impl<'p> Checker<'p> {
+    pub(super) fn check_expr(&mut self, expr: &'p Expr) -> Option<Type> {
+        match &expr.kind {
+            ExprKind::Literal(literal) => self.check_literal(literal, &expr.span),
+            ExprKind::Identifier(Identifier(name)) => {
+                let entry = self.scope.borrow().resolve(name);
+                match entry {
+                    // a function is known better once its return type is inferred
+                    Some(Entry::Var(Var {
+                        callable: Some(callable),
+                        ..
+                    })) if callable.inferred => {
+                        self.infer_returns_of(&callable);
+                        Some(callable.type_())
+                    }
+                    Some(Entry::Var(var)) => var.type_,
+                    Some(Entry::Module(_)) => Some(Type::Module),
+                    Some(_) => Some(Type::TypeAnnot),
+                    None => {
+                        self.name_error(format!("'{}' is not declared", name), &expr.span);
+                        None
+                    }
+                }
+            }
+            ExprKind::Call(callee, args) => self.check_call_expr(callee, args, &expr.span),
+            ExprKind::Infix(lhs, infix, rhs) => self.check_infix(lhs, infix, rhs, &expr.span),
+            ExprKind::Prefix(prefix, operand) => match prefix {
+                Prefix::Increment | Prefix::Decrement => {
+                    self.check_update(operand, &prefix.to_string())
+                }
+                Prefix::Not => {
+                    match self.check_expr(operand) {
+                        Some(type_) if type_ != Type::Boolean && !is_vague(&type_) => self
+                            .type_error(
+                                format!(
+                                    "'{}' prefix expects type boolean but found {}",
+                                    prefix, type_
+                                ),
+                                &expr.span,
+                            ),
+                        _ => {}
+                    }
+                    Some(Type::Boolean)
+                }
+                Prefix::Plus | Prefix::Minus => {
+                    let type_ = self.check_expr(operand)?;
+                    if !is_vague(&type_) && !is_number(&type_) {
+                        self.type_error(
+                            format!("'{}' prefix is for type number", prefix),
+                            &expr.span,
+                        );
+                    }
+                    Some(type_)
+                }
+            },
+            ExprKind::Postfix(operand, postfix) => self.check_update(operand, &postfix.to_string()),
+            ExprKind::Assign(Identifier(name), value) => {
+                self.check_assign(name, value, &expr.span);
+                None
+            }
+            ExprKind::FieldAcc(src, target) => {
+                self.check_field_access(src, target, false, &expr.span)
+            }
+            ExprKind::OptionalFieldAcc(src, target) => {
+                self.check_field_access(src, target, true, &expr.span)
+            }
+            ExprKind::Index(target, index) => self.check_index(target, index),
+            ExprKind::Slice(target, start, end) => {
+                self.check_slice(target, start.as_deref(), end.as_deref())
+            }
+            ExprKind::IndexAssign(target, index, value) => {
+                self.check_index_assign(target, index, value, &expr.span);
+                None
+            }
+            ExprKind::Interpolated(parts) => {
+                for part in parts {
+                    self.check_expr(part);
+                }
+                Some(Type::String)
+            }
+            ExprKind::Func(params, body, ret_type) => {
+                let callable = self.define_function(
+                    "<anonymous>",
+                    params,
+                    body,
+                    ret_type.as_ref(),
+                    &expr.span,
+                );
+                Some(callable.type_())
+            }
+            ExprKind::StructLit(Identifier(name), fields) => {
+                self.check_struct_literal(name, fields, &expr.span)
+            }
+            ExprKind::FieldAssign(src, Identifier(field), value) => {
+                self.check_field_assign(src, field, value, &expr.span);
+                None
+            }
+            ExprKind::Match(subject, arms) => self.check_match(subject, arms, true),
+            ExprKind::Spread(_) => {
+                self.semantic_error(
+                    "Spread '...' is only allowed in calls and array literals".to_string(),
+                    &expr.span,
+                );
+                None
+            }
+            ExprKind::Is(operand, type_) => {
+                self.check_expr(operand);
+                self.resolve_type(type_, &expr.span);
+                Some(Type::Boolean)
+            }
+        }
+    }
+
+    pub(super) fn check_non_null(&mut self, expr: &'p Expr) -> Option<Type> {
+        let type_ = self.check_expr(expr)?;
+        if let (ExprKind::Identifier(Identifier(name)), Type::Optional(inner)) =
+            (&expr.kind, &type_)
+        {
+            self.type_error(
+                format!(
+                    "'{}' of type '{}' may be null, check it with 'if {} != null' or use '??'",
+                    name, type_, name
+                ),
+                &expr.span,
+            );
+            return Some(*inner.clone());
+        }
+        Some(type_)
+    }
+
+    pub(super) fn check_expr_as(
+        &mut self,
+        expr: &'p Expr,
+        expected: Option<&Type>,
+    ) -> Option<Type> {
+        match (&expr.kind, expected) {
+            (ExprKind::Literal(Literal::Array(items)), Some(Type::Array(Some(items_type)))) => {
+                self.check_array_literal(items, Some(items_type), &expr.span)
+            }
+            (
+                ExprKind::Literal(Literal::Map(entries)),
+                Some(Type::Map(Some((key_type, value_type)))),
+            ) => self.check_map_literal(entries, Some((key_type, value_type)), &expr.span),
+            (_, Some(Type::Optional(inner))) => self.check_expr_as(expr, Some(inner)),
+            _ => self.check_expr(expr),
+        }
+    }
+
+    fn check_literal(&mut self, literal: &'p Literal, span: &Span) -> Option<Type> {
+        match literal {
+            Literal::Null => Some(Type::Null),
+            Literal::Int(_) => Some(Type::Int),
+            Literal::Float(_) => Some(Type::Float),
+            Literal::String(_) => Some(Type::String),
+            Literal::Boolean(_) => Some(Type::Boolean),
+            Literal::Array(items) => self.check_array_literal(items, None, span),
+            Literal::Map(entries) => self.check_map_literal(entries, None, span),
+            Literal::Tuple(items) => {
+                let mut types = vec![];
+                for item in items {
+                    types.push(self.check_expr(item));
+                }
+                Some(Type::Tuple(
+                    types.into_iter().collect::<Option<Vec<Type>>>()?,
+                ))
+            }
+        }
+    }
+
+    fn check_array_literal(
+        &mut self,
+        items: &'p [Expr],
+        expected: Option<&Type>,
+        span: &Span,
+    ) -> Option<Type> {
+        let mut items_type: Option<Type> = expected.cloned();
+        let mut known = true;
+        for item in items {
+            let type_ = match &item.kind {
+                ExprKind::Spread(spread) => match self.check_spread(spread) {
+                    Some(Some(type_)) => type_,
+                    Some(None) => continue,
+                    None => {
+                        known = false;
+                        continue;
+                    }
+                },
+                _ => match self.check_expr(item) {
+                    Some(type_) => type_,
+                    None => {
+                        known = false;
+                        continue;
+                    }
+                },
+            };
+            let first = items_type.get_or_insert_with(|| type_.clone());
+            if !fits(first, &type_) && !is_vague(first) {
+                self.type_error("Array item's type mismatch".to_string(), span);
+                return None;
+            }
+        }
+        if !known {
+            return None;
+        }
+        Some(Type::Array(items_type.map(Box::new)))
+    }
+
+    pub(super) fn check_spread(&mut self, expr: &'p Expr) -> Option<Option<Type>> {
+        match self.check_expr(expr)? {
+            Type::Array(items_type) => Some(items_type.map(|items_type| *items_type)),
+            type_ if is_vague(&type_) => None,
+            type_ => {
+                self.type_error(
+                    format!("Only arrays can be spread but found '{}'", type_),
+                    &expr.span,
+                );
+                None
+            }
+        }
+    }
+
+    fn check_map_literal(
+        &mut self,
+        entries: &'p [(Expr, Expr)],
+        expected: Option<(&Type, &Type)>,
+        span: &Span,
+    ) -> Option<Type> {
+        let mut entry_types: Option<(Type, Type)> =
+            expected.map(|(key_type, value_type)| (key_type.clone(), value_type.clone()));
+        let mut known = true;
+        for (key, value) in entries {
+            let key_type = self.check_expr(key);
+            let value_type = self.check_expr(value);
+            let (Some(key_type), Some(value_type)) = (key_type, value_type) else {
+                known = false;
+                continue;
+            };
+            if !matches!(key_type, Type::Int | Type::String | Type::Boolean) && !is_vague(&key_type)
+            {
+                self.type_error(
+                    format!(
+                        "Map keys must be of type 'int', 'string' or 'boolean' but found '{}'",
+                        key_type
+                    ),
+                    &key.span,
+                );
+                return None;
+            }
+            let (first_key, first_value) =
+                entry_types.get_or_insert_with(|| (key_type.clone(), value_type.clone()));
+            if !fits(first_key, &key_type) {
+                self.type_error("Map key's type mismatch".to_string(), span);
+                return None;
+            }
+            if !fits(first_value, &value_type) && !is_vague(first_value) {
+                self.type_error("Map value's type mismatch".to_string(), span);
+                return None;
+            }
+        }
+        if !known {
+            return None;
+        }
+        Some(Type::Map(entry_types.map(|(key_type, value_type)| {
+            (Box::new(key_type), Box::new(value_type))
+        })))
+    }
+
+    fn check_narrowed_expr(&mut self, expr: &'p Expr, narrowed: &[(String, Type)]) -> Option<Type> {
+        if narrowed.is_empty() {
+            return self.check_expr(expr);
+        }
+        let parent_scope = Rc::clone(&self.scope);
+        let mut scope = Scope::make_from(Rc::clone(&parent_scope), ScopeKind::IfElse);
+        for (name, type_) in narrowed {
+            scope.narrow(name, type_.clone());
+        }
+        self.scope = Rc::new(RefCell::new(scope));
+        let type_ = self.check_expr(expr);
+        self.scope = parent_scope;
+        type_
+    }
+
+    fn check_infix(
+        &mut self,
+        lhs: &'p Expr,
+        infix: &Infix,
+        rhs: &'p Expr,
+        span: &Span,
+    ) -> Option<Type> {
+        match infix {
+            Infix::And | Infix::Or => {
+                let lhs_type = self.check_expr(lhs);
+                // the rhs only runs when the lhs is true for `&&` and false for `||`
+                let narrowed = self.narrowed_types(lhs, matches!(infix, Infix::And));
+                let rhs_type = self.check_narrowed_expr(rhs, &narrowed);
+                for (operand, type_) in [(lhs, lhs_type), (rhs, rhs_type)] {
+                    match type_ {
+                        Some(type_) if type_ != Type::Boolean && !is_vague(&type_) => self
+                            .type_error(
+                                format!(
+                                    "'{}' operation expects type boolean but found {}",
+                                    infix, type_
+                                ),
+                                &operand.span,
+                            ),
+                        _ => {}
+                    }
+                }
+                return Some(Type::Boolean);
+            }
+            Infix::NullCoalescing => {
+                let lhs = self.check_expr(lhs);
+                let rhs = self.check_expr(rhs);
+                return match (lhs?, rhs) {
+                    (Type::Null, rhs) => rhs,
+                    (Type::Optional(inner), Some(rhs)) if inner.accepts(&rhs) => Some(*inner),
+                    (Type::Optional(inner), Some(Type::Optional(rhs))) if inner.accepts(&rhs) => {
+                        Some(Type::Optional(inner))
+                    }
+                    (Type::Optional(_), _) => None,
+                    (lhs, _) => Some(lhs),
+                };
+            }
+            Infix::Equal | Infix::NotEqual => {
+                let lhs = self.check_expr(lhs);
+                let rhs = self.check_expr(rhs);
+                if let (Some(lhs), Some(rhs)) = (lhs, rhs) {
+                    let equatable =
+                        |type_: &Type| !matches!(type_, Type::Function(Some(_)) | Type::Module);
+                    if !comparable(&lhs, &rhs) || !equatable(&lhs) || !equatable(&rhs) {
+                        self.report_operands(infix, &lhs, &rhs, span);
+                    }
+                }
+                return Some(Type::Boolean);
+            }
+            _ => {}
+        }
+
+        let is_comparison = matches!(
+            infix,
+            Infix::LessThan | Infix::LessOrEqual | Infix::GratherThan | Infix::GratherOrEqual
+        );
+        let result = |type_: Type| match is_comparison {
+            true => Type::Boolean,
+            false => type_,
+        };
+
+        let lhs = self.check_non_null(lhs);
+        let rhs = self.check_non_null(rhs);
+        let (lhs, rhs) = match (lhs, rhs) {
+            (Some(lhs), Some(rhs)) if !is_vague(&lhs) && !is_vague(&rhs) => (lhs, rhs),
+            (Some(known), _) | (_, Some(known)) if !is_vague(&known) => return Some(result(known)),
+            _ => return is_comparison.then_some(Type::Boolean),
+        };
+
+        if lhs != rhs || matches!(lhs, Type::Optional(_) | Type::Union(_)) {
+            self.report_operands(infix, &lhs, &rhs, span);
+            return Some(result(lhs));
+        }
+        let supported = match lhs {
+            Type::Int | Type::Float => true,
+            Type::String => matches!(infix, Infix::Plus),
+            Type::Boolean => is_comparison,
+            _ => {
+                self.report_operands(infix, &lhs, &rhs, span);
+                return Some(result(lhs));
+            }
+        };
+        if !supported {
+            self.type_error(
+                format!("'{}' operation not implemented for type {}", infix, lhs),
+                span,
+            );
+        }
+        Some(result(lhs))
+    }
+
+    fn report_operands(&mut self, infix: &Infix, lhs: &Type, rhs: &Type, span: &Span) {
+        self.type_error(
+            format!(
+                "'{}' operation not allowed between types {} and {}",
+                infix, lhs, rhs
+            ),
+            span,
+        );
+    }
+
+    fn check_update(&mut self, operand: &'p Expr, operator: &str) -> Option<Type> {
+        let ExprKind::Identifier(Identifier(name)) = &operand.kind else {
+            self.type_error(
+                format!("'{}' can only be applied to a variable", operator),
+                &operand.span,
+            );
+            return None;
+        };
+        let var = self.resolve_var(name, &operand.span)?;
+        if !var.is_mut {
+            self.set_not_assignable(name, &var, &operand.span);
+            return var.type_;
+        }
+        match &var.type_ {
+            Some(type_) if !is_vague(type_) && !is_number(type_) => self.type_error(
+                format!("'{}' operation is only allowed for type 'number'", operator),
+                &operand.span,
+            ),
+            _ => {}
+        }
+        var.type_
+    }
+
+    fn resolve_var(&mut self, name: &str, span: &Span) -> Option<Var> {
+        let entry = self.scope.borrow().resolve(name);
+        match entry {
+            Some(Entry::Var(var)) => Some(var),
+            Some(_) => {
+                self.name_error(format!("'{}' is not assignable", name), span);
+                None
+            }
+            None => {
+                self.name_error(format!("'{}' is not declared", name), span);
+                None
+            }
+        }
+    }
+
+    fn set_not_assignable(&mut self, name: &str, var: &Var, span: &Span) {
+        let msg = match &var.declared_at {
+            Some(declared_at) => format!(
+                "'{}' is not assignable, it's declared immutable at {}",
+                name, declared_at
+            ),
+            None => format!("'{}' is not assignable", name),
+        };
+        self.name_error(msg, span);
+    }
+
+    fn check_assign(&mut self, name: &str, value: &'p Expr, span: &Span) {
+        let declared_type = self.scope.borrow().declared_type(name);
+        let found = self.check_expr_as(value, declared_type.as_ref());
+        let Some(var) = self.resolve_var(name, span) else {
+            return;
+        };
+        if !var.is_mut {
+            self.set_not_assignable(name, &var, span);
+            return;
+        }
+
+        self.scope.borrow_mut().widen(name);
+        let (Some(expected), Some(found)) = (declared_type, found) else {
+            return;
+        };
+        if is_vague(&found) {
+            return;
+        }
+        let msg = match (&expected, &found) {
+            (Type::Array(Some(_)), Type::Array(None)) => return,
+            (Type::Array(Some(expected)), Type::Array(Some(found))) if !fits(expected, found) => {
+                format!(
+                    "'{}' expects array of type '{}' but provided array of type '{}'",
+                    name, expected, found
+                )
+            }
+            (Type::Array(Some(expected)), found) if !matches!(found, Type::Array(_)) => {
+                format!("'{}' expects value of type Array<{}>", name, expected)
+            }
+            (expected, found) if !fits(expected, found) => format!(
+                "'{}' expects value of type '{}' but provided value of type '{}'",
+                name, expected, found
+            ),
+            _ => return,
+        };
+        self.type_error(msg, span);
+    }
+
+    fn check_index_type(&mut self, index: &'p Expr) {
+        match self.check_expr(index) {
+            Some(type_) if type_ != Type::Int && !is_vague(&type_) => self.type_error(
+                format!("Index must be of type 'int' but found '{}'", type_),
+                &index.span,
+            ),
+            _ => {}
+        }
+    }
+
+    fn check_map_key(&mut self, key_type: &Type, key: &'p Expr) {
+        match self.check_expr(key) {
+            Some(found) if found != *key_type && !is_vague(&found) => self.type_error(
+                format!("Map keys are of type '{}' but found '{}'", key_type, found),
+                &key.span,
+            ),
+            _ => {}
+        }
+    }
+
+    fn check_index(&mut self, target: &'p Expr, index: &'p Expr) -> Option<Type> {
+        let target_type = self.check_non_null(target);
+        if let Some(Type::Map(entry_types)) = &target_type {
+            let (key_type, value_type) = match entry_types {
+                Some((key_type, value_type)) => (key_type, value_type),
+                None => {
+                    self.check_expr(index);
+                    return None;
+                }
+            };
+            self.check_map_key(key_type, index);
+            return Some(*value_type.clone());
+        }
+        self.check_index_type(index);
+
+        match target_type? {
+            Type::Array(items_type) => items_type.map(|items_type| *items_type),
+            Type::String => Some(Type::String),
+            Type::Tuple(items) => match &index.kind {
+                ExprKind::Literal(Literal::Int(position)) => {
+                    let item = usize::try_from(*position)
+                        .ok()
+                        .and_then(|position| items.get(position));
+                    if item.is_none() {
+                        self.error(
+                            ErrorKind::IndexError,
+                            format!(
+                                "Index {} out of range for tuple of length {}",
+                                position,
+                                items.len()
+                            ),
+                            &index.span,
+                        );
+                    }
+                    item.cloned()
+                }
+                _ => {
+                    let mut members: Vec<Type> = vec![];
+                    for item in items {
+                        if !members.contains(&item) {
+                            members.push(item);
+                        }
+                    }
+                    match members.len() {
+                        0 | 1 => members.pop(),
+                        _ => Some(Type::Union(members)),
+                    }
+                }
+            },
+            type_ if is_vague(&type_) => None,
+            type_ => {
+                self.type_error(format!("Type '{}' can't be indexed", type_), &target.span);
+                None
+            }
+        }
+    }
+
+    fn check_slice(
+        &mut self,
+        target: &'p Expr,
+        start: Option<&'p Expr>,
+        end: Option<&'p Expr>,
+    ) -> Option<Type> {
+        let target_type = self.check_expr(target);
+        for bound in [start, end].into_iter().flatten() {
+            self.check_index_type(bound);
+        }
+        match target_type? {
+            type_ @ (Type::Array(_) | Type::String) => Some(type_),
+            type_ if is_vague(&type_) => None,
+            type_ => {
+                self.type_error(format!("Type '{}' can't be sliced", type_), &target.span);
+                None
+            }
+        }
+    }
+
+    fn check_index_assign(
+        &mut self,
+        target: &'p Expr,
+        index: &'p Expr,
+        value: &'p Expr,
+        span: &Span,
+    ) {
+        let mut indexes = vec![index];
+        let mut root = target;
+        while let ExprKind::Index(inner_target, inner_index) = &root.kind {
+            indexes.push(inner_index);
+            root = inner_target;
+        }
+        indexes.reverse();
+
+        let slot = 'slot: {
+            let ExprKind::Identifier(Identifier(name)) = &root.kind else {
+                self.semantic_error(
+                    "Only items of variables can be assigned".to_string(),
+                    &root.span,
+                );
+                break 'slot None;
+            };
+            let Some(var) = self.resolve_var(name, &root.span) else {
+                break 'slot None;
+            };
+            // maps are shared like `m.set(key, value)`, only arrays need `mut`
+            if !var.is_mut && !matches!(var.type_, Some(Type::Map(_))) {
+                self.set_not_assignable(name, &var, span);
+                break 'slot None;
+            }
+
+            let mut slot = var.type_;
+            for index in indexes {
+                slot = match slot {
+                    Some(Type::Array(items_type)) => {
+                        self.check_index_type(index);
+                        items_type.map(|items_type| *items_type)
+                    }
+                    Some(Type::Map(Some((key_type, value_type)))) => {
+                        self.check_map_key(&key_type, index);
+                        Some(*value_type)
+                    }
+                    Some(type_ @ (Type::String | Type::Tuple(_))) => {
+                        self.type_error(
+                            format!("Type '{}' can't be modified by index", type_),
+                            span,
+                        );
+                        break 'slot None;
+                    }
+                    Some(type_) if !is_vague(&type_) && !matches!(type_, Type::Map(None)) => {
+                        self.type_error(format!("Type '{}' can't be indexed", type_), span);
+                        break 'slot None;
+                    }
+                    _ => {
+                        self.check_expr(index);
+                        None
+                    }
+                };
+            }
+            slot.map(|slot| (name, slot))
+        };
+        let found = self.check_expr_as(value, slot.as_ref().map(|(_, slot)| slot));
+
+        if let (Some((name, expected)), Some(found)) = (slot, found) {
+            if !fits(&expected, &found) {
+                self.type_error(
+                    format!(
+                        "'{}' expects items of type '{}' but provided value of type '{}'",
+                        name, expected, found
+                    ),
+                    span,
+                );
+            }
+        }
+    }
+
+    fn check_struct_literal(
+        &mut self,
+        name: &str,
+        values: &'p [(Identifier, Expr)],
+        span: &Span,
+    ) -> Option<Type> {
+        let entry = self.scope.borrow().resolve(name);
+        let def = match entry {
+            Some(Entry::Struct(def)) => Some(def),
+            Some(_) => {
+                self.type_error(format!("'{}' is not a struct", name), span);
+                None
+            }
+            None => {
+                self.name_error(format!("'{}' is not declared", name), span);
+                None
+            }
+        };
+
+        let mut given: Vec<&str> = vec![];
+        for (Identifier(field), value) in values {
+            let expected = def
+                .as_ref()
+                .and_then(|def| def.fields.iter().find(|(name, _)| name == field))
+                .map(|(_, expected)| expected.clone());
+            let found = self.check_expr_as(value, expected.as_ref());
+            let Some(def) = &def else {
+                continue;
+            };
+            let Some((_, expected)) = def.fields.iter().find(|(name, _)| name == field) else {
+                self.name_error(format!("'{}' has no field '{}'", name, field), &value.span);
+                continue;
+            };
+            if given.contains(&field.as_str()) {
+                self.name_error(
+                    format!("Field '{}' is given more than once", field),
+                    &value.span,
+                );
+                continue;
+            }
+            given.push(field);
+            if let Some(found) = found {
+                if !fits(expected, &found) {
+                    self.type_error(
+                        format!(
+                            "Field '{}.{}' expects value of type '{}' but provided value of type '{}'",
+                            name, field, expected, found
+                        ),
+                        &value.span,
+                    );
+                }
+            }
+        }
+
+        let def = def?;
+        let missing = def
+            .fields
+            .iter()
+            .filter(|(field, _)| !given.contains(&field.as_str()))
+            .map(|(field, _)| format!("'{}'", field))
+            .collect::<Vec<String>>();
+        if !missing.is_empty() {
+            self.type_error(
+                format!("Missing fields {} in '{}'", missing.join(", "), name),
+                span,
+            );
+        }
+        Some(Type::UserDefined(name.to_string()))
+    }
+
+    fn check_field_assign(&mut self, src: &'p Expr, field: &str, value: &'p Expr, span: &Span) {
+        let src_type = self.check_expr(src);
+        let declared = 'declared: {
+            let struct_name = match src_type {
+                Some(Type::UserDefined(name)) => name,
+                Some(type_) if !is_vague(&type_) => {
+                    self.type_error(
+                        format!("Can't assign field '{}' of type '{}'", field, type_),
+                        span,
+                    );
+                    break 'declared None;
+                }
+                _ => break 'declared None,
+            };
+            let Some(Entry::Struct(def)) = self.scope.borrow().resolve(&struct_name) else {
+                break 'declared None;
+            };
+            let Some((_, expected)) = def.fields.iter().find(|(name, _)| name == field) else {
+                self.name_error(format!("'{}' has no field '{}'", struct_name, field), span);
+                break 'declared None;
+            };
+            Some((struct_name, expected.clone()))
+        };
+        let found = self.check_expr_as(value, declared.as_ref().map(|(_, expected)| expected));
+
+        if let (Some((struct_name, expected)), Some(found)) = (declared, found) {
+            if !fits(&expected, &found) {
+                self.type_error(
+                    format!(
+                        "Field '{}.{}' expects value of type '{}' but provided value of type '{}'",
+                        struct_name, field, expected, found
+                    ),
+                    span,
+                );
+            }
+        }
+    }
+
+    pub(super) fn check_match(
+        &mut self,
+        subject: &'p Expr,
+        arms: &'p [MatchArm],
+        as_value: bool,
+    ) -> Option<Type> {
+        let mut subject_type = self.check_expr(subject);
+        let mut arm_types: Vec<Option<Type>> = vec![];
+
+        for arm in arms {
+            let mut bindings = vec![];
+            self.check_pattern(&arm.pattern, subject_type.clone(), &mut bindings, &arm.span);
+
+            let parent_scope = Rc::clone(&self.scope);
+            let mut arm_scope = Scope::make_from(Rc::clone(&parent_scope), ScopeKind::IfElse);
+            for (name, type_) in bindings {
+                let var = Var {
+                    type_,
+                    is_mut: false,
+                    declared_at: Some(arm.span.clone()),
+                    callable: None,
+                };
+                arm_scope.declare(name, Entry::Var(var));
+            }
+            self.scope = Rc::new(RefCell::new(arm_scope));
+
+            if let Some(guard) = &arm.guard {
+                match self.check_expr(guard) {
+                    Some(type_) if type_ != Type::Boolean && !is_vague(&type_) => self.type_error(
+                        format!(
+                            "Match guard must be of type 'boolean' but found '{}'",
+                            type_
+                        ),
+                        &guard.span,
+                    ),
+                    _ => {}
+                }
+            }
+            let arm_type = match &arm.body {
+                ArmBody::Expr(expr) => self.check_expr(expr),
+                ArmBody::Block(block) => {
+                    self.check_block(block);
+                    if let Some(jump) = leaving_jump(block, &mut vec![]).filter(|_| as_value) {
+                        self.semantic_error(
+                            format!(
+                                "'{}' can't leave a match used as a value",
+                                jump_keyword(jump)
+                            ),
+                            &jump.span,
+                        );
+                    }
+                    Some(Type::Null)
+                }
+            };
+            arm_types.push(arm_type);
+            self.scope = parent_scope;
+
+            // the arms after an unguarded `null` one only see values
+            if let (Pattern::Literal(Literal::Null), None, Some(Type::Optional(inner))) =
+                (&arm.pattern, &arm.guard, &subject_type)
+            {
+                subject_type = Some(*inner.clone());
+            }
+        }
+
+        let arm_types = arm_types.into_iter().collect::<Option<Vec<Type>>>()?;
+        Some(join(arm_types).unwrap_or(Type::Null))
+    }
+
+    fn check_pattern(
+        &mut self,
+        pattern: &Pattern,
+        subject: Option<Type>,
+        bindings: &mut Vec<(String, Option<Type>)>,
+        span: &Span,
+    ) {
+        let subject = subject.filter(|subject| !is_vague(subject));
+        let pattern_type = match pattern {
+            Pattern::Wildcard | Pattern::Literal(Literal::Null) => return,
+            Pattern::Binding(name) => {
+                bindings.push((name.clone(), subject));
+                return;
+            }
+            Pattern::Literal(literal) => {
+                let type_ = match literal {
+                    Literal::Int(_) => Type::Int,
+                    Literal::Float(_) => Type::Float,
+                    Literal::String(_) => Type::String,
+                    Literal::Boolean(_) => Type::Boolean,
+                    _ => return,
+                };
+                (type_.clone(), type_)
+            }
+            Pattern::Range(..) => (Type::Int, Type::Range),
+            Pattern::Variant(Identifier(enum_name), Identifier(variant), patterns) => {
+                let entry = self.scope.borrow().resolve(enum_name);
+                let def = match entry {
+                    Some(Entry::Enum(def)) => def,
+                    Some(_) => {
+                        self.type_error(format!("'{}' is not an enum", enum_name), span);
+                        return;
+                    }
+                    None => {
+                        self.name_error(format!("'{}' is not declared", enum_name), span);
+                        return;
+                    }
+                };
+                let enum_type = Type::Enum(enum_name.clone());
+                if let Some(subject) = &subject {
+                    if !fits(subject, &enum_type) {
+                        self.type_error(
+                            format!(
+                                "Pattern of type '{}' can't match value of type '{}'",
+                                enum_type, subject
+                            ),
+                            span,
+                        );
+                        return;
+                    }
+                }
+                let Some((_, types)) = def.variants.iter().find(|(name, _)| name == variant) else {
+                    self.name_error(
+                        format!("'{}' has no variant '{}'", enum_name, variant),
+                        span,
+                    );
+                    return;
+                };
+                if patterns.len() != types.len() {
+                    self.type_error(
+                        format!(
+                            "'{}.{}' holds {} value(s) but the pattern has {}",
+                            enum_name,
+                            variant,
+                            types.len(),
+                            patterns.len()
+                        ),
+                        span,
+                    );
+                    return;
+                }
+                for (pattern, type_) in patterns.iter().zip(types) {
+                    self.check_pattern(pattern, Some(type_.clone()), bindings, span);
+                }
+                return;
+            }
+        };
+
+        let (matched_type, shown_type) = pattern_type;
+        if let Some(subject) = subject {
+            if !fits(&subject, &matched_type) {
+                self.type_error(
+                    format!(
+                        "Pattern of type '{}' can't match value of type '{}'",
+                        shown_type, subject
+                    ),
+                    span,
+                );
+            }
+        }
+    }
+}
+
+fn is_number(type_: &Type) -> bool {
+    matches!(type_, Type::Int | Type::Float)
+}
+
+fn comparable(lhs: &Type, rhs: &Type) -> bool {
+    let has_empty = |type_: &Type| {
+        let found = Cell::new(false);
+        type_.replace(&|type_| {
+            if matches!(type_, Type::Array(None) | Type::Map(None)) {
+                found.set(true);
+            }
+            None
+        });
+        found.get()
+    };
+    is_vague(lhs)
+        || is_vague(rhs)
+        || has_empty(lhs)
+        || has_empty(rhs)
+        || matches!(lhs, Type::Null)
+        || matches!(rhs, Type::Null)
+        || lhs.accepts(rhs)
+        || rhs.accepts(lhs)
+}
+
+fn jump_keyword(jump: &Stmt) -> &'static str {
+    match jump.kind {
+        StmtKind::Break(_) => "break",
+        StmtKind::Continue(_) => "continue",
+        _ => "return",
+    }
+}
@@ -0,0 +1,128 @@
+use crate::frontend::ast::{ArmBody, Else, ExprKind, Literal, Stmt, StmtKind};
+
+// Whether running `block` always ends in a `return`, `break` or
+// `continue`, leaving the rest of the enclosing block behind.
+pub fn always_exits(block: &[Stmt]) -> bool {
+    block.iter().any(exits)
+}
+
+pub fn exits(stmt: &Stmt) -> bool {
+    match &stmt.kind {
+        StmtKind::Return(_) | StmtKind::Break(_) | StmtKind::Continue(_) => true,
+        _ => every_branch(stmt, always_exits),
+    }
+}
+
+// Whether a function body never gets to its end, each path either
+// returns or loops forever.
+pub fn always_returns(block: &[Stmt]) -> bool {
+    block.iter().any(|stmt| match &stmt.kind {
+        StmtKind::Return(_) => true,
+        StmtKind::While {
+            condition,
+            block,
+            label,
+        } => {
+            matches!(condition.kind, ExprKind::Literal(Literal::Boolean(true)))
+                && !breaks_out(block, label.as_deref(), true)
+        }
+        _ => every_branch(stmt, always_returns),
+    })
+}
+
+fn every_branch(stmt: &Stmt, holds: fn(&[Stmt]) -> bool) -> bool {
+    match &stmt.kind {
+        StmtKind::If {
+            consequence,
+            alternative: Some(alternative),
+            ..
+        } => {
+            holds(consequence)
+                && match alternative {
+                    Else::Block(block) => holds(block),
+                    Else::If(next) => holds(std::slice::from_ref(next)),
+                }
+        }
+        // matches are exhaustive, the parser reports the ones that aren't
+        StmtKind::Expr(expr) => match &expr.kind {
+            ExprKind::Match(_, arms) => arms.iter().all(|arm| match &arm.body {
+                ArmBody::Block(block) => holds(block),
+                ArmBody::Expr(_) => false,
+            }),
+            _ => false,
+        },
+        _ => false,
+    }
+}
+
+// Whether a `break` in `block` leaves the loop labelled `label` whose
+// body it is, `innermost` while no other loop is in between.
+fn breaks_out(block: &[Stmt], label: Option<&str>, innermost: bool) -> bool {
+    block.iter().any(|stmt| match &stmt.kind {
+        StmtKind::Break(None) => innermost,
+        StmtKind::Break(Some(target)) => label == Some(target.as_str()),
+        StmtKind::If {
+            consequence,
+            alternative,
+            ..
+        } => {
+            breaks_out(consequence, label, innermost)
+                || match alternative {
+                    Some(Else::Block(block)) => breaks_out(block, label, innermost),
+                    Some(Else::If(next)) => {
+                        breaks_out(std::slice::from_ref(next), label, innermost)
+                    }
+                    None => false,
+                }
+        }
+        StmtKind::While { block, .. } | StmtKind::ForLoop { block, .. } => {
+            breaks_out(block, label, false)
+        }
+        StmtKind::Expr(expr) => match &expr.kind {
+            ExprKind::Match(_, arms) => arms.iter().any(|arm| match &arm.body {
+                ArmBody::Block(block) => breaks_out(block, label, innermost),
+                ArmBody::Expr(_) => false,
+            }),
+            _ => false,
+        },
+        _ => false,
+    })
+}
+
+// The first `return`, `break` or `continue` in `block` that would leave
+// it, `loops` are the labels of the loops around it inside the block.
+pub fn leaving_jump<'a>(block: &'a [Stmt], loops: &mut Vec<Option<&'a str>>) -> Option<&'a Stmt> {
+    block.iter().find_map(|stmt| match &stmt.kind {
+        StmtKind::Return(_) => Some(stmt),
+        StmtKind::Break(label) | StmtKind::Continue(label) => {
+            let leaves = match label {
+                Some(label) => !loops.contains(&Some(label.as_str())),
+                None => loops.is_empty(),
+            };
+            leaves.then_some(stmt)
+        }
+        StmtKind::If {
+            consequence,
+            alternative,
+            ..
+        } => leaving_jump(consequence, loops).or_else(|| match alternative {
+            Some(Else::Block(block)) => leaving_jump(block, loops),
+            Some(Else::If(next)) => leaving_jump(std::slice::from_ref(next), loops),
+            None => None,
+        }),
+        StmtKind::While { block, label, .. } | StmtKind::ForLoop { block, label, .. } => {
+            loops.push(label.as_deref());
+            let jump = leaving_jump(block, loops);
+            loops.pop();
+            jump
+        }
+        StmtKind::Expr(expr) => match &expr.kind {
+            ExprKind::Match(_, arms) => arms.iter().find_map(|arm| match &arm.body {
+                ArmBody::Block(block) => leaving_jump(block, loops),
+                ArmBody::Expr(_) => None,
+            }),
+            _ => None,
+        },
+        _ => None,
+    })
+}
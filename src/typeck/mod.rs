@@ -0,0 +1,1337 @@
+mod calls;
+mod exprs;
+mod flow;
+mod scope;
+
+use core::fmt;
+use std::{cell::RefCell, collections::HashMap, rc::Rc};
+
+use crate::frontend::ast::*;
+use crate::frontend::span::Span;
+use crate::runtime::narrowings;
+use crate::runtime::runtime_error::ErrorKind;
+use crate::runtime::type_system::{expr_type_to_object_type, Type};
+use crate::stdlib::builtins::builtin_signatures;
+use crate::stdlib::modules::ModulesManager;
+use crate::stdlib::Member;
+use flow::{always_exits, always_returns, exits};
+use scope::{
+    Callable, CallableParam, Entry, EnumInfo, Scope, ScopeKind, StructInfo, TypeKind, Var,
+};
+
+#[derive(Clone)]
+pub struct CheckError {
+    kind: ErrorKind,
+    msg: String,
+    span: Span,
+}
+
+impl CheckError {
+    pub fn report(&self, source: &str) -> String {
+        format!("{}\n{}", self, self.span.snippet(source))
+    }
+}
+
+impl fmt::Display for CheckError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.kind, self.msg)
+    }
+}
+
+pub fn check(program: &Program) -> Vec<CheckError> {
+    let mut checker = Checker::new();
+    checker.check_block(program);
+
+    let mut errors = checker.errors;
+    errors.sort_by_key(|err| (err.span.line, err.span.colm));
+    errors
+}
+
+struct Deferred<'p> {
+    scope: Rc<RefCell<Scope>>,
+    name: String,
+    params: &'p [Param],
+    body: &'p [Stmt],
+    callable: Rc<Callable>,
+    span: Span,
+}
+
+// Walks the program like the runtime would, but through every branch and
+// with types in place of values. A type is `None` when it can only be
+// known by running the program, which is never reported.
+struct Checker<'p> {
+    scope: Rc<RefCell<Scope>>,
+    modules: ModulesManager,
+    deferred: Vec<Deferred<'p>>,
+    found_returns: Vec<Vec<Option<Type>>>,
+    cyclic_aliases: Vec<Span>,
+    errors: Vec<CheckError>,
+}
+
+impl<'p> Checker<'p> {
+    fn new() -> Self {
+        let entries = builtin_signatures()
+            .into_iter()
+            .map(|(name, member)| (name, member_entry(member)))
+            .collect();
+        Self {
+            scope: Rc::new(RefCell::new(Scope::global(entries))),
+            modules: ModulesManager::setup(),
+            deferred: vec![],
+            found_returns: vec![],
+            cyclic_aliases: vec![],
+            errors: vec![],
+        }
+    }
+
+    fn error(&mut self, kind: ErrorKind, msg: String, span: &Span) {
+        self.errors.push(CheckError {
+            kind,
+            msg,
+            span: span.clone(),
+        });
+    }
+
+    fn type_error(&mut self, msg: String, span: &Span) {
+        self.error(ErrorKind::TypeError, msg, span);
+    }
+
+    fn name_error(&mut self, msg: String, span: &Span) {
+        self.error(ErrorKind::NameError, msg, span);
+    }
+
+    fn semantic_error(&mut self, msg: String, span: &Span) {
+        self.error(ErrorKind::SemanticError, msg, span);
+    }
+
+    fn check_block(&mut self, block: &'p [Stmt]) {
+        for stmt in block {
+            let kind = match &stmt.kind {
+                StmtKind::Struct { name, .. } => (name, TypeKind::Struct),
+                StmtKind::Enum { name, .. } => (name, TypeKind::Enum),
+                StmtKind::TypeAlias { name, .. } => (name, TypeKind::Alias),
+                _ => continue,
+            };
+            let (Identifier(name), kind) = kind;
+            self.scope.borrow_mut().hoist_type(name.clone(), kind);
+        }
+        let aliases = block
+            .iter()
+            .filter_map(|stmt| match &stmt.kind {
+                StmtKind::TypeAlias {
+                    name: Identifier(name),
+                    type_,
+                } => Some((name.as_str(), (expr_type_to_object_type(type_), &stmt.span))),
+                _ => None,
+            })
+            .collect::<HashMap<&str, (Type, &Span)>>();
+        self.cyclic_aliases.extend(cyclic_aliases(&aliases));
+
+        let pending = self.deferred.len();
+        for stmt in block {
+            self.check_stmt(stmt);
+        }
+        self.check_reachable(block);
+        while self.deferred.len() > pending {
+            let deferred = self.deferred.remove(pending);
+            self.check_body(deferred);
+        }
+    }
+
+    fn check_scoped_block(
+        &mut self,
+        block: &'p [Stmt],
+        kind: ScopeKind,
+        narrowed: &[(String, Type)],
+        names: Vec<(String, Var)>,
+    ) {
+        let parent_scope = Rc::clone(&self.scope);
+        let mut scope = Scope::make_from(Rc::clone(&parent_scope), kind);
+        for (name, type_) in narrowed {
+            scope.narrow(name, type_.clone());
+        }
+        for (name, var) in names {
+            scope.declare(name, Entry::Var(var));
+        }
+        self.scope = Rc::new(RefCell::new(scope));
+        self.check_block(block);
+        self.scope = parent_scope;
+    }
+
+    fn check_reachable(&mut self, block: &[Stmt]) {
+        let Some(position) = block.iter().position(exits) else {
+            return;
+        };
+        let Some(unreachable) = block.get(position + 1) else {
+            return;
+        };
+        let msg = match &block[position].kind {
+            StmtKind::Return(_) => "Unreachable code after 'return'",
+            StmtKind::Break(_) => "Unreachable code after 'break'",
+            StmtKind::Continue(_) => "Unreachable code after 'continue'",
+            _ => "Unreachable code, every branch above exits",
+        };
+        self.semantic_error(msg.to_string(), &unreachable.span);
+    }
+
+    fn check_body(&mut self, deferred: Deferred<'p>) {
+        let callable = deferred.callable;
+        let kind = ScopeKind::Function {
+            name: deferred.name.clone(),
+            returns: match callable.inferred {
+                true => None,
+                false => callable.returns(),
+            },
+        };
+        let fn_scope = Scope::make_from(deferred.scope, kind);
+        let parent_scope = std::mem::replace(&mut self.scope, Rc::new(RefCell::new(fn_scope)));
+
+        for (param, info) in deferred.params.iter().zip(&callable.params) {
+            if let (Some(default), Some(expected)) = (&param.default, &info.type_) {
+                if let Some(found) = self.check_expr(default) {
+                    self.check_passed(expected, &found, &mut HashMap::new(), &default.span);
+                }
+            }
+            let Identifier(name) = &param.name;
+            let var = Var {
+                type_: info.type_.clone(),
+                is_mut: param.is_mut,
+                declared_at: Some(param.span.clone()),
+                callable: info
+                    .type_
+                    .as_ref()
+                    .and_then(Callable::from_type)
+                    .map(Rc::new),
+            };
+            if !self
+                .scope
+                .borrow_mut()
+                .declare(name.clone(), Entry::Var(var))
+            {
+                self.name_error(format!("Param '{}' already declared", name), &param.span);
+            }
+        }
+        self.found_returns.push(vec![]);
+        self.check_block(deferred.body);
+        let found = self.found_returns.pop().unwrap_or_default();
+        self.scope = parent_scope;
+
+        let falls_off = !always_returns(deferred.body);
+        if !callable.inferred {
+            match callable.returns() {
+                Some(expected) if falls_off && !fits(&expected, &Type::Null) => self.type_error(
+                    format!(
+                        "Function '{}' must return '{}' but not every path returns",
+                        deferred.name, expected
+                    ),
+                    &deferred.span,
+                ),
+                _ => {}
+            }
+            return;
+        }
+
+        let returns = self.infer_returns(&deferred.name, found, falls_off, &deferred.span);
+        *callable.returns.borrow_mut() = returns;
+    }
+
+    fn infer_returns(
+        &mut self,
+        name: &str,
+        found: Vec<Option<Type>>,
+        falls_off: bool,
+        span: &Span,
+    ) -> Option<Type> {
+        let unknown = found.iter().any(Option::is_none);
+        let mut types = found.into_iter().flatten().collect::<Vec<Type>>();
+        if falls_off {
+            types.push(Type::Null);
+        }
+        let Some(returns) = join(types) else {
+            return (!unknown).then_some(Type::Void);
+        };
+        if let (true, Type::Optional(type_)) = (falls_off, &returns) {
+            self.type_error(
+                format!(
+                    "Function '{}' returns '{}' but not every path returns",
+                    name, type_
+                ),
+                span,
+            );
+        }
+        Some(returns)
+    }
+
+    fn check_stmt(&mut self, stmt: &'p Stmt) {
+        match &stmt.kind {
+            // a match statement can leave the loop or function it is in
+            StmtKind::Expr(Expr {
+                kind: ExprKind::Match(subject, arms),
+                ..
+            }) => {
+                self.check_match(subject, arms, false);
+            }
+            StmtKind::Expr(expr) => {
+                self.check_expr(expr);
+            }
+            StmtKind::Let(Identifier(name), type_, expr, is_mut) => {
+                self.check_let(name, type_.as_ref(), expr.as_ref(), *is_mut, &stmt.span)
+            }
+            StmtKind::Const(Identifier(name), type_, expr) => {
+                self.check_let(name, type_.as_ref(), Some(expr), false, &stmt.span)
+            }
+            StmtKind::LetTuple(names, type_, expr, is_mut) => {
+                self.check_let_tuple(names, type_.as_ref(), expr, *is_mut, &stmt.span)
+            }
+            StmtKind::Func(Identifier(name), params, body, ret_type) => {
+                if self.scope.borrow().has(name) {
+                    self.name_error(format!("'{}' is already declared", name), &stmt.span);
+                    return;
+                }
+                let callable =
+                    self.define_function(name, params, body, ret_type.as_ref(), &stmt.span);
+                let var = Var {
+                    type_: Some(callable.type_()),
+                    is_mut: false,
+                    declared_at: Some(stmt.span.clone()),
+                    callable: Some(callable),
+                };
+                self.scope
+                    .borrow_mut()
+                    .declare(name.clone(), Entry::Var(var));
+            }
+            StmtKind::Return(expr) => self.check_return(expr.as_ref(), &stmt.span),
+            StmtKind::If {
+                condition,
+                consequence,
+                alternative,
+            } => self.check_if(condition, consequence, alternative.as_ref()),
+            StmtKind::ForLoop {
+                cursor,
+                value_cursor,
+                iterable,
+                block,
+                label,
+            } => self.check_for(cursor, value_cursor.as_deref(), iterable, block, label),
+            StmtKind::While {
+                condition,
+                block,
+                label,
+            } => {
+                self.check_expr(condition);
+                self.check_scoped_block(block, ScopeKind::Loop(label.clone()), &[], vec![]);
+            }
+            StmtKind::Break(label) => self.check_loop_jump("break", label, &stmt.span),
+            StmtKind::Continue(label) => self.check_loop_jump("continue", label, &stmt.span),
+            StmtKind::Import(target) => {
+                if self.modules.access(target).is_none() {
+                    self.name_error(format!("No module named '{}'", target), &stmt.span);
+                    return;
+                }
+                self.scope
+                    .borrow_mut()
+                    .declare(target.clone(), Entry::Module(target.clone()));
+            }
+            StmtKind::Struct {
+                name: Identifier(name),
+                fields,
+                methods,
+            } => self.check_struct(name, fields, methods, &stmt.span),
+            StmtKind::Enum {
+                name: Identifier(name),
+                variants,
+            } => {
+                let variants = variants
+                    .iter()
+                    .map(|(Identifier(variant), payload)| {
+                        let payload = payload
+                            .iter()
+                            .map(|type_| self.resolve_type(type_, &stmt.span))
+                            .collect();
+                        (variant.clone(), payload)
+                    })
+                    .collect();
+                let info = EnumInfo {
+                    name: name.clone(),
+                    variants,
+                };
+                self.declare_type(name, Entry::Enum(Rc::new(info)), &stmt.span);
+            }
+            StmtKind::TypeAlias {
+                name: Identifier(name),
+                type_,
+            } => {
+                let type_ = match self.cyclic_aliases.contains(&stmt.span) {
+                    true => {
+                        self.type_error(
+                            format!("Type alias '{}' refers to itself", name),
+                            &stmt.span,
+                        );
+                        // anything fits it, its uses aren't reported again
+                        Type::TypeAnnot
+                    }
+                    false => self.resolve_type(type_, &stmt.span),
+                };
+                self.declare_type(name, Entry::Alias(type_), &stmt.span);
+            }
+        }
+    }
+
+    fn declare_type(&mut self, name: &str, entry: Entry, span: &Span) {
+        if !self.scope.borrow_mut().declare(name.to_string(), entry) {
+            self.name_error(format!("'{}' is already declared", name), span);
+        }
+    }
+
+    fn define_function(
+        &mut self,
+        name: &str,
+        params: &'p [Param],
+        body: &'p [Stmt],
+        ret_type: Option<&ExprType>,
+        span: &Span,
+    ) -> Rc<Callable> {
+        let params_info = params
+            .iter()
+            .map(|param| CallableParam {
+                name: Some(param.name.0.clone()),
+                type_: Some(self.resolve_type(&param.type_, &param.span)),
+                has_default: param.default.is_some(),
+                rest: param.rest,
+            })
+            .collect();
+        let returns = ret_type.map(|ret_type| self.resolve_type(ret_type, span));
+        let callable = Rc::new(Callable {
+            params: params_info,
+            variadic: false,
+            named: true,
+            returns: RefCell::new(returns),
+            inferred: ret_type.is_none(),
+        });
+        self.deferred.push(Deferred {
+            scope: Rc::clone(&self.scope),
+            name: name.to_string(),
+            params,
+            body,
+            callable: Rc::clone(&callable),
+            span: span.clone(),
+        });
+        callable
+    }
+
+    fn check_struct(
+        &mut self,
+        name: &str,
+        fields: &[(Identifier, ExprType)],
+        methods: &'p [Method],
+        span: &Span,
+    ) {
+        let fields = fields
+            .iter()
+            .map(|(Identifier(field), type_)| (field.clone(), self.resolve_type(type_, span)))
+            .collect();
+        let methods = methods
+            .iter()
+            .map(|method| {
+                let Identifier(method_name) = &method.name;
+                let callable = self.define_function(
+                    method_name,
+                    &method.params,
+                    &method.body,
+                    method.ret_type.as_ref(),
+                    &method.span,
+                );
+                (method_name.clone(), callable)
+            })
+            .collect();
+        let info = StructInfo { fields, methods };
+        self.declare_type(name, Entry::Struct(Rc::new(info)), span);
+    }
+
+    fn check_let(
+        &mut self,
+        name: &str,
+        expr_type: Option<&ExprType>,
+        expr: Option<&'p Expr>,
+        is_mut: bool,
+        span: &Span,
+    ) {
+        if self.scope.borrow().has(name) {
+            self.name_error(format!("'{}' already declared", name), span);
+            return;
+        }
+
+        let expected = expr_type.map(|expr_type| self.resolve_type(expr_type, span));
+        let (found, callable) = match expr {
+            Some(Expr {
+                kind: ExprKind::Func(params, body, ret_type),
+                span: func_span,
+            }) => {
+                let callable =
+                    self.define_function(name, params, body, ret_type.as_ref(), func_span);
+                (Some(callable.type_()), Some(callable))
+            }
+            Some(expr) => (self.check_expr_as(expr, expected.as_ref()), None),
+            None => (None, None),
+        };
+
+        let type_ = match expected {
+            Some(expected) => {
+                self.check_declared_type(name, &expected, expr.is_some(), span);
+                if let Some(found) = &found {
+                    if !fits(&expected, found) {
+                        self.type_error(
+                            format!(
+                                "Assigning value of type {} to variable '{}' which has type {}",
+                                found, name, expected
+                            ),
+                            span,
+                        );
+                    }
+                }
+                Some(expected)
+            }
+            None if expr.is_none() => {
+                self.type_error(
+                    format!(
+                        "Can't infer type of '{}', define it's type or initialize it",
+                        name
+                    ),
+                    span,
+                );
+                None
+            }
+            None => {
+                match &found {
+                    Some(Type::Array(None)) => {
+                        self.type_error(format!("Can't infer type of array '{}'", name), span)
+                    }
+                    Some(Type::Map(None)) => {
+                        self.type_error(format!("Can't infer type of map '{}'", name), span)
+                    }
+                    _ => {}
+                }
+                found
+            }
+        };
+
+        let callable =
+            callable.or_else(|| type_.as_ref().and_then(Callable::from_type).map(Rc::new));
+        let var = Var {
+            type_,
+            is_mut,
+            declared_at: Some(span.clone()),
+            callable,
+        };
+        self.scope
+            .borrow_mut()
+            .declare(name.to_string(), Entry::Var(var));
+    }
+
+    fn check_declared_type(&mut self, name: &str, expected: &Type, initialized: bool, span: &Span) {
+        match expected {
+            Type::Void => self.type_error("Can't declared var of type 'void'".to_string(), span),
+            Type::Array(Some(items_type)) if **items_type == Type::Void => {
+                self.type_error("Can't declared array of type 'void'".to_string(), span)
+            }
+            Type::Array(_) | Type::Map(Some(_)) | Type::Optional(_) => {}
+            _ if !initialized => self.type_error(
+                format!(
+                    "'{}' of type '{}' must be initialized, declare it as '{}?' to start with null",
+                    name, expected, expected
+                ),
+                span,
+            ),
+            _ => {}
+        }
+    }
+
+    fn check_let_tuple(
+        &mut self,
+        names: &[Binding],
+        expr_type: Option<&ExprType>,
+        expr: &'p Expr,
+        is_mut: bool,
+        span: &Span,
+    ) {
+        let found = self.check_expr(expr);
+        let type_ = match expr_type {
+            Some(expr_type) => {
+                let expected = self.resolve_type(expr_type, span);
+                if let Some(found) = &found {
+                    if !fits(&expected, found) {
+                        self.type_error(
+                            format!(
+                                "Assigning value of type {} to a tuple of type {}",
+                                found, expected
+                            ),
+                            span,
+                        );
+                    }
+                }
+                Some(expected)
+            }
+            None => found,
+        };
+
+        let mut variables = vec![];
+        self.destructure(&Binding::Tuple(names.to_vec()), type_, &mut variables, span);
+        for (name, type_) in variables {
+            if self.scope.borrow().has(&name) {
+                self.name_error(format!("'{}' already declared", name), span);
+                continue;
+            }
+            let var = Var {
+                type_,
+                is_mut,
+                declared_at: Some(span.clone()),
+                callable: None,
+            };
+            self.scope.borrow_mut().declare(name, Entry::Var(var));
+        }
+    }
+
+    fn destructure(
+        &mut self,
+        binding: &Binding,
+        type_: Option<Type>,
+        variables: &mut Vec<(String, Option<Type>)>,
+        span: &Span,
+    ) {
+        let names = match binding {
+            Binding::Name(name) if name == "_" => return,
+            Binding::Name(name) => {
+                if variables.iter().any(|(bound, _)| bound == name) {
+                    self.name_error(format!("'{}' is bound more than once", name), span);
+                    return;
+                }
+                variables.push((name.clone(), type_));
+                return;
+            }
+            Binding::Tuple(names) => names,
+        };
+
+        let types = match type_ {
+            Some(Type::Tuple(types)) if types.len() == names.len() => {
+                types.into_iter().map(Some).collect()
+            }
+            Some(type_) if !is_vague(&type_) => {
+                self.type_error(
+                    format!(
+                        "Can't destructure value of type '{}' into {} names",
+                        type_,
+                        names.len()
+                    ),
+                    span,
+                );
+                vec![None; names.len()]
+            }
+            _ => vec![None; names.len()],
+        };
+        for (name, type_) in names.iter().zip(types) {
+            self.destructure(name, type_, variables, span);
+        }
+    }
+
+    fn check_return(&mut self, expr: Option<&'p Expr>, span: &Span) {
+        let found = match expr {
+            Some(expr) => self.check_expr(expr),
+            None => Some(Type::Null),
+        };
+        let function = self.scope.borrow().function();
+        let Some((name, returns)) = function else {
+            self.semantic_error("'return' outside of function".to_string(), span);
+            return;
+        };
+        let Some(expected) = returns else {
+            if let Some(found_returns) = self.found_returns.last_mut() {
+                found_returns.push(found);
+            }
+            return;
+        };
+        if let Some(found) = found {
+            if !fits(&expected, &found) {
+                self.type_error(
+                    format!(
+                        "Function '{}' must return '{}' but found '{}'",
+                        name, expected, found
+                    ),
+                    span,
+                );
+            }
+        }
+    }
+
+    fn check_if(
+        &mut self,
+        condition: &'p Expr,
+        consequence: &'p [Stmt],
+        alternative: Option<&'p Else>,
+    ) {
+        self.check_expr(condition);
+
+        let narrowed = self.narrowed_types(condition, true);
+        self.check_scoped_block(consequence, ScopeKind::IfElse, &narrowed, vec![]);
+
+        let narrowed = self.narrowed_types(condition, false);
+        match alternative {
+            Some(Else::Block(block)) => {
+                self.check_scoped_block(block, ScopeKind::IfElse, &narrowed, vec![])
+            }
+            Some(Else::If(next)) => self.check_scoped_block(
+                std::slice::from_ref(next),
+                ScopeKind::IfElse,
+                &narrowed,
+                vec![],
+            ),
+            None => {}
+        }
+
+        // a branch that always leaves tells the rest of the block the
+        // condition went the other way
+        let holds = match alternative {
+            None if always_exits(consequence) => false,
+            Some(Else::Block(block)) if always_exits(block) && !always_exits(consequence) => true,
+            _ => return,
+        };
+        for (name, type_) in self.narrowed_types(condition, holds) {
+            self.scope.borrow_mut().narrow(&name, type_);
+        }
+    }
+
+    fn narrowed_types(&mut self, condition: &Expr, holds: bool) -> Vec<(String, Type)> {
+        let mut narrowed = vec![];
+        for (name, narrowing) in narrowings(condition, holds) {
+            let Some(Entry::Var(var)) = self.scope.borrow().resolve(&name) else {
+                continue;
+            };
+            let declared_type = var.type_.unwrap_or(Type::TypeAnnot);
+            let type_ = declared_type.narrow(&narrowing, |type_| {
+                self.resolve_type(type_, &condition.span)
+            });
+            if let Some(type_) = type_ {
+                narrowed.push((name, type_));
+            }
+        }
+        narrowed
+    }
+
+    fn check_for(
+        &mut self,
+        cursor: &Cursor,
+        value_cursor: Option<&Cursor>,
+        iterable: &'p Expr,
+        block: &'p [Stmt],
+        label: &Option<String>,
+    ) {
+        let iterable_type = self.check_expr(iterable);
+        let (cursor_type, value_type) = match (&iterable_type, value_cursor) {
+            (Some(Type::Map(Some((key_type, value_type)))), _) => {
+                (Some(*key_type.clone()), Some(*value_type.clone()))
+            }
+            (Some(Type::Array(items_type)), Some(_)) => {
+                (Some(Type::Int), items_type.as_deref().cloned())
+            }
+            (Some(Type::Array(items_type)), None) => (items_type.as_deref().cloned(), None),
+            (Some(type_), Some(_)) if !is_vague(type_) && !matches!(type_, Type::Map(_)) => {
+                self.type_error(
+                    format!(
+                        "Only maps and arrays can be iterated with two cursors but found '{}'",
+                        type_
+                    ),
+                    &iterable.span,
+                );
+                (None, None)
+            }
+            (Some(Type::Range), _) => (Some(Type::Int), None),
+            (Some(type_), _) if !is_vague(type_) && !matches!(type_, Type::Map(_)) => {
+                self.type_error(
+                    format!(
+                        "Type '{}' is not iterable, for loops go through ranges, arrays and maps",
+                        type_
+                    ),
+                    &iterable.span,
+                );
+                (None, None)
+            }
+            _ => (None, None),
+        };
+
+        let mut cursors = vec![];
+        self.bind_cursor(cursor, cursor_type, &mut cursors, &iterable.span);
+        if let Some(value_cursor) = value_cursor {
+            self.bind_cursor(value_cursor, value_type, &mut cursors, &iterable.span);
+        }
+        let names = cursors
+            .into_iter()
+            .map(|(name, type_, declared_at)| {
+                let var = Var {
+                    type_,
+                    is_mut: false,
+                    declared_at: Some(declared_at),
+                    callable: None,
+                };
+                (name, var)
+            })
+            .collect();
+        self.check_scoped_block(block, ScopeKind::Loop(label.clone()), &[], names);
+    }
+
+    fn bind_cursor(
+        &mut self,
+        cursor: &Cursor,
+        items_type: Option<Type>,
+        cursors: &mut Vec<(String, Option<Type>, Span)>,
+        span: &Span,
+    ) {
+        let type_ = match &cursor.type_ {
+            Some(annotation) => {
+                let expected = self.resolve_type(annotation, span);
+                if let Some(found) = items_type.filter(|found| !fits(&expected, found)) {
+                    self.type_error(
+                        format!(
+                            "Cursor of type '{}' can't take items of type '{}'",
+                            expected, found
+                        ),
+                        span,
+                    );
+                }
+                Some(expected)
+            }
+            None => items_type,
+        };
+        let mut variables = vec![];
+        self.destructure(&cursor.binding, type_, &mut variables, span);
+        for (name, type_) in variables {
+            if cursors.iter().any(|(bound, ..)| *bound == name) {
+                self.name_error(format!("'{}' is bound more than once", name), span);
+                continue;
+            }
+            cursors.push((name, type_, cursor.span.clone()));
+        }
+    }
+
+    fn check_loop_jump(&mut self, keyword: &str, label: &Option<String>, span: &Span) {
+        if self.scope.borrow().in_loop(label.as_deref()) {
+            return;
+        }
+        let msg = match label {
+            Some(label) => format!("No loop labelled '{}' around '{}'", label, keyword),
+            None => format!("'{}' outside of loop", keyword),
+        };
+        self.semantic_error(msg, span);
+    }
+
+    fn resolve_type(&mut self, expr_type: &ExprType, span: &Span) -> Type {
+        let type_ = expr_type_to_object_type(expr_type);
+        let scope = Rc::clone(&self.scope);
+        let scope = scope.borrow();
+
+        let named = RefCell::new(vec![]);
+        type_.replace(&|type_| {
+            if let Type::UserDefined(name) = type_ {
+                named.borrow_mut().push(name.clone());
+            }
+            None
+        });
+        for name in named.into_inner() {
+            let declared = scope.type_kind(&name).is_some()
+                || matches!(
+                    scope.resolve(&name),
+                    Some(Entry::Struct(_) | Entry::Enum(_) | Entry::Alias(_))
+                );
+            if !declared {
+                self.name_error(format!("Type '{}' is not declared", name), span);
+            }
+        }
+
+        type_.replace(&|type_| {
+            let Type::UserDefined(name) = type_ else {
+                return None;
+            };
+            match scope.resolve(name) {
+                Some(Entry::Alias(type_)) => Some(type_),
+                Some(Entry::Enum(_)) => Some(Type::Enum(name.clone())),
+                _ => match scope.type_kind(name) {
+                    Some(TypeKind::Enum) => Some(Type::Enum(name.clone())),
+                    _ => None,
+                },
+            }
+        })
+    }
+}
+
+fn member_entry(member: Member) -> Entry {
+    let var = match member {
+        Member::Value(type_) => Var {
+            type_: Some(type_),
+            is_mut: false,
+            declared_at: None,
+            callable: None,
+        },
+        Member::Function(signature) => Var {
+            type_: Some(Type::Function(None)),
+            is_mut: false,
+            declared_at: None,
+            callable: Some(Rc::new(Callable::from_signature(&signature))),
+        },
+    };
+    Entry::Var(var)
+}
+
+fn fits(expected: &Type, found: &Type) -> bool {
+    // an annotation naming a cyclic alias, reported where it's declared
+    if (is_vague(found) && !is_generic_function(found)) || *expected == Type::TypeAnnot {
+        return true;
+    }
+    expected.infer(&found.instantiate(), &mut HashMap::new())
+        || matches!(
+            (expected, found),
+            (Type::Void, Type::Null) | (Type::Null, Type::Void)
+        )
+}
+
+fn cyclic_aliases(aliases: &HashMap<&str, (Type, &Span)>) -> Vec<Span> {
+    let refers_to = |type_: &Type| {
+        let names = RefCell::new(vec![]);
+        type_.replace(&|type_| {
+            if let Type::UserDefined(name) = type_ {
+                names.borrow_mut().push(name.clone());
+            }
+            None
+        });
+        names.into_inner()
+    };
+    let mut cyclic = vec![];
+    for (name, (type_, span)) in aliases {
+        let mut seen: Vec<String> = vec![];
+        let mut pending = refers_to(type_);
+        while let Some(next) = pending.pop() {
+            if next == *name {
+                cyclic.push((*span).clone());
+                break;
+            }
+            if seen.contains(&next) {
+                continue;
+            }
+            if let Some((type_, _)) = aliases.get(next.as_str()) {
+                pending.extend(refers_to(type_));
+            }
+            seen.push(next);
+        }
+    }
+    cyclic
+}
+
+fn is_generic_function(type_: &Type) -> bool {
+    matches!(type_, Type::Function(Some(_))) && type_.is_generic()
+}
+
+fn is_vague(type_: &Type) -> bool {
+    match type_ {
+        Type::TypeParam(_) | Type::Function(None) | Type::TypeAnnot => true,
+        Type::Array(Some(inner)) | Type::Optional(inner) => is_vague(inner),
+        Type::Map(Some((key_type, value_type))) => is_vague(key_type) || is_vague(value_type),
+        Type::Tuple(items) | Type::Union(items) => items.iter().any(is_vague),
+        Type::Function(Some((params, return_type))) => {
+            params.iter().any(is_vague) || is_vague(return_type)
+        }
+        _ => false,
+    }
+}
+
+fn join(types: Vec<Type>) -> Option<Type> {
+    let nullable = types
+        .iter()
+        .any(|type_| matches!(type_, Type::Null | Type::Void));
+    let mut members: Vec<Type> = vec![];
+    for type_ in types {
+        if !matches!(type_, Type::Null | Type::Void)
+            && !members.iter().any(|member| fits(member, &type_))
+        {
+            members.push(type_);
+        }
+    }
+    let joined = match members.len() {
+        0 => return None,
+        1 => members.remove(0),
+        _ => Type::Union(members),
+    };
+    match nullable {
+        true => Some(Type::Optional(Box::new(joined))),
+        false => Some(joined),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{check, CheckError};
+    use crate::frontend::lexer::Lexer;
+    use crate::frontend::parser::Parser;
+
+    fn check_source(input: &str, file: &str) -> Vec<CheckError> {
+        let chars = input.chars().collect::<Vec<char>>();
+        let mut l = Lexer::new(&chars, file);
+        let mut p = Parser::new(&mut l);
+        let program = p.parse();
+        assert!(!p.has_errors());
+        check(&program)
+    }
+
+    fn located_errors(errors: &[CheckError], input: &str) -> Vec<String> {
+        errors
+            .iter()
+            .map(|err| {
+                let location = err.report(input).lines().nth(1).unwrap().trim().to_string();
+                format!("{} {}", location, err)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_reports_every_type_error() {
+        let input = r#"
+import io
+
+define add(a: int, b: int): int { return a + b }
+define greet(name: string): string { return 5 }
+
+let total: string = add(1, 2)
+add(1, "two")
+io.shout("hi")
+print(total)
+
+define later(): int { return count }
+let count = 0
+"#;
+        let errors = check_source(input, "types.fl");
+
+        assert_eq!(
+            vec![
+                "--> types.fl:5:38 [Type Error] Function 'greet' must return 'string' but found 'int'",
+                "--> types.fl:7:1 [Type Error] Assigning value of type int to variable 'total' which has type string",
+                "--> types.fl:8:8 [Type Error] Passing argument of type 'string' to parameter of type 'int'",
+                "--> types.fl:9:4 [Name error] No method 'shout' associated with [Module] io",
+                "--> types.fl:10:1 [Name error] 'print' is not declared",
+            ],
+            located_errors(&errors, input)
+        );
+    }
+
+    #[test]
+    fn test_infers_return_types_and_reports_missing_returns() {
+        let input = r#"
+define double(x: int) { return x * 2 }
+define find(xs: Array<int>, x: int) {
+    for i in range(0, xs.length()) {
+        if xs[i] == x { return i }
+    }
+    return null
+}
+define half(x: int): int {
+    if x > 0 { return x / 2 }
+}
+define early(x: int): int {
+    return x
+    double(x)
+}
+let d: string = double(2)
+let i: int? = find([1, 2], 2)
+"#;
+        let errors = check_source(input, "returns.fl");
+
+        assert_eq!(
+            vec![
+                "--> returns.fl:9:1 [Type Error] Function 'half' must return 'int' but not every path returns",
+                "--> returns.fl:14:5 [Semantic Error] Unreachable code after 'return'",
+                "--> returns.fl:16:1 [Type Error] Assigning value of type int to variable 'd' which has type string",
+            ],
+            located_errors(&errors, input)
+        );
+    }
+
+    #[test]
+    fn test_calls_function_bound_by_cursor() {
+        let input = r#"
+define one(): int { return 1 }
+let fs: Array<() -> int> = [one]
+for f in fs {
+    let n: int = f()
+    f(n)
+}
+"#;
+        let errors = check_source(input, "cursor.fl");
+
+        assert_eq!(
+            vec!["--> cursor.fl:6:5 [Type Error] Function 'f' expects 0 args but provided 1"],
+            located_errors(&errors, input)
+        );
+    }
+
+    #[test]
+    fn test_calls_function_bound_by_destructuring() {
+        let input = r#"
+define one(): int { return 1 }
+let (a, b) = (one, 2)
+let n: int = a() + b
+"#;
+        let errors = check_source(input, "tuple.fl");
+        assert!(errors.is_empty());
+    }
+
+    #[test]
+    fn test_calls_function_bound_by_pattern() {
+        let input = r#"
+enum Op { Fn((int) -> int), Nothing }
+define inc(x: int): int { return x + 1 }
+let o = Op.Fn(inc)
+match o {
+    Op.Fn(f) => f("three"),
+    _ => 0,
+}
+"#;
+        let errors = check_source(input, "pattern.fl");
+
+        assert_eq!(
+            vec!["--> pattern.fl:6:19 [Type Error] Passing argument of type 'string' to parameter of type 'int'"],
+            located_errors(&errors, input)
+        );
+    }
+
+    #[test]
+    fn test_match_block_arms_in_value_position() {
+        let input = r#"
+import io
+for i in range(0, 5) {
+    let r = match i { 2 => { break }, _ => i }
+    match i { 3 => { continue }, _ => { io.puts(i) } }
+}
+let a: int = match 0 { 0 => { io.puts("x") }, _ => 2 }
+"#;
+        let errors = check_source(input, "arms.fl");
+
+        assert_eq!(
+            vec![
+                "--> arms.fl:4:30 [Semantic Error] 'break' can't leave a match used as a value",
+                "--> arms.fl:7:1 [Type Error] Assigning value of type int? to variable 'a' which has type int",
+            ],
+            located_errors(&errors, input)
+        );
+    }
+
+    #[test]
+    fn test_array_items_fit_the_expected_type() {
+        let input = r#"
+define f(): int { return 1 }
+define h(): string { return "a" }
+let ks: Array<function> = [f, h]
+let mut hs: Array<function> = []
+hs = [...hs, define(): int { return 2 }, h]
+let ns: Array<int> = [1, "a"]
+let fs = [f, h]
+"#;
+        let errors = check_source(input, "items.fl");
+
+        assert_eq!(
+            vec![
+                "--> items.fl:7:22 [Type Error] Array item's type mismatch",
+                "--> items.fl:8:10 [Type Error] Array item's type mismatch",
+            ],
+            located_errors(&errors, input)
+        );
+    }
+
+    #[test]
+    fn test_literals_fit_unions_and_optionals() {
+        let input = r#"
+let xs: Array<int | string> = [1, "a"]
+let m: Map<string, int?> = {"a": null, "b": 1}
+let mut n: Map<string, int?> = {}
+n = {"c": null}
+let bad: Map<string, int> = {"a": null}
+"#;
+        let errors = check_source(input, "literals.fl");
+
+        assert_eq!(
+            vec!["--> literals.fl:6:29 [Type Error] Map value's type mismatch"],
+            located_errors(&errors, input)
+        );
+    }
+
+    #[test]
+    fn test_narrows_the_rhs_of_logical_operators() {
+        let input = r#"
+let x: int? = 3
+let s: int | string = "ab"
+let a = x != null && x > 2
+let b = x == null || x > 2
+let c = s is string && s.length() == 2
+let d = x != null || x > 2
+"#;
+        let errors = check_source(input, "logical.fl");
+
+        assert_eq!(
+            vec!["--> logical.fl:7:22 [Type Error] 'x' of type 'int?' may be null, check it with 'if x != null' or use '??'"],
+            located_errors(&errors, input)
+        );
+    }
+
+    #[test]
+    fn test_compares_compound_values_but_not_functions() {
+        let input = r#"
+define f(): int { return 1 }
+let a = (1, 2) == (1, 2)
+let b = [1] != [2]
+let c = f == f
+"#;
+        let errors = check_source(input, "equality.fl");
+
+        assert_eq!(
+            vec!["--> equality.fl:5:9 [Type Error] '==' operation not allowed between types () -> int and () -> int"],
+            located_errors(&errors, input)
+        );
+    }
+
+    #[test]
+    fn test_only_arrays_need_mut_to_change_items() {
+        let input = r#"
+struct P { x: int }
+let m: Map<string, int> = {"a": 1}
+m["b"] = 2
+m.set("c", 3)
+let p = P { x: 1 }
+p.x = 3
+let xs = [1, 2]
+xs[0] = 3
+"#;
+        let errors = check_source(input, "mut.fl");
+
+        assert_eq!(
+            vec!["--> mut.fl:9:1 [Name error] 'xs' is not assignable, it's declared immutable at mut.fl:8:1"],
+            located_errors(&errors, input)
+        );
+    }
+
+    #[test]
+    fn test_not_takes_booleans_only() {
+        let input = r#"
+let a = not (1 < 2)
+let b = not 1
+let c = !null
+"#;
+        let errors = check_source(input, "not.fl");
+
+        assert_eq!(
+            vec![
+                "--> not.fl:3:9 [Type Error] '!' prefix expects type boolean but found int",
+                "--> not.fl:4:9 [Type Error] '!' prefix expects type boolean but found null",
+            ],
+            located_errors(&errors, input)
+        );
+    }
+
+    #[test]
+    fn test_reports_cyclic_type_aliases() {
+        let input = r#"
+type Id = int | string
+type A = A | int
+type B = C
+type C = Array<B>
+let x: B = 1
+let id: Id = 2
+"#;
+        let errors = check_source(input, "aliases.fl");
+
+        assert_eq!(
+            vec![
+                "--> aliases.fl:3:1 [Type Error] Type alias 'A' refers to itself",
+                "--> aliases.fl:4:1 [Type Error] Type alias 'B' refers to itself",
+                "--> aliases.fl:5:1 [Type Error] Type alias 'C' refers to itself",
+            ],
+            located_errors(&errors, input)
+        );
+    }
+
+    #[test]
+    fn test_checks_generic_functions_passed_as_values() {
+        let input = r#"
+define id<T>(x: T): T { return x }
+define apply(f: (int) -> int, x: int): int { return f(x) }
+define shout(f: (int) -> string): string { return f(1) }
+let n: int = apply(id, 3)
+let same = id(id)
+let g: (string) -> string = id
+shout(id)
+"#;
+        let errors = check_source(input, "generic.fl");
+
+        assert_eq!(
+            vec![
+                "--> generic.fl:8:7 [Type Error] Passing argument of type '(T) -> T' to parameter of type '(int) -> string'",
+            ],
+            located_errors(&errors, input)
+        );
+    }
+
+    #[test]
+    fn test_types_for_cursors_from_the_iterable() {
+        let input = r#"
+let mut fruit: string = ""
+for f in ["Apple"] { fruit = f }
+for i, x in ["a", "b"] {
+    let n: int = i
+    let s: string = x
+    let wrong: string = i
+}
+for f: int in ["Apple"] { }
+"#;
+        let errors = check_source(input, "cursors.fl");
+
+        assert_eq!(
+            vec![
+                "--> cursors.fl:7:5 [Type Error] Assigning value of type int to variable 'wrong' which has type string",
+                "--> cursors.fl:9:15 [Type Error] Cursor of type 'int' can't take items of type 'string'",
+            ],
+            located_errors(&errors, input)
+        );
+    }
+
+    #[test]
+    fn test_reports_where_cursors_and_functions_are_declared() {
+        let input = r#"
+define f() { }
+f = 3
+for i in range(0, 2) { i = 3 }
+for k, (a, b) in [(1, 2)] { b = 1 }
+"#;
+        let errors = check_source(input, "declared.fl");
+
+        assert_eq!(
+            vec![
+                "--> declared.fl:3:1 [Name error] 'f' is not assignable, it's declared immutable at declared.fl:2:1",
+                "--> declared.fl:4:24 [Name error] 'i' is not assignable, it's declared immutable at declared.fl:4:5",
+                "--> declared.fl:5:29 [Name error] 'b' is not assignable, it's declared immutable at declared.fl:5:8",
+            ],
+            located_errors(&errors, input)
+        );
+    }
+
+    #[test]
+    fn test_types_tuple_items_taken_by_a_variable_index() {
+        let input = r#"
+let t = (1, "a")
+let i = 1
+let s: string = t[i]
+let u: int | string = t[i]
+let n: int = (1, 2)[i]
+"#;
+        let errors = check_source(input, "tuple.fl");
+
+        assert_eq!(
+            vec![
+                "--> tuple.fl:4:1 [Type Error] Assigning value of type int | string to variable 's' which has type string",
+            ],
+            located_errors(&errors, input)
+        );
+    }
+}
@@ -0,0 +1,234 @@
+use std::{cell::RefCell, collections::HashMap, rc::Rc};
+
+use crate::frontend::span::Span;
+use crate::runtime::type_system::Type;
+use crate::stdlib::Signature;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ScopeKind {
+    Global,
+    Function { name: String, returns: Option<Type> },
+    Loop(Option<String>),
+    IfElse,
+}
+
+#[derive(Debug, Clone)]
+pub struct CallableParam {
+    pub name: Option<String>,
+    pub type_: Option<Type>,
+    pub has_default: bool,
+    pub rest: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct Callable {
+    pub params: Vec<CallableParam>,
+    pub variadic: bool,
+    pub named: bool,
+    pub returns: RefCell<Option<Type>>,
+    pub inferred: bool,
+}
+
+impl Callable {
+    pub fn from_signature(signature: &Signature) -> Self {
+        let required = signature.params.len() - signature.optional;
+        let params = signature
+            .params
+            .iter()
+            .enumerate()
+            .map(|(position, (name, type_))| CallableParam {
+                name: Some(name.to_string()),
+                type_: type_.clone(),
+                has_default: position >= required,
+                rest: false,
+            })
+            .collect();
+        Self {
+            params,
+            variadic: signature.variadic,
+            named: signature.named,
+            returns: RefCell::new(Some(signature.returns.clone())),
+            inferred: false,
+        }
+    }
+
+    pub fn from_type(type_: &Type) -> Option<Self> {
+        let Type::Function(Some((params, returns))) = type_ else {
+            return None;
+        };
+        let params = params
+            .iter()
+            .map(|type_| CallableParam {
+                name: None,
+                type_: Some(type_.clone()),
+                has_default: false,
+                rest: false,
+            })
+            .collect();
+        Some(Self {
+            params,
+            variadic: false,
+            named: false,
+            returns: RefCell::new(Some(*returns.clone())),
+            inferred: false,
+        })
+    }
+
+    pub fn returns(&self) -> Option<Type> {
+        self.returns.borrow().clone()
+    }
+
+    pub fn type_(&self) -> Type {
+        let params = self
+            .params
+            .iter()
+            .map(|param| param.type_.clone().unwrap_or(Type::Function(None)))
+            .collect();
+        match self.returns() {
+            Some(returns) if !self.variadic => Type::Function(Some((params, Box::new(returns)))),
+            _ => Type::Function(None),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Var {
+    pub type_: Option<Type>,
+    pub is_mut: bool,
+    pub declared_at: Option<Span>,
+    pub callable: Option<Rc<Callable>>,
+}
+
+#[derive(Debug)]
+pub struct StructInfo {
+    pub fields: Vec<(String, Type)>,
+    pub methods: HashMap<String, Rc<Callable>>,
+}
+
+#[derive(Debug)]
+pub struct EnumInfo {
+    pub name: String,
+    pub variants: Vec<(String, Vec<Type>)>,
+}
+
+#[derive(Debug, Clone)]
+pub enum Entry {
+    Var(Var),
+    Struct(Rc<StructInfo>),
+    Enum(Rc<EnumInfo>),
+    Alias(Type),
+    Module(String),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TypeKind {
+    Struct,
+    Enum,
+    Alias,
+}
+
+#[derive(Debug)]
+pub struct Scope {
+    pub kind: ScopeKind,
+    entries: HashMap<String, Entry>,
+    narrowed: HashMap<String, Type>,
+    types: HashMap<String, TypeKind>,
+    parent: Option<Rc<RefCell<Scope>>>,
+}
+
+impl Scope {
+    pub fn global(entries: HashMap<String, Entry>) -> Self {
+        Self {
+            kind: ScopeKind::Global,
+            entries,
+            narrowed: HashMap::new(),
+            types: HashMap::new(),
+            parent: None,
+        }
+    }
+
+    pub fn make_from(parent: Rc<RefCell<Scope>>, kind: ScopeKind) -> Self {
+        Self {
+            kind,
+            parent: Some(parent),
+            ..Self::global(HashMap::new())
+        }
+    }
+
+    pub fn has(&self, name: &str) -> bool {
+        self.entries.contains_key(name)
+    }
+
+    pub fn declare(&mut self, name: String, entry: Entry) -> bool {
+        if self.entries.contains_key(&name) {
+            return false;
+        }
+        self.entries.insert(name, entry);
+        true
+    }
+
+    pub fn resolve(&self, name: &str) -> Option<Entry> {
+        let resolved = match self.entries.get(name) {
+            Some(entry) => Some(entry.clone()),
+            None => self.parent.as_ref()?.borrow().resolve(name),
+        };
+        match (self.narrowed.get(name), resolved) {
+            (Some(type_), Some(Entry::Var(var))) => Some(Entry::Var(Var {
+                type_: Some(type_.clone()),
+                ..var
+            })),
+            (_, resolved) => resolved,
+        }
+    }
+
+    pub fn declared_type(&self, name: &str) -> Option<Type> {
+        match self.entries.get(name) {
+            Some(Entry::Var(var)) => var.type_.clone(),
+            Some(_) => None,
+            None => self.parent.as_ref()?.borrow().declared_type(name),
+        }
+    }
+
+    pub fn narrow(&mut self, name: &str, type_: Type) {
+        self.narrowed.insert(name.to_string(), type_);
+    }
+
+    pub fn widen(&mut self, name: &str) {
+        self.narrowed.remove(name);
+        if self.entries.contains_key(name) {
+            return;
+        }
+        if let Some(ref p) = self.parent {
+            p.borrow_mut().widen(name);
+        }
+    }
+
+    pub fn hoist_type(&mut self, name: String, kind: TypeKind) {
+        self.types.insert(name, kind);
+    }
+
+    pub fn type_kind(&self, name: &str) -> Option<TypeKind> {
+        match self.types.get(name) {
+            Some(kind) => Some(*kind),
+            None => self.parent.as_ref()?.borrow().type_kind(name),
+        }
+    }
+
+    pub fn in_loop(&self, label: Option<&str>) -> bool {
+        match &self.kind {
+            ScopeKind::Loop(found) if label.is_none() || found.as_deref() == label => true,
+            ScopeKind::Function { .. } => false,
+            _ => match self.parent {
+                Some(ref p) => p.borrow().in_loop(label),
+                None => false,
+            },
+        }
+    }
+
+    pub fn function(&self) -> Option<(String, Option<Type>)> {
+        match &self.kind {
+            ScopeKind::Function { name, returns } => Some((name.clone(), returns.clone())),
+            _ => self.parent.as_ref()?.borrow().function(),
+        }
+    }
+}